@@ -0,0 +1,125 @@
+//! Compares firing lasers by creating and deleting an entity per shot
+//! with re-using the entities in the game's `EntityPool`.
+//!
+//! Each run fires a few shots a frame, for ten seconds' worth of frames,
+//! and retires each shot once it is half a second old, as lasers do when they
+//! leave the arena. Before timing anything, the benchmark prints how many
+//! entities each approach had to allocate.
+//!
+//! Run with `cargo bench --features "vulkan"` (or your graphics backend).
+
+use amethyst::core::transform::Transform;
+use amethyst::core::Hidden;
+use amethyst::ecs::prelude::{
+    Component, DenseVecStorage, Entities, Join, LazyUpdate, Read, ReadStorage, World, WorldExt,
+    Write, WriteStorage,
+};
+use criterion::{criterion_group, criterion_main, Criterion};
+use space_shooter::components::Inactive;
+use space_shooter::resources::EntityPool;
+
+/// How many frames each run lasts (ten seconds at 60 frames a second)
+const FRAMES: u32 = 600;
+/// How many shots are fired each frame
+const SHOTS_PER_FRAME: u32 = 4;
+/// How many frames each shot lasts
+const SHOT_LIFETIME: u32 = 30;
+
+/// A stand-in for the laser component
+struct Shot {
+    age: u32,
+}
+
+impl Component for Shot {
+    type Storage = DenseVecStorage<Self>;
+}
+
+/// How the shots are brought into the world and taken out again
+#[derive(Clone, Copy)]
+enum Allocation {
+    /// A new entity for each shot, deleted once the shot is finished
+    CreateDelete,
+    /// Entities taken from the pool, and put back once the shot is finished
+    Pooled,
+}
+
+fn new_world() -> World {
+    let mut world = World::new();
+    world.register::<Shot>();
+    world.register::<Transform>();
+    world.register::<Hidden>();
+    world.register::<Inactive>();
+    world.insert(EntityPool::<Shot>::default());
+    world
+}
+
+/// Runs the shots through every frame, the way the ship and laser systems do,
+/// and returns the number of entities that had to be created
+fn run_frames(world: &mut World, allocation: Allocation) -> usize {
+    let mut created = 0;
+    for _frame in 0..FRAMES {
+        {
+            let (entities, mut shots, inactives, lazy_update, mut pool): (
+                Entities,
+                WriteStorage<Shot>,
+                ReadStorage<Inactive>,
+                Read<LazyUpdate>,
+                Write<EntityPool<Shot>>,
+            ) = world.system_data();
+            for (entity, shot, _active) in (&*entities, &mut shots, !&inactives).join() {
+                shot.age += 1;
+                if shot.age > SHOT_LIFETIME {
+                    match allocation {
+                        Allocation::CreateDelete => {
+                            let _result = entities.delete(entity);
+                        }
+                        Allocation::Pooled => pool.release(entity, &lazy_update),
+                    }
+                }
+            }
+            for _shot in 0..SHOTS_PER_FRAME {
+                let entity = match allocation {
+                    Allocation::CreateDelete => {
+                        created += 1;
+                        entities.create()
+                    }
+                    Allocation::Pooled => pool.acquire(&entities, &lazy_update),
+                };
+                lazy_update.insert(entity, Shot { age: 0 });
+                lazy_update.insert(entity, Transform::default());
+            }
+        }
+        world.maintain();
+    }
+    match allocation {
+        Allocation::CreateDelete => created,
+        Allocation::Pooled => world.read_resource::<EntityPool<Shot>>().created,
+    }
+}
+
+fn entity_pool_benchmark(c: &mut Criterion) {
+    let shots = FRAMES * SHOTS_PER_FRAME;
+    let created = run_frames(&mut new_world(), Allocation::CreateDelete);
+    println!("create/delete: {} shots, {} entities created", shots, created);
+    let mut world = new_world();
+    let created = run_frames(&mut world, Allocation::Pooled);
+    let pool = world.read_resource::<EntityPool<Shot>>();
+    println!(
+        "pooled:        {} shots, {} entities created ({} re-used, {} in use, {} resting)",
+        shots,
+        created,
+        pool.reused,
+        pool.in_use(),
+        pool.resting()
+    );
+
+    c.bench_function("lasers created and deleted", |b| {
+        b.iter(|| run_frames(&mut new_world(), Allocation::CreateDelete))
+    });
+    c.bench_function("lasers pooled", |b| {
+        b.iter(|| run_frames(&mut new_world(), Allocation::Pooled))
+    });
+}
+
+criterion_group!(benches, entity_pool_benchmark);
+criterion_main!(benches);
@@ -0,0 +1,100 @@
+//! The achievements the players can unlock
+//!
+//! Like the [ship roster](struct.ShipRoster.html), the achievements are loaded
+//! from a RON file ("achievements.ron" in resources), so the game designer can
+//! add achievements and change their conditions without having to recompile the code.
+//!
+//! Each achievement has a [condition](enum.AchievementCondition.html), which the
+//! [AchievementSystem](systems/struct.AchievementSystem.html) checks every frame
+//! against the game's [statistics](resources/struct.Telemetry.html) and play state.
+//! Once unlocked, an achievement stays unlocked: the unlocked achievements are saved in the
+//! player's data directory (see [UnlockedAchievements](resources/struct.UnlockedAchievements.html)).
+
+use amethyst::config::Config;
+use lazy_static::lazy_static;
+use serde_derive::{Deserialize, Serialize};
+
+use crate::resources::{GameClock, PlayState, Telemetry};
+
+/// What the players need to do in a game to unlock an achievement
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub enum AchievementCondition {
+    /// Destroy at least this many asteroids in one game
+    AsteroidsDestroyed(u32),
+    /// Play for at least this long (sec) without losing a life,
+    /// from the start of the game or the last life lost
+    SurviveWithoutLosingLife(f32),
+    /// Finish a wave with at least this accuracy (0 to 1),
+    /// having fired at least the minimum number of shots during the wave
+    WaveAccuracy {
+        /// The share of shots that must hit an asteroid
+        accuracy: f32,
+        /// The fewest shots that count (so a wave with one lucky shot doesn't)
+        min_shots: u32,
+    },
+    /// Reach at least this score (any player)
+    Score(u32),
+    /// Reach this wave
+    ReachWave(u32),
+}
+
+impl AchievementCondition {
+    /// Whether or not the game so far meets the condition
+    pub fn is_met(
+        &self,
+        telemetry: &Telemetry,
+        play_state: &PlayState,
+        game_clock: &GameClock,
+    ) -> bool {
+        match *self {
+            AchievementCondition::AsteroidsDestroyed(count) => telemetry.asteroids_destroyed >= count,
+            AchievementCondition::SurviveWithoutLosingLife(seconds) => {
+                game_clock.elapsed_seconds - telemetry.last_life_lost_at >= seconds
+            }
+            AchievementCondition::WaveAccuracy {
+                accuracy,
+                min_shots,
+            } => telemetry.last_completed_wave.map_or(false, |wave| {
+                wave.shots_fired >= min_shots.max(1) && wave.accuracy() >= accuracy
+            }),
+            AchievementCondition::Score(score) => play_state.best_score() >= score,
+            AchievementCondition::ReachWave(wave) => play_state.level >= wave,
+        }
+    }
+}
+
+/// One of the achievements
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct Achievement {
+    /// The name the achievement is saved under when unlocked; don't change it once players have it
+    pub id: String,
+    /// The message ID of the name shown to the players (see [localisation](struct.Catalogues.html))
+    pub name: String,
+    /// The message ID of what the players need to do, in words
+    pub description: String,
+    /// What the players need to do, for the game to check
+    pub condition: AchievementCondition,
+}
+
+/// The list of achievements
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct AchievementList {
+    /// How long the pop-up announcing an unlocked achievement stays up (sec)
+    pub toast_duration: f32,
+    /// The achievements, in the order they are listed
+    pub achievements: Vec<Achievement>,
+}
+
+lazy_static! {
+    /// The actual [list of achievements](struct.AchievementList.html).
+    ///
+    /// The achievements are automatically loaded on startup
+    /// from the file "achievements.ron" in resources.
+    pub static ref ACHIEVEMENTS: AchievementList = {
+        let achievements_path = format!(
+            "{}/resources/achievements.ron",
+            env!("CARGO_MANIFEST_DIR")
+        );
+        AchievementList::load(&achievements_path).unwrap()
+    };
+}
@@ -0,0 +1,117 @@
+use amethyst::ecs::prelude::Entity;
+use amethyst::input::{is_close_requested, is_key_down};
+use amethyst::prelude::*;
+use amethyst::ui::UiText;
+use amethyst::winit::VirtualKeyCode;
+
+use crate::achievements::ACHIEVEMENTS;
+use crate::entities::load_font;
+use crate::menu_state::{create_text, MenuState};
+use crate::resources::{Localisation, UnlockedAchievements};
+
+const NAME_FONT_SIZE: f32 = 16.;
+const DESCRIPTION_FONT_SIZE: f32 = 11.;
+const ACHIEVEMENT_HEIGHT: f32 = 44.;
+const FIRST_ACHIEVEMENT_Y: f32 = 200.;
+const UNLOCKED_COLOUR: [f32; 4] = [1.0, 0.8, 0.2, 1.0]; // amber
+const LOCKED_COLOUR: [f32; 4] = [0.5, 0.5, 0.5, 1.0]; // grey
+
+/// The achievements screen, listing every achievement and whether it has been unlocked
+///
+/// Like the [ControlsState](struct.ControlsState.html), this is a simple screen
+/// built out of UI entities, reached from the ship-select screen.
+/// The unlocked achievements are read from the player's own file
+/// (see [UnlockedAchievements](resources/struct.UnlockedAchievements.html)),
+/// so the list is up to date with the games played before.
+/// Escape goes back to the ship-select screen.
+#[derive(Default)]
+pub struct AchievementsState {
+    /// All the UI entities of the screen, so we can delete them when we leave
+    ui_entities: Vec<Entity>,
+}
+
+impl SimpleState for AchievementsState {
+    fn on_start(&mut self, state_data: StateData<'_, GameData<'_, '_>>) {
+        let world = state_data.world;
+        let font = load_font(world);
+        let localisation = world.read_resource::<Localisation>().clone();
+        let unlocked = UnlockedAchievements::load_or_default();
+        let unlocked_count = ACHIEVEMENTS
+            .achievements
+            .iter()
+            .filter(|achievement| unlocked.is_unlocked(&achievement.id))
+            .count();
+
+        let title_text = localisation.text("achievements-title");
+        let title = create_text(world, &font, "achievements_title", &title_text, 260., 30.);
+        let summary = localisation.format(
+            "achievements-summary",
+            &[
+                ("unlocked", unlocked_count.to_string()),
+                ("total", ACHIEVEMENTS.achievements.len().to_string()),
+            ],
+        );
+        let status = create_text(world, &font, "achievements_status", &summary, -280., 14.);
+        self.ui_entities = vec![title, status];
+
+        for (row, achievement) in ACHIEVEMENTS.achievements.iter().enumerate() {
+            let y = FIRST_ACHIEVEMENT_Y - row as f32 * ACHIEVEMENT_HEIGHT;
+            let colour = if unlocked.is_unlocked(&achievement.id) {
+                UNLOCKED_COLOUR
+            } else {
+                LOCKED_COLOUR
+            };
+            let name = create_text(
+                world,
+                &font,
+                &format!("achievement_name_{}", row),
+                &localisation.text(&achievement.name),
+                y,
+                NAME_FONT_SIZE,
+            );
+            let description = create_text(
+                world,
+                &font,
+                &format!("achievement_description_{}", row),
+                &localisation.text(&achievement.description),
+                y - NAME_FONT_SIZE - 2.,
+                DESCRIPTION_FONT_SIZE,
+            );
+            let mut ui_texts = world.write_storage::<UiText>();
+            for entity in &[name, description] {
+                if let Some(ui_text) = ui_texts.get_mut(*entity) {
+                    ui_text.color = colour;
+                }
+            }
+            self.ui_entities.push(name);
+            self.ui_entities.push(description);
+        }
+    }
+
+    fn on_stop(&mut self, state_data: StateData<'_, GameData<'_, '_>>) {
+        let _result = state_data.world.delete_entities(&self.ui_entities);
+        self.ui_entities.clear();
+    }
+
+    fn handle_event(
+        &mut self,
+        _: StateData<'_, GameData<'_, '_>>,
+        event: StateEvent,
+    ) -> SimpleTrans {
+        if let StateEvent::Window(event) = event {
+            if is_close_requested(&event) {
+                return Trans::Quit;
+            }
+            if is_key_down(&event, VirtualKeyCode::Escape) {
+                return Trans::Switch(Box::new(MenuState::default()));
+            }
+        }
+        Trans::None
+    }
+
+    // This code tells Amethyst to run all the systems in your game data.
+    fn update(&mut self, state_data: &mut StateData<'_, GameData<'_, '_>>) -> SimpleTrans {
+        state_data.data.update(&state_data.world);
+        Trans::None
+    }
+}
@@ -0,0 +1,480 @@
+//! Driving the game from a program, to train and evaluate bots
+//!
+//! An [AgentEnvironment](struct.AgentEnvironment.html) runs the game's own systems
+//! with no window: an agent [resets](struct.AgentEnvironment.html#method.reset) it with a seed,
+//! then [steps](struct.AgentEnvironment.html#method.step) it one frame at a time,
+//! choosing player one's controls each frame. Each step tells the agent what it can see
+//! (an [Observation](struct.Observation.html)), how well it did (the reward) and whether the game is over.
+//! The frames run back to back on the fixed timestep (see [exact_timestep](../fn.exact_timestep.html)),
+//! so the game runs as fast as the computer can play it, and a seed and a list of actions
+//! always play out the same.
+//!
+//! Programs in other languages drive the game through the same calls, sent as JSON Lines:
+//! `space_shooter --agent` reads requests from stdin and writes the replies to stdout,
+//! and `space_shooter --agent-tcp 127.0.0.1:5555` does the same for each program that connects.
+//! The requests are:
+//!
+//! * `{"command": "reset", "seed": 42}` (the seed is optional), answered with `{"observation": ...}`;
+//! * `{"command": "step", "action": {"horizontal": 1.0, "vertical": 0.0, "fire": true}}`,
+//!   answered with the observation, `reward`, `done`, `score_delta` and `lives_lost`;
+//! * `{"command": "close"}`, which ends the conversation.
+//!
+//! A request that can't be carried out is answered with `{"error": "..."}`.
+//!
+//! Nothing an agent does is kept: it doesn't set the high score, unlock achievements
+//! or add to the telemetry.
+
+use std::io::{BufRead, Write};
+use std::path::{Path, PathBuf};
+
+use amethyst::assets::{AssetStorage, Loader};
+use amethyst::core::rayon::ThreadPoolBuilder;
+use amethyst::core::transform::{Transform, TransformBundle};
+use amethyst::core::{ArcThreadPool, Hidden};
+use amethyst::ecs::prelude::{Join, World, WorldExt};
+use amethyst::input::{InputBundle, StringBindings};
+use amethyst::prelude::*;
+use amethyst::renderer::camera::Camera;
+use amethyst::renderer::{SpriteRender, SpriteSheet, Texture};
+use amethyst::ui::{FontAsset, UiImage, UiText, UiTransform};
+use amethyst::window::ScreenDimensions;
+use amethyst::DataInit;
+use rand::{thread_rng, Rng};
+use serde_derive::{Deserialize, Serialize};
+
+use crate::bundle::GameBundle;
+use crate::components::{register_components, Asteroid, Inactive, Laser, Player, Ship, ShipStatus};
+use crate::config::GAME_CONFIGURATION;
+use crate::entities::initialise_entities;
+use crate::replay::Session;
+use crate::resources::{
+    add_resources, Controls, DebugOverlay, DebugStats, GameClock, GamePhase, PlayState,
+    PlayerControls,
+};
+use crate::roster::{ShipDefinition, SHIP_ROSTER};
+
+/// What an agent can see of player one's ship, or of another player's
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct ShipObservation {
+    /// The player flying the ship (0 being player one)
+    pub player: usize,
+    /// Where the ship is, in the arena
+    pub x: f32,
+    /// Where the ship is, in the arena
+    pub y: f32,
+    /// How fast the ship is moving, to the right
+    pub velocity_x: f32,
+    /// How fast the ship is moving, upwards
+    pub velocity_y: f32,
+    /// Whether or not the ship can be hit (it isn't waiting to respawn, nor invulnerable)
+    pub is_vulnerable: bool,
+    /// Whether or not the ship is waiting to respawn
+    pub is_destroyed: bool,
+    /// How long before the ship can fire again (sec)
+    pub fire_cooldown: f32,
+    /// How many lives the player has left
+    pub lives: u8,
+    /// The player's score
+    pub score: u32,
+}
+
+/// What an agent can see of an asteroid
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct AsteroidObservation {
+    /// Where the asteroid is, in the arena
+    pub x: f32,
+    /// Where the asteroid is, in the arena
+    pub y: f32,
+    /// How fast the asteroid is moving, to the right
+    pub velocity_x: f32,
+    /// How fast the asteroid is moving, upwards
+    pub velocity_y: f32,
+    /// The width of the asteroid
+    pub width: f32,
+    /// The height of the asteroid
+    pub height: f32,
+    /// How many more laser hits it takes to destroy the asteroid
+    pub hit_points: u8,
+    /// How far the asteroid is from player one's ship
+    pub distance: f32,
+}
+
+/// What an agent can see of a laser in flight
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct LaserObservation {
+    /// The player who fired the laser (0 being player one)
+    pub owner: usize,
+    /// Where the laser is, in the arena
+    pub x: f32,
+    /// Where the laser is, in the arena
+    pub y: f32,
+    /// How fast the laser is moving, upwards
+    pub velocity_y: f32,
+}
+
+/// Everything an agent can see after a frame
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct Observation {
+    /// How many frames have been played
+    pub frame: u64,
+    /// The width of the arena
+    pub arena_width: f32,
+    /// The height of the arena
+    pub arena_height: f32,
+    /// Every player's ship, in player order
+    pub ships: Vec<ShipObservation>,
+    /// The asteroids nearest player one's ship, nearest first
+    /// (as many as `agent_asteroid_count` in the game configuration)
+    pub asteroids: Vec<AsteroidObservation>,
+    /// Every laser in flight
+    pub lasers: Vec<LaserObservation>,
+}
+
+/// What happened during a step
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct StepResult {
+    /// What the agent can see after the step
+    pub observation: Observation,
+    /// How well the step went: the points player one scored,
+    /// less `agent_life_penalty` (from the game configuration) for each life they lost
+    pub reward: f32,
+    /// Whether or not the game is over
+    pub done: bool,
+    /// The points player one scored during the step
+    pub score_delta: u32,
+    /// How many lives player one lost during the step
+    pub lives_lost: u32,
+}
+
+/// A request from an agent driving the game through the JSON protocol
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(tag = "command", rename_all = "snake_case")]
+pub enum AgentRequest {
+    /// Starts a new game, with the given seed or a random one
+    Reset {
+        /// The seed of the game's random number generator
+        #[serde(default)]
+        seed: Option<u64>,
+    },
+    /// Plays a frame with player one's controls set to the given action
+    Step {
+        /// Player one's controls for the frame
+        action: PlayerControls,
+    },
+    /// Ends the conversation
+    Close,
+}
+
+/// A reply to an agent driving the game through the JSON protocol
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(untagged)]
+pub enum AgentReply {
+    /// What the agent can see at the start of a new game
+    Reset {
+        /// What the agent can see
+        observation: Observation,
+    },
+    /// What happened during a step
+    Step(StepResult),
+    /// Why a request couldn't be carried out
+    Error {
+        /// What went wrong
+        error: String,
+    },
+}
+
+/// The game, run without a window for an agent to drive
+///
+/// The environment has the same systems as the game (the [GameBundle](../struct.GameBundle.html)),
+/// and sets up each game the way the [GameState](../struct.GameState.html) does,
+/// with the first ship in the roster (or the one given to [with_ship](#method.with_ship)).
+/// Nothing is drawn: the sprites and fonts are asked for, but never turned into textures.
+pub struct AgentEnvironment {
+    /// The folder the game's assets are in
+    assets_path: PathBuf,
+    /// The threads the asset loader works on, shared by every game
+    thread_pool: ArcThreadPool,
+    /// The ship player one flies, if not the first in the roster
+    ship: Option<ShipDefinition>,
+    /// The world of the current game, once the environment has been reset
+    world: Option<World>,
+    /// The systems of the current game
+    game_data: Option<GameData<'static, 'static>>,
+    /// Player one's score and lives after the last frame
+    last_progress: (u32, u8),
+}
+
+impl AgentEnvironment {
+    /// Creates the environment, with the game's assets in the given folder
+    pub fn new(assets_path: &Path) -> Result<AgentEnvironment, amethyst::Error> {
+        let thread_pool = ThreadPoolBuilder::new()
+            .build()
+            .map_err(|error| amethyst::Error::from_string(error.to_string()))?;
+        Ok(AgentEnvironment {
+            assets_path: assets_path.to_path_buf(),
+            thread_pool: ArcThreadPool::new(thread_pool),
+            ship: None,
+            world: None,
+            game_data: None,
+            last_progress: (0, 0),
+        })
+    }
+
+    /// Has player one fly the given ship from the next reset on
+    pub fn with_ship(mut self, ship: ShipDefinition) -> AgentEnvironment {
+        self.ship = Some(ship);
+        self
+    }
+
+    /// Starts a new game with the given seed, and returns what the agent can see of it
+    pub fn reset(&mut self, seed: u64) -> Result<Observation, amethyst::Error> {
+        let mut world = World::new();
+        world.insert(Session::agent(seed));
+        world.insert(ScreenDimensions::new(
+            GAME_CONFIGURATION.arena_width as u32,
+            GAME_CONFIGURATION.arena_height as u32,
+            1.0,
+        ));
+        world.insert(Loader::new(&self.assets_path, self.thread_pool.clone()));
+        world.insert(self.thread_pool.clone());
+        world.insert(AssetStorage::<Texture>::new());
+        world.insert(AssetStorage::<SpriteSheet>::new());
+        world.insert(AssetStorage::<FontAsset>::new());
+        world.insert(
+            self.ship
+                .clone()
+                .unwrap_or_else(|| SHIP_ROSTER.ships[0].clone()),
+        );
+
+        let game_data = GameDataBuilder::default()
+            .with_bundle(InputBundle::<StringBindings>::new())?
+            .with_bundle(TransformBundle::new())?
+            .with_bundle(GameBundle)?
+            .build(&mut world);
+        // the components the renderer and the UI would otherwise register
+        world.register::<SpriteRender>();
+        world.register::<Camera>();
+        world.register::<Hidden>();
+        world.register::<UiImage>();
+        world.register::<UiText>();
+        world.register::<UiTransform>();
+        register_components(&mut world);
+        add_resources(&mut world);
+        initialise_entities(&mut world);
+        let ship_name = world.read_resource::<ShipDefinition>().name.clone();
+        let player_count = world.read_resource::<PlayState>().players.len();
+        {
+            let mut session = world.write_resource::<Session>();
+            session.replay.ship = ship_name;
+            session.replay.player_count = player_count;
+        }
+        world.insert(GamePhase::Playing);
+
+        self.last_progress = player_one_progress(&world);
+        let observation = observe(&world);
+        self.world = Some(world);
+        self.game_data = Some(game_data);
+        Ok(observation)
+    }
+
+    /// Plays a frame with player one's controls set to the given action
+    /// (the other players, if any, let go of their controls)
+    ///
+    /// Once the game is over, the game stays as it is.
+    pub fn step(&mut self, action: &PlayerControls) -> Result<StepResult, amethyst::Error> {
+        let (world, game_data) = match (&mut self.world, &mut self.game_data) {
+            (Some(world), Some(game_data)) => (world, game_data),
+            _ => {
+                return Err(amethyst::Error::from_string(
+                    "The game must be reset before it can be stepped",
+                ))
+            }
+        };
+        let done = world.read_resource::<PlayState>().is_game_over();
+        if !done {
+            {
+                let mut controls = world.write_resource::<Controls>();
+                for (index, player_controls) in controls.players.iter_mut().enumerate() {
+                    *player_controls = if index == 0 {
+                        *action
+                    } else {
+                        PlayerControls::default()
+                    };
+                }
+            }
+            game_data.update(world);
+            world.maintain();
+        }
+
+        let (last_score, last_lives) = self.last_progress;
+        let (score, lives) = player_one_progress(world);
+        self.last_progress = (score, lives);
+        let score_delta = score.saturating_sub(last_score);
+        let lives_lost = u32::from(last_lives.saturating_sub(lives));
+        Ok(StepResult {
+            observation: observe(world),
+            reward: score_delta as f32 - GAME_CONFIGURATION.agent_life_penalty * lives_lost as f32,
+            done: world.read_resource::<PlayState>().is_game_over(),
+            score_delta,
+            lives_lost,
+        })
+    }
+
+    /// The timings and entity counts of the [debug overlay](../resources/struct.DebugOverlay.html),
+    /// or `None` if the environment hasn't been reset yet
+    ///
+    /// The [DebugSystem](../systems/struct.DebugSystem.html) counts the entities while the last frame
+    /// is played, so the lasers and fragments that frame created aren't counted until the next one.
+    pub fn debug_stats(&self) -> Option<DebugStats> {
+        self.world
+            .as_ref()
+            .map(|world| world.read_resource::<DebugOverlay>().stats)
+    }
+
+    /// Answers the JSON Lines requests read from the reader, writing each reply to the writer,
+    /// until the reader runs out or the agent sends `close`
+    pub fn serve<R: BufRead, W: Write>(&mut self, reader: R, mut writer: W) -> std::io::Result<()> {
+        for line in reader.lines() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let reply = match serde_json::from_str(&line) {
+                Ok(AgentRequest::Reset { seed }) => self
+                    .reset(seed.unwrap_or_else(|| thread_rng().gen()))
+                    .map(|observation| AgentReply::Reset { observation }),
+                Ok(AgentRequest::Step { action }) => self.step(&action).map(AgentReply::Step),
+                Ok(AgentRequest::Close) => break,
+                Err(error) => Err(amethyst::Error::from_string(format!(
+                    "Can't read request: {}",
+                    error
+                ))),
+            };
+            let reply = reply.unwrap_or_else(|error| AgentReply::Error {
+                error: error.to_string(),
+            });
+            serde_json::to_writer(&mut writer, &reply)?;
+            writeln!(writer)?;
+            writer.flush()?;
+        }
+        Ok(())
+    }
+}
+
+/// Player one's score and lives
+fn player_one_progress(world: &World) -> (u32, u8) {
+    world
+        .read_resource::<PlayState>()
+        .players
+        .first()
+        .map_or((0, 0), |player| (player.score, player.lives))
+}
+
+/// What an agent can see of the game
+fn observe(world: &World) -> Observation {
+    let play_state = world.read_resource::<PlayState>();
+    let transforms = world.read_storage::<Transform>();
+    let inactives = world.read_storage::<Inactive>();
+
+    let mut ships: Vec<ShipObservation> = (
+        &world.read_storage::<Ship>(),
+        &world.read_storage::<Player>(),
+        &transforms,
+    )
+        .join()
+        .map(|(ship, player, transform)| {
+            let player_state = play_state.players.get(player.index);
+            ShipObservation {
+                player: player.index,
+                x: transform.translation().x,
+                y: transform.translation().y,
+                velocity_x: ship.velocity.x,
+                velocity_y: ship.velocity.y,
+                is_vulnerable: ship.status == ShipStatus::Active,
+                is_destroyed: match ship.status {
+                    ShipStatus::Destroyed { .. } => true,
+                    _ => false,
+                },
+                fire_cooldown: ship.trigger_reset_timer.max(0.0),
+                lives: player_state.map_or(0, |player_state| player_state.lives),
+                score: player_state.map_or(0, |player_state| player_state.score),
+            }
+        })
+        .collect();
+    ships.sort_by_key(|ship| ship.player);
+
+    let (ship_x, ship_y) = ships.first().map_or((0.0, 0.0), |ship| (ship.x, ship.y));
+    let mut asteroids: Vec<AsteroidObservation> = (&world.read_storage::<Asteroid>(), &transforms)
+        .join()
+        .filter(|(asteroid, _transform)| !asteroid.is_destroyed)
+        .map(|(asteroid, transform)| {
+            let (x, y) = (transform.translation().x, transform.translation().y);
+            AsteroidObservation {
+                x,
+                y,
+                velocity_x: asteroid.velocity.x,
+                velocity_y: asteroid.velocity.y,
+                width: asteroid.width,
+                height: asteroid.height,
+                hit_points: asteroid.hit_points,
+                distance: (x - ship_x).hypot(y - ship_y),
+            }
+        })
+        .collect();
+    asteroids.sort_by(|a, b| {
+        a.distance
+            .partial_cmp(&b.distance)
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+    asteroids.truncate(GAME_CONFIGURATION.agent_asteroid_count);
+
+    let lasers = (&world.read_storage::<Laser>(), &transforms, !&inactives)
+        .join()
+        .map(|(laser, transform, _)| LaserObservation {
+            owner: laser.owner,
+            x: transform.translation().x,
+            y: transform.translation().y,
+            velocity_y: laser.velocity,
+        })
+        .collect();
+
+    Observation {
+        frame: world.read_resource::<GameClock>().frame,
+        arena_width: GAME_CONFIGURATION.arena_width,
+        arena_height: GAME_CONFIGURATION.arena_height,
+        ships,
+        asteroids,
+        lasers,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn the_debug_overlay_counts_the_entities_of_a_seeded_game() {
+        let assets_path = Path::new(env!("CARGO_MANIFEST_DIR")).join("assets");
+        let mut environment = AgentEnvironment::new(&assets_path).unwrap();
+        assert_eq!(environment.debug_stats(), None);
+        let mut observation = environment.reset(7).unwrap();
+        let fire = PlayerControls {
+            horizontal: 0.0,
+            vertical: 0.0,
+            fire: true,
+        };
+        for _frame in 0..10 {
+            let step = environment.step(&fire).unwrap();
+            // the stats were counted before the frame's new entities were added
+            let stats = environment.debug_stats().unwrap();
+            assert_eq!(stats.ships, observation.ships.len());
+            assert_eq!(stats.lasers, observation.lasers.len());
+            assert!(stats.asteroids >= observation.asteroids.len());
+            observation = step.observation;
+        }
+        let stats = environment.debug_stats().unwrap();
+        assert_eq!(stats.ships, GAME_CONFIGURATION.player_count());
+        assert!(stats.asteroids > 0);
+        assert!(stats.lasers > 0);
+    }
+}
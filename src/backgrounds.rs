@@ -0,0 +1,93 @@
+//! The backgrounds of the levels
+//!
+//! Like the [ship roster](struct.ShipRoster.html), the backgrounds are
+//! loaded from a RON file ("backgrounds.ron" in resources), so the game designer
+//! can change the look of each level without having to recompile the code.
+//!
+//! Each level's background is a stack of layers, drawn back to front,
+//! each scrolling down the screen at its own speed to give a parallax effect:
+//! the slower a layer scrolls, the further away it looks.
+
+use amethyst::config::Config;
+use lazy_static::lazy_static;
+use serde_derive::{Deserialize, Serialize};
+
+/// One layer of a level's background
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub enum BackgroundLayer {
+    /// An image tiled across the whole screen
+    Image {
+        /// The path of the image, relative to the assets folder, e.g. "Backgrounds/black.png"
+        path: String,
+        /// The size of each (square) tile on the screen
+        tile_size: f32,
+        /// How fast the layer scrolls down the screen
+        speed: f32,
+    },
+    /// Star sprites from the sprite sheet, scattered at random across the screen
+    Stars {
+        /// How many stars the layer has
+        count: usize,
+        /// The names of the star sprites to pick from, e.g. "star1"
+        sprites: Vec<String>,
+        /// The smallest and largest scale of the stars
+        scale: (f32, f32),
+        /// How fast the layer scrolls down the screen
+        speed: f32,
+    },
+}
+
+/// The background of one level
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct LevelBackground {
+    /// The layers of the background, from the furthest away to the nearest
+    pub layers: Vec<BackgroundLayer>,
+}
+
+/// The backgrounds of all the levels
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct Backgrounds {
+    /// The background of each level, in order.
+    /// Once the levels run out, the backgrounds start again from the first.
+    pub levels: Vec<LevelBackground>,
+}
+
+impl Backgrounds {
+    /// The background of the given level (level 1 being the first)
+    pub fn for_level(&self, level: u32) -> &LevelBackground {
+        let index = (level.max(1) - 1) as usize % self.levels.len();
+        &self.levels[index]
+    }
+
+    /// The paths of all the images used by the backgrounds
+    pub fn image_paths(&self) -> Vec<String> {
+        let mut paths: Vec<String> = self
+            .levels
+            .iter()
+            .flat_map(|level| level.layers.iter())
+            .filter_map(|layer| match layer {
+                BackgroundLayer::Image { path, .. } => Some(path.clone()),
+                BackgroundLayer::Stars { .. } => None,
+            })
+            .collect();
+        paths.sort();
+        paths.dedup();
+        paths
+    }
+}
+
+lazy_static! {
+    /// The actual [backgrounds](struct.Backgrounds.html).
+    ///
+    /// The backgrounds are automatically loaded on startup
+    /// from the file "backgrounds.ron" in resources.
+    pub static ref BACKGROUNDS: Backgrounds = {
+        let backgrounds_path = format!(
+            "{}/resources/backgrounds.ron",
+            env!("CARGO_MANIFEST_DIR")
+        );
+        let backgrounds = Backgrounds::load(&backgrounds_path).unwrap();
+        assert!(!backgrounds.levels.is_empty(), "the backgrounds need at least one level");
+        backgrounds
+    };
+}
@@ -1,8 +1,10 @@
 use amethyst::core::bundle::SystemBundle;
+use amethyst::core::SystemExt;
 use amethyst::ecs::prelude::DispatcherBuilder;
 use amethyst::Result;
 
 //use components::*;
+use crate::resources::GamePhase;
 use crate::systems::*;
 use amethyst::prelude::World;
 
@@ -13,20 +15,118 @@ use amethyst::prelude::World;
 /// can be referenced in a system or in the state.
 ///
 /// This bundle prepares the world for the space_shooter game.
+///
+/// The game's systems only run while the game is being played
+/// (see [GamePhase](resources/enum.GamePhase.html)),
+/// so they leave the ship-select screen alone,
+/// and wait while a [network game](network/index.html) waits for the other players.
+///
+/// The screens before the game dispatch these systems too, before the game state has set up
+/// the resources it creates when the game starts (such as the laser and explosion resources).
+/// So the systems fetch those resources as `Option`s, and do nothing until they are there.
 pub struct GameBundle;
 
 impl<'a, 'b> SystemBundle<'a, 'b> for GameBundle {
     fn build(self, _world: &mut World, builder: &mut DispatcherBuilder<'a, 'b>) -> Result<()> {
-        builder.add(ShipSystem, "ship_system", &["input_system"]);
-        builder.add(ShipCollisionSystem, "collision_system", &["ship_system"]);
-        builder.add(AsteroidSystem, "asteroid_system", &["collision_system"]);
-        builder.add(LaserSystem, "laser_system", &["ship_system"]);
+        let playing = GamePhase::Playing;
+        // the lockstep system decides whether the other systems run, so it's never paused
+        builder.add(LockstepSystem, "lockstep_system", &["input_system"]);
+        builder.add(
+            ClockSystem.pausable(playing),
+            "clock_system",
+            &["lockstep_system"],
+        );
+        builder.add(
+            ControlSystem.pausable(playing),
+            "control_system",
+            &["input_system", "lockstep_system"],
+        );
+        builder.add(
+            ShipSystem.pausable(playing),
+            "ship_system",
+            &["control_system", "clock_system"],
+        );
         builder.add(
-            LaserCollisionSystem,
+            ShipCollisionSystem.pausable(playing),
+            "collision_system",
+            &["ship_system"],
+        );
+        builder.add(
+            AsteroidSystem.pausable(playing),
+            "asteroid_system",
+            &["collision_system"],
+        );
+        builder.add(LaserSystem.pausable(playing), "laser_system", &["ship_system"]);
+        builder.add(
+            LaserCollisionSystem.pausable(playing),
             "laser_collision_system",
             &["laser_system"],
         );
-        builder.add(LivesSystem, "lives_system", &["collision_system"]);
+        builder.add(
+            LivesSystem.pausable(playing),
+            "lives_system",
+            &["collision_system", "laser_collision_system"],
+        );
+        builder.add(
+            RespawnSystem.pausable(playing),
+            "respawn_system",
+            &["collision_system"],
+        );
+        builder.add(
+            ExplosionSystem.pausable(playing),
+            "explosion_system",
+            &["clock_system"],
+        );
+        builder.add(
+            DamageSystem.pausable(playing),
+            "damage_system",
+            &["respawn_system"],
+        );
+        builder.add(
+            CameraSystem.pausable(playing),
+            "camera_system",
+            &["lockstep_system"],
+        );
+        builder.add(
+            BackgroundSystem.pausable(playing),
+            "background_system",
+            &["asteroid_system", "laser_collision_system"],
+        );
+        builder.add(
+            HudSystem.pausable(playing),
+            "hud_system",
+            &[
+                "laser_system",
+                "laser_collision_system",
+                "respawn_system",
+                "camera_system",
+            ],
+        );
+        builder.add(
+            DebugSystem.pausable(playing),
+            "debug_system",
+            &["hud_system", "explosion_system"],
+        );
+        builder.add(
+            AchievementSystem.pausable(playing),
+            "achievement_system",
+            &[
+                "ship_system",
+                "collision_system",
+                "laser_collision_system",
+                "hud_system",
+            ],
+        );
+        builder.add(
+            TelemetrySystem.pausable(playing),
+            "telemetry_system",
+            &[
+                "ship_system",
+                "collision_system",
+                "laser_collision_system",
+                "lives_system",
+            ],
+        );
         Ok(())
     }
 }
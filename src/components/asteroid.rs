@@ -1,4 +1,35 @@
+use amethyst::core::math::Vector2;
 use amethyst::ecs::prelude::{Component, DenseVecStorage};
+use serde_derive::{Deserialize, Serialize};
+
+/// The size classes an asteroid can come in
+///
+/// Each size has its own set of sprites and its own number of hit points.
+/// When a larger asteroid is destroyed, it splits into fragments of the next size down.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+pub enum AsteroidSize {
+    /// The largest asteroids, which take the most hits to destroy
+    Big,
+    /// The medium-sized asteroids (the original asteroid size)
+    Medium,
+    /// Small asteroids
+    Small,
+    /// Tiny asteroids, which don't split any further
+    Tiny,
+}
+
+impl AsteroidSize {
+    /// The size of the fragments this asteroid breaks into when destroyed,
+    /// or `None` if the asteroid is too small to split.
+    pub fn smaller(self) -> Option<AsteroidSize> {
+        match self {
+            AsteroidSize::Big => Some(AsteroidSize::Medium),
+            AsteroidSize::Medium => Some(AsteroidSize::Small),
+            AsteroidSize::Small => Some(AsteroidSize::Tiny),
+            AsteroidSize::Tiny => None,
+        }
+    }
+}
 
 /// A component for our asteroid
 ///
@@ -10,12 +41,23 @@ use amethyst::ecs::prelude::{Component, DenseVecStorage};
 /// modified by a system.
 #[derive(Clone)]
 pub struct Asteroid {
-    /// How fast the asteroid is falling
-    pub velocity: f32,
-    /// The width of the asteroid in pixels
+    /// How fast the asteroid is moving, horizontally (drift) and vertically.
+    ///
+    /// Note the asteroid falls down the screen, so its vertical velocity is negative.
+    pub velocity: Vector2<f32>,
+    /// How fast the asteroid is spinning, in radians per second
+    pub angular_velocity: f32,
+    /// The width of the asteroid sprite in pixels (before any scaling by its transform)
     pub width: f32,
-    /// The height of the asteroid in pixels
+    /// The height of the asteroid sprite in pixels (before any scaling by its transform)
     pub height: f32,
+    /// The size class of the asteroid
+    pub size: AsteroidSize,
+    /// How many more laser hits the asteroid can take before it is destroyed
+    pub hit_points: u8,
+    /// Whether the asteroid is a fragment of a larger asteroid.
+    /// Fragments are deleted rather than relocated once destroyed.
+    pub is_fragment: bool,
     /// Whether or not the asteroid has been destroyed and is ready for relocation
     pub is_destroyed: bool,
 }
@@ -0,0 +1,17 @@
+use amethyst::ecs::prelude::{Component, DenseVecStorage};
+
+/// A component for the damage drawn over the player's ship
+///
+/// The overlay is a child entity of the ship, so it moves with it.
+/// The damage system picks which of the damage sprites to show
+/// based on how much damage the ship has taken,
+/// and hides the overlay when the ship is undamaged.
+#[derive(Clone)]
+pub struct DamageOverlay {
+    /// The sprite numbers of the damage sprites, from the lightest damage to the heaviest
+    pub sprite_numbers: Vec<usize>,
+}
+
+impl Component for DamageOverlay {
+    type Storage = DenseVecStorage<Self>;
+}
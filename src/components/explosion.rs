@@ -0,0 +1,17 @@
+use amethyst::ecs::prelude::{Component, DenseVecStorage};
+
+/// A component for a short-lived explosion effect
+///
+/// The explosion grows over its lifetime,
+/// and is deleted once its time is up.
+#[derive(Clone)]
+pub struct Explosion {
+    /// How long in seconds the explosion has been going
+    pub age: f32,
+    /// How long in seconds the explosion lasts
+    pub duration: f32,
+}
+
+impl Component for Explosion {
+    type Storage = DenseVecStorage<Self>;
+}
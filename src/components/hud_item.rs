@@ -0,0 +1,43 @@
+use amethyst::ecs::prelude::{Component, DenseVecStorage};
+
+/// A component for the items on the UI layer that show part of the play state
+///
+/// The [HudSystem](../systems/struct.HudSystem.html) keeps each item up to date.
+/// (The life icons are kept up to date by the [LivesSystem](../systems/struct.LivesSystem.html) instead.)
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum HudItem {
+    /// The text showing a player's score
+    Score {
+        /// The index of the player whose score is shown
+        player: usize,
+    },
+    /// The text showing a player's score multiplier
+    Multiplier {
+        /// The index of the player whose multiplier is shown
+        player: usize,
+    },
+    /// The bar showing how soon a player's ship can fire again
+    CooldownBar {
+        /// The index of the player whose ship's cooldown is shown
+        player: usize,
+    },
+    /// The text showing the time left on a player's active power-ups
+    PowerUps {
+        /// The index of the player whose power-ups are shown
+        player: usize,
+    },
+    /// The text showing the best score so far
+    HighScore,
+    /// The text showing the wave being played
+    Wave,
+    /// The text showing the debug overlay's timings and entity counts
+    /// (kept up to date by the [DebugSystem](../systems/struct.DebugSystem.html))
+    DebugStats,
+    /// The pop-up announcing a newly unlocked achievement
+    /// (kept up to date by the [AchievementSystem](../systems/struct.AchievementSystem.html))
+    AchievementToast,
+}
+
+impl Component for HudItem {
+    type Storage = DenseVecStorage<Self>;
+}
@@ -0,0 +1,48 @@
+use amethyst::ecs::prelude::{Component, DenseVecStorage};
+use amethyst::ui::{Anchor, UiTransform};
+
+use crate::resources::Arena;
+
+/// A component for the items on the UI layer that sit over the arena (e.g. life icons and scores)
+///
+/// The UI layer is laid out in pixels, from the top-left corner of the window,
+/// whereas the HUD needs to follow the [arena](../resources/struct.Arena.html)
+/// as it is scaled and letterboxed to fit the window.
+/// So each HUD item keeps its layout here, in arena units from the arena's top-left corner,
+/// and the [CameraSystem](../systems/struct.CameraSystem.html) works out its
+/// place on the UI layer whenever the window changes size.
+#[derive(Clone, Copy, Debug)]
+pub struct HudLayout {
+    /// How far the item's anchor point is from the left of the arena
+    pub x: f32,
+    /// How far the item's anchor point is from the top of the arena (negative going down)
+    pub y: f32,
+    /// The width of the item
+    pub width: f32,
+    /// The height of the item
+    pub height: f32,
+    /// The size of the item's text, if it has any
+    pub font_size: Option<f32>,
+}
+
+impl HudLayout {
+    /// Places the item on the UI layer, for the arena as it is currently fitted to the window
+    ///
+    /// The pivot is the point of the item that sits at its layout's x and y.
+    pub fn ui_transform(&self, id: String, pivot: Anchor, arena: &Arena) -> UiTransform {
+        UiTransform::new(
+            id,
+            Anchor::TopLeft,
+            pivot,
+            arena.margin_x + self.x * arena.scale,
+            -arena.margin_y + self.y * arena.scale,
+            0.,
+            self.width * arena.scale,
+            self.height * arena.scale,
+        )
+    }
+}
+
+impl Component for HudLayout {
+    type Storage = DenseVecStorage<Self>;
+}
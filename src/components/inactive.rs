@@ -0,0 +1,13 @@
+use amethyst::ecs::prelude::{Component, NullStorage};
+
+/// A marker for an entity that is resting in an [entity pool](../resources/struct.EntityPool.html)
+///
+/// An inactive entity is also hidden, so it isn't drawn.
+/// Systems skip inactive entities (e.g. inactive lasers don't move or hit anything),
+/// until the pool hands the entity out again.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Inactive;
+
+impl Component for Inactive {
+    type Storage = NullStorage<Self>;
+}
@@ -9,6 +9,8 @@ pub struct Laser {
     pub width: f32,
     /// The height of our laser beam
     pub height: f32,
+    /// The index of the player who fired the laser, so they get the points for what it hits
+    pub owner: usize,
 }
 
 impl Component for Laser {
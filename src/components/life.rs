@@ -3,10 +3,12 @@ use amethyst::ecs::prelude::{Component, DenseVecStorage};
 /// A component to represent a life the player has
 ///
 /// It's connected to a life sprite shown in the UI layer.
-/// When the play state number of lives fall below
+/// When the play state number of lives of its player fall below
 /// the life number of this component,
 /// the system will remove the associated life entity.
 pub struct Life {
+    /// The index of the player the life belongs to
+    pub player: usize,
     /// The life number this component represents
     pub life_number: u8,
 }
@@ -6,17 +6,33 @@
 //! a component when you need them. That's just good practice.
 
 mod asteroid;
+mod damage_overlay;
+mod explosion;
+mod hud_item;
+mod hud_layout;
+mod inactive;
 mod laser;
 mod life;
+mod player;
+mod scrolling;
 mod ship;
 
 use amethyst::ecs::prelude::World;
 use amethyst::ecs::WorldExt;
 
 pub use self::asteroid::Asteroid;
+pub use self::asteroid::AsteroidSize;
+pub use self::damage_overlay::DamageOverlay;
+pub use self::explosion::Explosion;
+pub use self::hud_item::HudItem;
+pub use self::hud_layout::HudLayout;
+pub use self::inactive::Inactive;
 pub use self::laser::Laser;
 pub use self::life::Life;
+pub use self::player::Player;
+pub use self::scrolling::Scrolling;
 pub use self::ship::Ship;
+pub use self::ship::ShipStatus;
 
 /// Register all the components to the world
 pub fn register_components(world: &mut World) {
@@ -24,4 +40,11 @@ pub fn register_components(world: &mut World) {
     world.register::<Asteroid>();
     world.register::<Laser>();
     world.register::<Life>();
+    world.register::<DamageOverlay>();
+    world.register::<Explosion>();
+    world.register::<Player>();
+    world.register::<HudItem>();
+    world.register::<Scrolling>();
+    world.register::<HudLayout>();
+    world.register::<Inactive>();
 }
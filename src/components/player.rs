@@ -0,0 +1,48 @@
+use amethyst::ecs::prelude::{Component, DenseVecStorage};
+
+/// A component tying a ship to one of the players
+///
+/// Each player has their own input bindings (from "input.ron" in resources),
+/// so several players can share a keyboard.
+/// Each player also has a controller slot: while a controller is plugged into it,
+/// the player steers with its analog stick and buttons instead.
+#[derive(Clone)]
+pub struct Player {
+    /// The player's position in the list of players (0 for player one, and so on)
+    pub index: usize,
+    /// The name of the axis that moves the player's ship from side to side, e.g. "ship_p1"
+    pub move_axis: String,
+    /// The name of the axis that moves the player's ship up and down, e.g. "ship_vertical_p1"
+    pub vertical_axis: String,
+    /// The name of the action that fires the player's lasers, e.g. "fire_p1"
+    pub fire_action: String,
+    /// The controller slot of the player (0 for the first controller plugged in, and so on)
+    pub controller_id: u32,
+    /// The name of the controller axis that moves the player's ship from side to side, e.g. "pad_ship_p1"
+    pub pad_move_axis: String,
+    /// The name of the controller axis that moves the player's ship up and down,
+    /// e.g. "pad_ship_vertical_p1"
+    pub pad_vertical_axis: String,
+    /// The name of the controller action that fires the player's lasers, e.g. "pad_fire_p1"
+    pub pad_fire_action: String,
+}
+
+impl Player {
+    /// Creates the player with the given index, with the bindings named after them
+    pub fn new(index: usize) -> Player {
+        Player {
+            index,
+            move_axis: format!("ship_p{}", index + 1),
+            vertical_axis: format!("ship_vertical_p{}", index + 1),
+            fire_action: format!("fire_p{}", index + 1),
+            controller_id: index as u32,
+            pad_move_axis: format!("pad_ship_p{}", index + 1),
+            pad_vertical_axis: format!("pad_ship_vertical_p{}", index + 1),
+            pad_fire_action: format!("pad_fire_p{}", index + 1),
+        }
+    }
+}
+
+impl Component for Player {
+    type Storage = DenseVecStorage<Self>;
+}
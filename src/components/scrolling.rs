@@ -0,0 +1,19 @@
+use amethyst::ecs::prelude::{Component, DenseVecStorage};
+
+/// A component for the pieces of the background, which scroll down the screen
+///
+/// Once a piece has scrolled off the bottom of the screen,
+/// it jumps back up by the wrap height, so its layer never runs out.
+#[derive(Clone)]
+pub struct Scrolling {
+    /// How fast the piece scrolls down the screen
+    pub speed: f32,
+    /// How far the piece jumps back up once it has scrolled off the bottom of the screen
+    pub wrap_height: f32,
+    /// Half the height of the piece, so we know when it is completely off the screen
+    pub half_height: f32,
+}
+
+impl Component for Scrolling {
+    type Storage = DenseVecStorage<Self>;
+}
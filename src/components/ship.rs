@@ -1,22 +1,87 @@
+use amethyst::core::math::Vector2;
 use amethyst::ecs::prelude::{Component, DenseVecStorage};
+use serde_derive::{Deserialize, Serialize};
+
+/// What the player's ship is currently doing
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+pub enum ShipStatus {
+    /// The ship is flying and can be hit by asteroids
+    Active,
+    /// The ship has been destroyed and is waiting to respawn
+    Destroyed {
+        /// How much time in seconds is left before the ship reappears
+        respawn_timer: f32,
+    },
+    /// The ship has just respawned, and asteroids pass through it
+    Invulnerable {
+        /// How much time in seconds is left before the ship can be hit again
+        invulnerable_timer: f32,
+    },
+}
 
 /// The component for the player's space ship
 ///
-/// We store the width and height with the ship
+/// We store the width, height and handling of the ship
+/// (taken from the ship the player chose from the [roster](../struct.ShipRoster.html))
 /// even though they are constants, to make them readily
 /// available.
 ///
 /// In a proper COP design, you only store what will be
 /// modified by a system.
 pub struct Ship {
-    /// Current horizontal speed of the ship
-    pub velocity: f32,
+    /// Current velocity of the ship, in world coordinates
+    /// (positive x is to the right, positive y is up the screen)
+    pub velocity: Vector2<f32>,
     /// The width of the ship sprite
     pub width: f32,
     /// The height of the ship sprite
     pub height: f32,
-    /// How much time in seconds has passed since the last time the laser was fired
+    /// The effect each key press has on the ship's speed
+    pub thrust: f32,
+    /// The fastest the ship can go
+    pub max_speed: f32,
+    /// How long in seconds to wait after firing a laser before the ship can fire again
+    pub trigger_reset_timeout: f32,
+    /// How much time in seconds is left before the ship can fire again
     pub trigger_reset_timer: f32,
+    /// Whether the ship is flying, destroyed or invulnerable
+    pub status: ShipStatus,
+}
+
+impl Ship {
+    /// Whether or not the ship can be hit by an asteroid
+    pub fn is_vulnerable(&self) -> bool {
+        self.status == ShipStatus::Active
+    }
+
+    /// Whether or not the ship has been destroyed and is waiting to respawn
+    pub fn is_destroyed(&self) -> bool {
+        match self.status {
+            ShipStatus::Destroyed { .. } => true,
+            _ => false,
+        }
+    }
+
+    /// The ship's power-ups that are running, each with the message ID of its name
+    /// and the time left on it (sec)
+    ///
+    /// So far the only one is the shield the ship gets when it respawns.
+    pub fn power_up_timers(&self) -> Vec<(&'static str, f32)> {
+        match self.status {
+            ShipStatus::Invulnerable { invulnerable_timer } => {
+                vec![("hud-shield", invulnerable_timer)]
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    /// How far the ship's weapon has recharged since it last fired, from 0 (just fired) to 1 (ready)
+    pub fn weapon_readiness(&self) -> f32 {
+        if self.trigger_reset_timeout <= 0.0 {
+            return 1.0;
+        }
+        (1.0 - self.trigger_reset_timer / self.trigger_reset_timeout).max(0.0).min(1.0)
+    }
 }
 
 impl Component for Ship {
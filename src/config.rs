@@ -2,52 +2,527 @@
 
 use amethyst::config::Config;
 use lazy_static::lazy_static;
+use rand::Rng;
 use serde_derive::{Deserialize, Serialize};
 
+use crate::backgrounds::BACKGROUNDS;
+use crate::prefabs::PREFABS;
+use crate::roster::SHIP_ROSTER;
+
+/// A range of values the game picks from at random, e.g. an asteroid's spin
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct ValueRange {
+    /// the smallest value in the range
+    pub min: f32,
+    /// the largest value in the range
+    pub max: f32,
+}
+
+impl ValueRange {
+    /// Picks a value in the range at random
+    pub fn sample<R: Rng>(&self, random_number_generator: &mut R) -> f32 {
+        self.min + random_number_generator.gen::<f32>() * (self.max - self.min)
+    }
+}
+
+/// What happens to an object that reaches one of the side walls of the arena
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+pub enum WallBehaviour {
+    /// The object disappears off one side and comes back on the other
+    Wrap,
+    /// The object bounces off the wall
+    Bounce,
+}
+
+impl Default for WallBehaviour {
+    fn default() -> Self {
+        WallBehaviour::Wrap
+    }
+}
+
+/// What happens to the player's ship when it reaches the edge of its play area
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+pub enum ShipEdgeBehaviour {
+    /// The ship bounces off the edge, as if it were a wall
+    Bounce,
+    /// The ship stops dead at the edge
+    Clamp,
+}
+
+impl Default for ShipEdgeBehaviour {
+    fn default() -> Self {
+        ShipEdgeBehaviour::Bounce
+    }
+}
+
+/// How the joystick moves the player's ship
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+pub enum MovementModel {
+    /// The joystick applies thrust, and the ship keeps its speed until thrust is applied the other way
+    /// (see [InertialMovement](struct.InertialMovement.html))
+    Inertial,
+    /// The joystick applies thrust, and drag slows the ship down when the joystick is let go
+    /// (see [DampedMovement](struct.DampedMovement.html))
+    Damped,
+    /// The joystick sets the ship's speed directly, arcade-style
+    /// (see [DirectMovement](struct.DirectMovement.html))
+    Direct,
+}
+
+impl Default for MovementModel {
+    fn default() -> Self {
+        MovementModel::Inertial
+    }
+}
+
+/// The tunables of the [inertial](enum.MovementModel.html#variant.Inertial) movement model
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+pub struct InertialMovement {
+    /// the multiple of the ship's thrust applied by the joystick
+    pub thrust: f32,
+}
+
+impl Default for InertialMovement {
+    fn default() -> Self {
+        InertialMovement { thrust: 1.0 }
+    }
+}
+
+/// The tunables of the [damped](enum.MovementModel.html#variant.Damped) movement model
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+pub struct DampedMovement {
+    /// the multiple of the ship's thrust applied by the joystick
+    pub thrust: f32,
+    /// how much of its speed the ship loses each second
+    pub drag: f32,
+    /// the multiple of the ship's maximum speed allowed
+    pub max_speed: f32,
+}
+
+impl Default for DampedMovement {
+    fn default() -> Self {
+        DampedMovement {
+            thrust: 2.0,
+            drag: 2.0,
+            max_speed: 1.0,
+        }
+    }
+}
+
+/// The tunables of the [direct](enum.MovementModel.html#variant.Direct) movement model
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+pub struct DirectMovement {
+    /// the multiple of the ship's maximum speed reached with the joystick pushed all the way
+    pub max_speed: f32,
+    /// roughly how long in seconds the ship takes to get up to the speed the joystick asks for
+    /// (0.0 means instantly)
+    pub response_time: f32,
+}
+
+impl Default for DirectMovement {
+    fn default() -> Self {
+        DirectMovement {
+            max_speed: 1.0,
+            response_time: 0.0,
+        }
+    }
+}
+
+/// How the player's ship handles along one axis (horizontal or vertical)
+///
+/// Thrust and maximum speed are multiples of those of the ship
+/// chosen from the [roster](struct.ShipRoster.html)
+/// and of those of the [movement model](enum.MovementModel.html),
+/// so every ship keeps its own character along both axes.
+/// The drag is added to that of the damped movement model, and the other models ignore it;
+/// the inertial model ignores the maximum speed too.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+pub struct AxisHandling {
+    /// the multiple of the ship's thrust applied along this axis
+    pub thrust: f32,
+    /// how much of its speed along this axis the ship loses each second (0.0 to 1.0),
+    /// with the damped movement model
+    pub drag: f32,
+    /// the multiple of the ship's maximum speed allowed along this axis
+    pub max_speed: f32,
+}
+
+impl Default for AxisHandling {
+    fn default() -> Self {
+        AxisHandling {
+            thrust: 1.0,
+            drag: 0.0,
+            max_speed: 1.0,
+        }
+    }
+}
+
 /// "Constants" that control the game mechanics
+///
+/// Note the handling of each ship (thrust, fire rate, weapon) is set
+/// in the [ship roster](struct.ShipRoster.html) instead.
 #[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
 pub struct GameConfiguration {
-    /// The effect each key press has on the ship's speed
-    #[serde(default)]
-    pub ship_thrust: f32,
     /// asteroid vertical velocity
     #[serde(default)]
     pub asteroid_velocity: f32,
+    /// range of extra vertical velocity added to each asteroid when it is launched
+    #[serde(default)]
+    pub asteroid_extra_velocity: ValueRange,
+    /// range of horizontal velocity given to each asteroid when it is launched
+    #[serde(default)]
+    pub asteroid_drift: ValueRange,
+    /// range of angular velocity (radians/sec) given to each asteroid when it is launched
+    #[serde(default)]
+    pub asteroid_spin: ValueRange,
+    /// what happens when an asteroid drifts into the side walls
+    #[serde(default)]
+    pub asteroid_wall_behaviour: WallBehaviour,
     /// how long to wait before the first asteroid falls (sec)
     #[serde(default)]
     pub wait_for_first_asteroid: f32,
     /// how close the asteroids are together
     #[serde(default)]
     pub asteroid_density: f32,
-    /// laser vertical velocity
+    /// how many laser hits a big asteroid can take
+    #[serde(default)]
+    pub big_asteroid_hit_points: u8,
+    /// how many laser hits a medium asteroid can take
+    #[serde(default)]
+    pub medium_asteroid_hit_points: u8,
+    /// how many laser hits a small asteroid can take
+    #[serde(default)]
+    pub small_asteroid_hit_points: u8,
+    /// how many laser hits a tiny asteroid can take
+    #[serde(default)]
+    pub tiny_asteroid_hit_points: u8,
+    /// how many fragments a destroyed asteroid splits into (at least two)
+    #[serde(default)]
+    pub asteroid_fragment_count: u8,
+    /// how fast the fragments of a destroyed asteroid fly apart
+    #[serde(default)]
+    pub asteroid_fragment_speed: f32,
+    /// how long an explosion lasts (sec)
+    #[serde(default)]
+    pub explosion_duration: f32,
+    /// how long to wait after the ship is destroyed before it reappears (sec)
+    #[serde(default)]
+    pub respawn_delay: f32,
+    /// how long the ship can't be hit after it reappears (sec)
+    #[serde(default)]
+    pub invulnerability_period: f32,
+    /// how quickly the ship blinks while it can't be hit (sec between blinks)
+    #[serde(default)]
+    pub invulnerability_blink_interval: f32,
+    /// how many players share the game (each with their own ship and key bindings)
+    #[serde(default)]
+    pub player_count: u8,
+    /// how many points a player scores for destroying an asteroid
+    #[serde(default)]
+    pub asteroid_points: u32,
+    /// how far an analog stick can move off centre before it counts (0.0 to 1.0)
+    #[serde(default)]
+    pub stick_deadzone: f32,
+    /// the power the stick reading is raised to:
+    /// 1.0 is linear, higher values give finer control near the centre
+    #[serde(default)]
+    pub stick_response_curve: f32,
+    /// whether or not the players can also move their ships up and down
     #[serde(default)]
-    pub laser_velocity: f32,
-    /// how long to wait after firing a laser before can fire again
+    pub ship_vertical_movement: bool,
+    /// how high up the arena the ships can go, as a fraction of the arena height
     #[serde(default)]
-    pub trigger_reset_timeout: f32,
+    pub ship_area_height: f32,
+    /// how the ships handle from side to side
+    #[serde(default)]
+    pub ship_horizontal_handling: AxisHandling,
+    /// how the ships handle up and down
+    #[serde(default)]
+    pub ship_vertical_handling: AxisHandling,
+    /// what happens when a ship reaches the edge of its play area
+    #[serde(default)]
+    pub ship_edge_behaviour: ShipEdgeBehaviour,
+    /// how the joystick moves the ships
+    #[serde(default)]
+    pub ship_movement_model: MovementModel,
+    /// the tunables of the inertial movement model
+    #[serde(default)]
+    pub inertial_movement: InertialMovement,
+    /// the tunables of the damped movement model
+    #[serde(default)]
+    pub damped_movement: DampedMovement,
+    /// the tunables of the direct movement model
+    #[serde(default)]
+    pub direct_movement: DirectMovement,
+    /// how much game time passes each frame (sec);
+    /// 0.0 means the game keeps up with the real time between frames instead.
+    /// Recordings always use a fixed timestep, so they can be replayed exactly.
+    #[serde(default)]
+    pub fixed_timestep: f32,
+    /// the width of the arena the game is played in, whatever the size of the window
+    #[serde(default)]
+    pub arena_width: f32,
+    /// the height of the arena the game is played in, whatever the size of the window
+    #[serde(default)]
+    pub arena_height: f32,
+    /// how many lives each player starts with
+    #[serde(default)]
+    pub starting_lives: u8,
+    /// the most lives a player can have (extra lives beyond this are lost)
+    #[serde(default)]
+    pub max_lives: u8,
+    /// the scores at which a player is awarded an extra life, in order
+    #[serde(default)]
+    pub extra_life_scores: Vec<u32>,
+    /// once past the last of the extra life scores, how many more points
+    /// earn each further extra life; 0 means no more extra lives
+    #[serde(default)]
+    pub extra_life_interval: u32,
+    /// how many asteroids a player must destroy in a row (without missing or being hit)
+    /// to raise their score multiplier by one; 0 means the multiplier stays at 1
+    #[serde(default)]
+    pub combo_step: u32,
+    /// the highest the score multiplier can go
+    #[serde(default)]
+    pub max_multiplier: u32,
+    /// how many asteroids must be destroyed to move on to the next wave;
+    /// 0 means the game stays on the first wave
+    #[serde(default)]
+    pub wave_asteroids: u32,
+    /// whether the debug overlay (collision boxes, FPS and entity counts) is shown
+    /// when the game starts; F3 shows or hides it during the game
+    #[serde(default)]
+    pub debug_overlay: bool,
+    /// whether every shot, hit, destroyed asteroid, lost or extra life and new wave is written
+    /// to "events.jsonl" in the player's data directory as it happens (see [telemetry](struct.SessionRecord.html))
+    #[serde(default)]
+    pub stream_telemetry_events: bool,
+    /// the language code of the language the game speaks until the player chooses another
+    /// (see [localisation](struct.Catalogues.html))
+    #[serde(default = "default_language")]
+    pub language: String,
+    /// the language code of the language whose messages stand in for any missing
+    /// from the chosen language's catalogue
+    #[serde(default = "default_language")]
+    pub fallback_language: String,
+    /// the UDP port a [network game](network/index.html) is hosted on
+    #[serde(default = "default_network_port")]
+    pub network_port: u16,
+    /// the address of the host to join, offered when joining a network game
+    #[serde(default = "default_network_join_address")]
+    pub network_join_address: String,
+    /// how many of the asteroids nearest player one's ship an [agent](agent/index.html) is shown each step
+    #[serde(default)]
+    pub agent_asteroid_count: usize,
+    /// how much an agent's reward drops when player one loses a life (in points)
+    #[serde(default)]
+    pub agent_life_penalty: f32,
 }
 
 // Default values
-pub const SHIP_THRUST: f32 = 0.02;
 pub const ASTEROID_VELOCITY: f32 = 5.0;
-pub const LASER_VELOCITY: f32 = 60.0;
 pub const WAIT_FOR_FIRST_ASTEROID: f32 = 2.0;
 pub const ASTEROID_DENSITY: f32 = 0.3;
-pub const TRIGGER_RESET_TIMEOUT: f32 = 0.5;
+pub const PLAYER_COUNT: u8 = 1;
+pub const ASTEROID_POINTS: u32 = 10;
+pub const STICK_DEADZONE: f32 = 0.15;
+pub const STICK_RESPONSE_CURVE: f32 = 2.0;
+pub const SHIP_AREA_HEIGHT: f32 = 0.3;
+pub const BIG_ASTEROID_HIT_POINTS: u8 = 4;
+pub const MEDIUM_ASTEROID_HIT_POINTS: u8 = 2;
+pub const SMALL_ASTEROID_HIT_POINTS: u8 = 1;
+pub const TINY_ASTEROID_HIT_POINTS: u8 = 1;
+pub const ASTEROID_FRAGMENT_COUNT: u8 = 2;
+pub const ASTEROID_FRAGMENT_SPEED: f32 = 30.0;
+pub const EXPLOSION_DURATION: f32 = 0.5;
+pub const RESPAWN_DELAY: f32 = 1.5;
+pub const INVULNERABILITY_PERIOD: f32 = 2.0;
+pub const INVULNERABILITY_BLINK_INTERVAL: f32 = 0.1;
+pub const ARENA_WIDTH: f32 = 500.0;
+pub const ARENA_HEIGHT: f32 = 500.0;
+pub const STARTING_LIVES: u8 = 3;
+pub const MAX_LIVES: u8 = 5;
+pub const COMBO_STEP: u32 = 5;
+pub const MAX_MULTIPLIER: u32 = 4;
+pub const WAVE_ASTEROIDS: u32 = 30;
+pub const LANGUAGE: &str = "en";
+pub const NETWORK_PORT: u16 = 7777;
+pub const NETWORK_JOIN_ADDRESS: &str = "127.0.0.1:7777";
+pub const AGENT_ASTEROID_COUNT: usize = 8;
+pub const AGENT_LIFE_PENALTY: f32 = 100.0;
 
 impl Default for GameConfiguration {
     fn default() -> Self {
         GameConfiguration {
-            ship_thrust: SHIP_THRUST,
             asteroid_velocity: ASTEROID_VELOCITY,
+            asteroid_extra_velocity: ValueRange::default(),
+            asteroid_drift: ValueRange::default(),
+            asteroid_spin: ValueRange::default(),
+            asteroid_wall_behaviour: WallBehaviour::default(),
             asteroid_density: ASTEROID_DENSITY,
+            player_count: PLAYER_COUNT,
+            asteroid_points: ASTEROID_POINTS,
+            stick_deadzone: STICK_DEADZONE,
+            stick_response_curve: STICK_RESPONSE_CURVE,
+            ship_vertical_movement: false,
+            ship_area_height: SHIP_AREA_HEIGHT,
+            ship_horizontal_handling: AxisHandling::default(),
+            ship_vertical_handling: AxisHandling::default(),
+            ship_edge_behaviour: ShipEdgeBehaviour::default(),
+            ship_movement_model: MovementModel::default(),
+            inertial_movement: InertialMovement::default(),
+            damped_movement: DampedMovement::default(),
+            direct_movement: DirectMovement::default(),
+            fixed_timestep: 0.0,
+            arena_width: ARENA_WIDTH,
+            arena_height: ARENA_HEIGHT,
+            starting_lives: STARTING_LIVES,
+            max_lives: MAX_LIVES,
+            extra_life_scores: Vec::new(),
+            extra_life_interval: 0,
+            combo_step: COMBO_STEP,
+            max_multiplier: MAX_MULTIPLIER,
+            wave_asteroids: WAVE_ASTEROIDS,
+            debug_overlay: false,
+            stream_telemetry_events: false,
+            language: default_language(),
+            fallback_language: default_language(),
+            network_port: default_network_port(),
+            network_join_address: default_network_join_address(),
+            agent_asteroid_count: AGENT_ASTEROID_COUNT,
+            agent_life_penalty: AGENT_LIFE_PENALTY,
             wait_for_first_asteroid: WAIT_FOR_FIRST_ASTEROID,
-            laser_velocity: LASER_VELOCITY,
-            trigger_reset_timeout: TRIGGER_RESET_TIMEOUT,
+            big_asteroid_hit_points: BIG_ASTEROID_HIT_POINTS,
+            medium_asteroid_hit_points: MEDIUM_ASTEROID_HIT_POINTS,
+            small_asteroid_hit_points: SMALL_ASTEROID_HIT_POINTS,
+            tiny_asteroid_hit_points: TINY_ASTEROID_HIT_POINTS,
+            asteroid_fragment_count: ASTEROID_FRAGMENT_COUNT,
+            asteroid_fragment_speed: ASTEROID_FRAGMENT_SPEED,
+            explosion_duration: EXPLOSION_DURATION,
+            respawn_delay: RESPAWN_DELAY,
+            invulnerability_period: INVULNERABILITY_PERIOD,
+            invulnerability_blink_interval: INVULNERABILITY_BLINK_INTERVAL,
         }
     }
 }
 
+/// The default language, for serde
+fn default_language() -> String {
+    LANGUAGE.to_string()
+}
+
+/// The default port network games are hosted on, for serde
+fn default_network_port() -> u16 {
+    NETWORK_PORT
+}
+
+/// The default address of the host to join, for serde
+fn default_network_join_address() -> String {
+    NETWORK_JOIN_ADDRESS.to_string()
+}
+
+impl GameConfiguration {
+    /// The number of players, which is always at least one
+    pub fn player_count(&self) -> usize {
+        usize::from(self.player_count.max(1))
+    }
+
+    /// The number of lives each player starts with, which is always at least one
+    pub fn starting_lives(&self) -> u8 {
+        self.starting_lives.max(1)
+    }
+
+    /// The most lives a player can have, which is never fewer than they start with
+    pub fn max_lives(&self) -> u8 {
+        self.max_lives.max(self.starting_lives())
+    }
+
+    /// The score at which a player is awarded the given extra life
+    /// (0 being the first extra life), or `None` if there are no more extra lives
+    ///
+    /// The first extra lives come at the extra life scores;
+    /// after those, one comes every extra life interval.
+    pub fn extra_life_score(&self, extra_life: usize) -> Option<u32> {
+        if let Some(score) = self.extra_life_scores.get(extra_life) {
+            return Some(*score);
+        }
+        if self.extra_life_interval == 0 {
+            return None;
+        }
+        let last_score = self.extra_life_scores.last().copied().unwrap_or_default();
+        let intervals = (extra_life - self.extra_life_scores.len() + 1) as u32;
+        last_score.checked_add(self.extra_life_interval.checked_mul(intervals)?)
+    }
+
+    /// The score multiplier of a player who has destroyed the given number of asteroids in a row
+    ///
+    /// The multiplier starts at 1, and goes up by one every combo step, up to the max multiplier.
+    pub fn score_multiplier(&self, combo: u32) -> u32 {
+        if self.combo_step == 0 {
+            return 1;
+        }
+        (1 + combo / self.combo_step).min(self.max_multiplier.max(1))
+    }
+
+    /// Shapes a raw analog stick reading (-1.0 to 1.0)
+    /// with the stick deadzone and response curve
+    ///
+    /// Readings inside the deadzone come out as zero;
+    /// the rest of the stick's travel is stretched back out to the full range,
+    /// then bent by the response curve, keeping the reading's sign.
+    pub fn shape_stick(&self, value: f32) -> f32 {
+        let deadzone = self.stick_deadzone.max(0.0).min(0.99);
+        let magnitude = ((value.abs() - deadzone) / (1.0 - deadzone)).max(0.0).min(1.0);
+        magnitude.powf(self.stick_response_curve.max(0.1)).copysign(value)
+    }
+}
+
+/// A fingerprint of the game rules: the game configuration, ship roster, backgrounds and prefabs
+///
+/// Replays store it, so we can tell when a replay was recorded
+/// with different game rules from the ones we have now.
+///
+/// Only the values that change how the game plays are hashed,
+/// so the layout of the files (comments, spacing, line endings) doesn't count,
+/// and neither do the settings that only change what the players see or how they connect
+/// (see [gameplay_hash](fn.gameplay_hash.html)).
+pub fn configuration_hash() -> u64 {
+    *CONFIGURATION_HASH
+}
+
+/// The hash of the game rules, with the given game configuration
+///
+/// The language, debug overlay, telemetry, network and agent settings are left out:
+/// two copies of the game that differ only in those still play the same game.
+///
+/// The values are hashed as JSON, with the FNV-1a hash rather than the standard library's hasher,
+/// because its results don't change between Rust versions.
+pub fn gameplay_hash(game_configuration: &GameConfiguration) -> u64 {
+    let gameplay_configuration = GameConfiguration {
+        debug_overlay: false,
+        stream_telemetry_events: false,
+        language: String::new(),
+        fallback_language: String::new(),
+        network_port: 0,
+        network_join_address: String::new(),
+        agent_asteroid_count: 0,
+        agent_life_penalty: 0.0,
+        ..game_configuration.clone()
+    };
+    let rules = (
+        &gameplay_configuration,
+        &*SHIP_ROSTER,
+        &*BACKGROUNDS,
+        &*PREFABS,
+    );
+    serde_json::to_vec(&rules)
+        .unwrap_or_default()
+        .iter()
+        .fold(0xcbf2_9ce4_8422_2325, |hash: u64, byte| {
+            (hash ^ u64::from(*byte)).wrapping_mul(0x0100_0000_01b3)
+        })
+}
+
 lazy_static! {
     /// The actual values for the [game configuration](struct.GameConfiguration.html)</a>.
     ///
@@ -67,4 +542,71 @@ lazy_static! {
         GameConfiguration::load(&game_config_path).unwrap()
     };
 
+    /// The [configuration hash](fn.configuration_hash.html), worked out once
+    static ref CONFIGURATION_HASH: u64 = gameplay_hash(&GAME_CONFIGURATION);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn presentation_settings_leave_the_hash_alone() {
+        let mut game_configuration = GAME_CONFIGURATION.clone();
+        game_configuration.language = "fr".to_string();
+        game_configuration.debug_overlay = !game_configuration.debug_overlay;
+        game_configuration.stream_telemetry_events = !game_configuration.stream_telemetry_events;
+        game_configuration.network_port = game_configuration.network_port.wrapping_add(1);
+        assert_eq!(gameplay_hash(&game_configuration), configuration_hash());
+    }
+
+    /// The game configuration, with the given extra life scores and interval
+    fn extra_lives_at(extra_life_scores: Vec<u32>, extra_life_interval: u32) -> GameConfiguration {
+        GameConfiguration {
+            extra_life_scores,
+            extra_life_interval,
+            ..GAME_CONFIGURATION.clone()
+        }
+    }
+
+    #[test]
+    fn extra_lives_come_at_the_scores_then_every_interval() {
+        let game_configuration = extra_lives_at(vec![100, 500], 1000);
+        let scores: Vec<Option<u32>> = (0..4)
+            .map(|extra_life| game_configuration.extra_life_score(extra_life))
+            .collect();
+        assert_eq!(scores, vec![Some(100), Some(500), Some(1500), Some(2500)]);
+    }
+
+    #[test]
+    fn extra_lives_come_every_interval_without_any_scores() {
+        let game_configuration = extra_lives_at(Vec::new(), 1000);
+        assert_eq!(game_configuration.extra_life_score(0), Some(1000));
+        assert_eq!(game_configuration.extra_life_score(2), Some(3000));
+    }
+
+    #[test]
+    fn extra_lives_stop_after_the_scores_without_an_interval() {
+        let game_configuration = extra_lives_at(vec![100], 0);
+        assert_eq!(game_configuration.extra_life_score(0), Some(100));
+        assert_eq!(game_configuration.extra_life_score(1), None);
+        assert_eq!(extra_lives_at(Vec::new(), 0).extra_life_score(0), None);
+    }
+
+    #[test]
+    fn extra_lives_stop_when_the_score_would_overflow() {
+        let game_configuration = extra_lives_at(vec![u32::MAX - 10], 100);
+        assert_eq!(game_configuration.extra_life_score(0), Some(u32::MAX - 10));
+        assert_eq!(game_configuration.extra_life_score(1), None);
+        let game_configuration = extra_lives_at(Vec::new(), u32::MAX / 2);
+        assert_eq!(game_configuration.extra_life_score(1), Some(u32::MAX - 1));
+        assert_eq!(game_configuration.extra_life_score(2), None);
+    }
+
+    #[test]
+    fn gameplay_settings_change_the_hash() {
+        let mut game_configuration = GAME_CONFIGURATION.clone();
+        game_configuration.asteroid_velocity += 1.0;
+        assert_ne!(gameplay_hash(&game_configuration), configuration_hash());
+    }
 }
@@ -0,0 +1,248 @@
+use amethyst::config::Config;
+use amethyst::ecs::prelude::Entity;
+use amethyst::input::{
+    is_close_requested, Bindings, Button, InputEvent, InputHandler, StringBindings,
+};
+use amethyst::prelude::*;
+use amethyst::ui::UiText;
+use amethyst::winit::VirtualKeyCode;
+
+use crate::entities::load_font;
+use crate::key_bindings::{
+    binding_slots, bound_buttons, find_conflict, rebind, save_user_key_bindings,
+    shipped_bindings_path, user_bindings_path, BindingSlot,
+};
+use crate::menu_state::{create_text, MenuState};
+use crate::resources::Localisation;
+
+const ROW_HEIGHT: f32 = 22.;
+const ROW_FONT_SIZE: f32 = 14.;
+const FIRST_ROW_Y: f32 = 200.;
+const SELECTED_COLOUR: [f32; 4] = [1.0, 0.8, 0.2, 1.0]; // amber
+const ROW_COLOUR: [f32; 4] = [1.0, 1.0, 1.0, 1.0]; // white
+
+/// The controls screen, where the players rebind their keys and buttons
+///
+/// This state demonstrates several standard patterns:
+///
+/// 1. Listing and changing the input handler's bindings while the game runs
+/// 2. Capturing the next key or button pressed, through input events
+/// 3. Saving the player's choices to a file of their own
+///    (see [key bindings](fn.load_key_bindings.html))
+///
+/// Every action and every end of an emulated axis is listed.
+/// W / S (or the arrow keys) choose a binding, Return captures a new key or button for it,
+/// R restores the shipped bindings and Escape saves and goes back to the ship-select screen.
+/// A key that is already bound elsewhere is refused, naming the binding it belongs to.
+#[derive(Default)]
+pub struct ControlsState {
+    /// Everything a key can be bound to, in the order listed
+    slots: Vec<BindingSlot>,
+    /// The position in the list of the binding currently selected
+    selected: usize,
+    /// Whether or not we are waiting for the new key or button of the selected binding
+    is_capturing: bool,
+    /// Whether or not the bindings have changed since they were loaded
+    is_changed: bool,
+    /// The UI entity of each row of the list
+    rows: Vec<Entity>,
+    /// The UI entity telling the player what's going on
+    status: Option<Entity>,
+    /// All the UI entities of the screen, so we can delete them when we leave
+    ui_entities: Vec<Entity>,
+}
+
+impl SimpleState for ControlsState {
+    fn on_start(&mut self, state_data: StateData<'_, GameData<'_, '_>>) {
+        let world = state_data.world;
+        let font = load_font(world);
+        self.slots = {
+            let input = world.read_resource::<InputHandler<StringBindings>>();
+            binding_slots(&input.bindings)
+        };
+
+        let title_text = world.read_resource::<Localisation>().text("controls-title");
+        let title = create_text(world, &font, "controls_title", &title_text, 260., 30.);
+        let status = create_text(world, &font, "controls_status", "", -280., ROW_FONT_SIZE);
+        self.rows = (0..self.slots.len())
+            .map(|row| {
+                create_text(
+                    world,
+                    &font,
+                    &format!("controls_row_{}", row),
+                    "",
+                    FIRST_ROW_Y - row as f32 * ROW_HEIGHT,
+                    ROW_FONT_SIZE,
+                )
+            })
+            .collect();
+        self.status = Some(status);
+        self.ui_entities = vec![title, status];
+        self.ui_entities.extend(self.rows.iter().copied());
+        self.show_bindings(world);
+        self.show_status(world, "controls-instructions", &[]);
+    }
+
+    fn on_stop(&mut self, state_data: StateData<'_, GameData<'_, '_>>) {
+        let _result = state_data.world.delete_entities(&self.ui_entities);
+        self.ui_entities.clear();
+    }
+
+    fn handle_event(
+        &mut self,
+        state_data: StateData<'_, GameData<'_, '_>>,
+        event: StateEvent,
+    ) -> SimpleTrans {
+        let world = state_data.world;
+        match event {
+            StateEvent::Window(event) if is_close_requested(&event) => Trans::Quit,
+            // scan codes come along with every key press, so we stick to the key codes
+            StateEvent::Input(InputEvent::ButtonPressed(Button::ScanCode(_))) => Trans::None,
+            StateEvent::Input(InputEvent::ButtonPressed(button)) if self.is_capturing => {
+                self.is_capturing = false;
+                if button == Button::Key(VirtualKeyCode::Escape) {
+                    self.show_status(world, "controls-cancelled", &[]);
+                } else {
+                    self.capture(world, button);
+                }
+                self.show_bindings(world);
+                Trans::None
+            }
+            StateEvent::Input(InputEvent::ButtonPressed(Button::Key(key))) => {
+                self.navigate(world, key)
+            }
+            _ => Trans::None,
+        }
+    }
+
+    // This code tells Amethyst to run all the systems in your game data.
+    fn update(&mut self, state_data: &mut StateData<'_, GameData<'_, '_>>) -> SimpleTrans {
+        state_data.data.update(&state_data.world);
+        Trans::None
+    }
+}
+
+impl ControlsState {
+    /// Moves around the list, starts capturing, restores or leaves, depending on the key
+    fn navigate(&mut self, world: &mut World, key: VirtualKeyCode) -> SimpleTrans {
+        let slot_count = self.slots.len().max(1);
+        match key {
+            VirtualKeyCode::W | VirtualKeyCode::Up => {
+                self.selected = (self.selected + slot_count - 1) % slot_count;
+            }
+            VirtualKeyCode::S | VirtualKeyCode::Down => {
+                self.selected = (self.selected + 1) % slot_count;
+            }
+            VirtualKeyCode::Return if !self.slots.is_empty() => {
+                self.is_capturing = true;
+                let binding = self.slots[self.selected].name();
+                self.show_status(world, "controls-capture", &[("binding", binding)]);
+            }
+            VirtualKeyCode::R => self.restore_shipped_bindings(world),
+            VirtualKeyCode::Escape => {
+                if self.is_changed {
+                    let bindings = world
+                        .read_resource::<InputHandler<StringBindings>>()
+                        .bindings
+                        .clone();
+                    if let Err(message) = save_user_key_bindings(&bindings) {
+                        log::error!("{}", message);
+                    }
+                }
+                return Trans::Switch(Box::new(MenuState::default()));
+            }
+            _ => {}
+        }
+        self.show_bindings(world);
+        Trans::None
+    }
+
+    /// Binds the button to the selected binding, unless it's already bound elsewhere
+    fn capture(&mut self, world: &mut World, button: Button) {
+        let slot = self.slots[self.selected].clone();
+        let button_name = format!("{:?}", button);
+        let result = {
+            let mut input = world.write_resource::<InputHandler<StringBindings>>();
+            match find_conflict(&input.bindings, button, &slot) {
+                Some(other_slot) => Err(world.read_resource::<Localisation>().format(
+                    "controls-conflict",
+                    &[("button", button_name.clone()), ("binding", other_slot.name())],
+                )),
+                None => rebind(&mut input.bindings, &slot, button),
+            }
+        };
+        match result {
+            Ok(()) => {
+                self.is_changed = true;
+                let arguments = [("binding", slot.name()), ("button", button_name)];
+                self.show_status(world, "controls-rebound", &arguments);
+            }
+            Err(message) => self.show_message(world, message),
+        }
+    }
+
+    /// Goes back to the bindings shipped with the game,
+    /// forgetting the player's own bindings file
+    fn restore_shipped_bindings(&mut self, world: &mut World) {
+        match Bindings::<StringBindings>::load(shipped_bindings_path()) {
+            Ok(bindings) => {
+                world.write_resource::<InputHandler<StringBindings>>().bindings = bindings;
+                let _result = std::fs::remove_file(user_bindings_path());
+                self.is_changed = false;
+                self.show_status(world, "controls-restored", &[]);
+            }
+            Err(error) => {
+                let arguments = [("error", error.to_string())];
+                self.show_status(world, "controls-restore-failed", &arguments);
+            }
+        }
+    }
+
+    /// Writes each binding into its row, highlighting the selected one
+    fn show_bindings(&self, world: &mut World) {
+        let rows: Vec<(String, [f32; 4])> = {
+            let input = world.read_resource::<InputHandler<StringBindings>>();
+            self.slots
+                .iter()
+                .enumerate()
+                .map(|(row, slot)| {
+                    let buttons: Vec<String> = bound_buttons(&input.bindings, slot)
+                        .iter()
+                        .map(|button| format!("{:?}", button))
+                        .collect();
+                    let is_selected = row == self.selected;
+                    let marker = match (is_selected, self.is_capturing) {
+                        (true, true) => "? ",
+                        (true, false) => "> ",
+                        _ => "",
+                    };
+                    let text = format!("{}{}: {}", marker, slot.name(), buttons.join(", "));
+                    let colour = if is_selected { SELECTED_COLOUR } else { ROW_COLOUR };
+                    (text, colour)
+                })
+                .collect()
+        };
+        let mut ui_texts = world.write_storage::<UiText>();
+        for (row_entity, (text, colour)) in self.rows.iter().zip(rows) {
+            if let Some(ui_text) = ui_texts.get_mut(*row_entity) {
+                ui_text.text = text;
+                ui_text.color = colour;
+            }
+        }
+    }
+
+    /// Shows the given message at the bottom of the screen, in the chosen language
+    fn show_status(&self, world: &mut World, id: &str, arguments: &[(&str, String)]) {
+        let message = world.read_resource::<Localisation>().format(id, arguments);
+        self.show_message(world, message);
+    }
+
+    /// Shows the given text at the bottom of the screen, as it is
+    fn show_message(&self, world: &mut World, message: String) {
+        if let Some(status) = self.status {
+            if let Some(ui_text) = world.write_storage::<UiText>().get_mut(status) {
+                ui_text.text = message;
+            }
+        }
+    }
+}
@@ -17,26 +17,42 @@
 //! (Note the underlying world actually maintains a list of deleted
 //! (destroyed) entities and re-uses the slots when you create new ones,
 //! so both methods have the same underlying implementation.)
+//!
+//! The one exception is asteroid fragments: when a larger asteroid is destroyed
+//! it splits into smaller fragments, which are created on the fly
+//! with [split_asteroid](fn.split_asteroid.html), like [lasers](../laser/index.html),
+//! and deleted once they are destroyed or fall off the screen.
 
 use amethyst::assets::Handle;
-use amethyst::core::math::Vector3;
+use amethyst::core::math::{Vector2, Vector3};
 use amethyst::core::transform::Transform;
-use amethyst::ecs::prelude::{Entity, World, WorldExt};
+use amethyst::ecs::prelude::{Entities, Entity, LazyUpdate, World, WorldExt};
 use amethyst::prelude::Builder;
 use amethyst::renderer::SpriteRender;
 use amethyst::renderer::SpriteSheet;
-use rand::prelude::{thread_rng, Rng, ThreadRng};
+use rand::rngs::StdRng;
+use rand::Rng;
 
-use crate::components::Asteroid;
+use crate::components::{Asteroid, AsteroidSize};
 use crate::config::GAME_CONFIGURATION;
-use amethyst::window::ScreenDimensions;
+use crate::prefabs::PREFABS;
+use crate::resources::{Arena, AsteroidResource, GameRandom};
+
+/// The sizes of asteroid that fall from the top of the screen.
+/// Tiny asteroids only appear as fragments of larger ones.
+const FALLING_ASTEROID_SIZES: [AsteroidSize; 3] =
+    [AsteroidSize::Big, AsteroidSize::Medium, AsteroidSize::Small];
 
 /// Initialises a hundred asteroid objects somewhere above the arena.
 ///
 /// The method first sets up the resources each entity will need,
-/// then uses a random number generator and a location function
+/// then uses the game's seeded random number generator and a location function
 /// to position the asteroids above the screen top.
 ///
+/// Each asteroid is given a random size (big, medium or small)
+/// and a random sprite of that size,
+/// then launched with a random velocity and spin by [launch_asteroid](fn.launch_asteroid.html).
+///
 /// This implements a pattern of creating a fixed number of entities
 /// and re-using them to make them appear endless. It is less wasteful
 /// than creating and destroying entities on the fly (like [lasers](../laser/index.html)).
@@ -47,42 +63,97 @@ pub fn initialise_asteroids(
     world: &mut World,
     sprite_sheet_handle: Handle<SpriteSheet>,
 ) -> Vec<Entity> {
-    //let (mesh, background) = png_mesh_and_material("PNG/Meteors/meteorBrown_med1.png", [43.0,43.0], world);
-    let asteroid = Asteroid {
-        velocity: GAME_CONFIGURATION.asteroid_velocity,
-        width: 43.0,
-        height: 43.0,
-        is_destroyed: false,
-    };
-    let (screen_width, screen_height) = {
-        let screen_dimensions = (*world.read_resource::<ScreenDimensions>()).clone();
-        (screen_dimensions.width(), screen_dimensions.height())
-    };
+    world.insert(AsteroidResource {
+        sprite_sheet: sprite_sheet_handle.clone(),
+    });
 
-    let mut rng = thread_rng();
+    let (arena_width, arena_height) = {
+        let arena = world.read_resource::<Arena>();
+        (arena.width, arena.height)
+    };
 
-    let numbers = 0..;
-    let range = numbers.take(100);
-    range
-        .map(|_number| {
-            let local_transform = locate_asteroid(&asteroid, screen_width, screen_height, &mut rng);
+    // pick all the asteroids first, since we can't create entities
+    // while we're borrowing the random number generator from the world
+    let asteroids: Vec<_> = {
+        let mut game_random = world.write_resource::<GameRandom>();
+        let rng = &mut game_random.rng;
+        (0..100)
+            .map(|_number| {
+                let size = FALLING_ASTEROID_SIZES[rng.gen_range(0..FALLING_ASTEROID_SIZES.len())];
+                let (mut asteroid, sprite_render) =
+                    create_asteroid(size, &sprite_sheet_handle, rng);
+                launch_asteroid(&mut asteroid, rng);
+                let local_transform = locate_asteroid(&asteroid, arena_width, arena_height, rng);
+                (asteroid, local_transform, sprite_render)
+            })
+            .collect()
+    };
 
+    asteroids
+        .into_iter()
+        .map(|(asteroid, local_transform, sprite_render)| {
             world
                 .create_entity()
-                //        .with(mesh.clone())
-                //        .with(background.clone())
-                .with(asteroid.clone())
+                .with(asteroid)
                 .with(local_transform)
-                /*.with(GlobalTransform::default())*/
-                .with(SpriteRender {
-                    sprite_sheet: sprite_sheet_handle.clone(),
-                    sprite_number: 2,
-                })
+                .with(sprite_render)
                 .build()
         })
         .collect()
 }
 
+/// The number of laser hits an asteroid of the given size can take,
+/// as set in the [game configuration](../../struct.GameConfiguration.html).
+pub fn asteroid_hit_points(size: AsteroidSize) -> u8 {
+    match size {
+        AsteroidSize::Big => GAME_CONFIGURATION.big_asteroid_hit_points,
+        AsteroidSize::Medium => GAME_CONFIGURATION.medium_asteroid_hit_points,
+        AsteroidSize::Small => GAME_CONFIGURATION.small_asteroid_hit_points,
+        AsteroidSize::Tiny => GAME_CONFIGURATION.tiny_asteroid_hit_points,
+    }
+    .max(1)
+}
+
+/// Creates an asteroid component and sprite render for an asteroid of the given size.
+///
+/// The asteroid is picked at random from the [prefabs](../../struct.Prefabs.html) of that size,
+/// and the component takes its width and height from the chosen prefab.
+fn create_asteroid(
+    size: AsteroidSize,
+    sprite_sheet_handle: &Handle<SpriteSheet>,
+    random_number_generator: &mut StdRng,
+) -> (Asteroid, SpriteRender) {
+    let prefabs = PREFABS.asteroids_of_size(size);
+    let prefab = prefabs[random_number_generator.gen_range(0..prefabs.len())];
+    let asteroid = Asteroid {
+        velocity: Vector2::new(0.0, -GAME_CONFIGURATION.asteroid_velocity),
+        angular_velocity: 0.0,
+        width: prefab.width,
+        height: prefab.height,
+        size,
+        hit_points: asteroid_hit_points(size),
+        is_fragment: false,
+        is_destroyed: false,
+    };
+    (asteroid, prefab.sprite_render(sprite_sheet_handle))
+}
+
+/// Gives the asteroid a random velocity and spin.
+///
+/// Each is picked from the ranges set in the [game configuration](../../struct.GameConfiguration.html),
+/// so the asteroids drift diagonally and spin as they fall.
+pub fn launch_asteroid(asteroid: &mut Asteroid, random_number_generator: &mut StdRng) {
+    let fall_speed = GAME_CONFIGURATION.asteroid_velocity
+        + GAME_CONFIGURATION
+            .asteroid_extra_velocity
+            .sample(random_number_generator);
+    asteroid.velocity = Vector2::new(
+        GAME_CONFIGURATION.asteroid_drift.sample(random_number_generator),
+        -fall_speed,
+    );
+    asteroid.angular_velocity = GAME_CONFIGURATION.asteroid_spin.sample(random_number_generator);
+}
+
 /// (Re)locate the asteroid to a random spot somewhere above the screen.
 ///
 /// The speed at which the asteroids fall is calculated into the
@@ -93,15 +164,15 @@ pub fn initialise_asteroids(
 /// so the asteroids will fall at continuously regular intervals.
 pub fn locate_asteroid(
     asteroid: &Asteroid,
-    screen_width: f32,
-    screen_height: f32,
-    random_number_generator: &mut ThreadRng,
+    arena_width: f32,
+    arena_height: f32,
+    random_number_generator: &mut StdRng,
 ) -> Transform {
-    let max_width = screen_width - asteroid.width;
-    let min_height = screen_height
+    let max_width = arena_width - asteroid.width;
+    let min_height = arena_height
         + GAME_CONFIGURATION.wait_for_first_asteroid * GAME_CONFIGURATION.asteroid_velocity;
     let max_height = min_height
-        + (screen_height * GAME_CONFIGURATION.asteroid_velocity)
+        + (arena_height * GAME_CONFIGURATION.asteroid_velocity)
             / GAME_CONFIGURATION.asteroid_density;
     let pos_x = random_number_generator.gen::<f32>() * max_width;
     let pos_y = min_height + random_number_generator.gen::<f32>() * (max_height - min_height);
@@ -110,3 +181,48 @@ pub fn locate_asteroid(
     local_transform.set_translation(Vector3::new(pos_x, pos_y, 0.0));
     local_transform
 }
+
+/// Splits a destroyed asteroid into fragments of the next size down.
+///
+/// Like [fire_laser](../laser/fn.fire_laser.html), this is a pattern for
+/// instantiating entities from within a System: we use a lazy update
+/// to queue the creation of each fragment until the world is next maintained.
+///
+/// The fragments start where the destroyed asteroid was,
+/// keep its velocity, and fly apart sideways with a random spin
+/// drawn from the given random number generator.
+///
+/// Tiny asteroids don't split, so nothing is created for them.
+pub fn split_asteroid(
+    entities: &Entities,
+    asteroid_resource: &AsteroidResource,
+    asteroid: &Asteroid,
+    position: Vector3<f32>,
+    random_number_generator: &mut StdRng,
+    lazy_update: &LazyUpdate,
+) {
+    let fragment_size = match asteroid.size.smaller() {
+        Some(fragment_size) => fragment_size,
+        None => return,
+    };
+    let fragment_count = GAME_CONFIGURATION.asteroid_fragment_count.max(2);
+
+    for fragment_number in 0..fragment_count {
+        let (mut fragment, sprite_render) =
+            create_asteroid(fragment_size, &asteroid_resource.sprite_sheet, random_number_generator);
+        // spread the fragments evenly from flying left to flying right
+        let spread = 2.0 * f32::from(fragment_number) / f32::from(fragment_count - 1) - 1.0;
+        fragment.velocity =
+            asteroid.velocity + Vector2::new(spread * GAME_CONFIGURATION.asteroid_fragment_speed, 0.0);
+        fragment.angular_velocity = GAME_CONFIGURATION.asteroid_spin.sample(random_number_generator);
+        fragment.is_fragment = true;
+
+        let mut local_transform = Transform::default();
+        local_transform.set_translation(position);
+
+        let fragment_entity: Entity = entities.create();
+        lazy_update.insert(fragment_entity, fragment);
+        lazy_update.insert(fragment_entity, sprite_render);
+        lazy_update.insert(fragment_entity, local_transform);
+    }
+}
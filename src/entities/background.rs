@@ -1,9 +1,13 @@
-//! Manage the background entity
+//! Manage the background entities
+
+use std::collections::HashMap;
 
 use amethyst::core::math::Vector3;
 use amethyst::core::transform::Transform;
-use amethyst::ecs::prelude::{Entity, World, WorldExt};
+use amethyst::ecs::prelude::{World, WorldExt};
 use amethyst::prelude::Builder;
+use rand::rngs::StdRng;
+use rand::Rng;
 
 use amethyst::assets::AssetStorage;
 use amethyst::assets::Handle;
@@ -13,83 +17,207 @@ use amethyst::renderer::Sprite;
 use amethyst::renderer::SpriteRender;
 use amethyst::renderer::SpriteSheet;
 use amethyst::renderer::Texture;
-use amethyst::window::ScreenDimensions;
 
-/// Initialises the background as a sprite object
+use crate::backgrounds::{BackgroundLayer, LevelBackground, BACKGROUNDS};
+use crate::components::Scrolling;
+use crate::resources::{Arena, BackgroundResource, GameRandom, PlayState};
+use crate::sprites::sprite_number;
+
+/// How far back the furthest background layer is drawn
+const BACKGROUND_DEPTH: f32 = -0.9;
+/// How much nearer each background layer is drawn than the one behind it
+const LAYER_SPACING: f32 = 0.01;
+/// The (rough) height of the star sprites in "Spritesheet/sheet.xml"
+const STAR_HEIGHT: f32 = 24.;
+
+/// Initialises the background of the current level
 ///
-/// Sprites are assumed to belong to some "sprite sheet"
-/// which stores a list of sprites.
-pub fn initialise_background(world: &mut World) -> Entity {
-    let sprite_sheet_handle = load_background_sprite_sheet_handle(world);
-    let screen_dimensions = (*world.read_resource::<ScreenDimensions>()).clone();
+/// The function first loads a sprite sheet for each image the
+/// [backgrounds](../../struct.Backgrounds.html) use, and keeps them in the
+/// [BackgroundResource](../../resources/struct.BackgroundResource.html),
+/// along with the sprite sheet the star sprites come from.
+/// It then builds the layers of the current level's background
+/// with [background_pieces](fn.background_pieces.html).
+pub fn initialise_background(
+    world: &mut World,
+    sprite_sheet_handle: Handle<SpriteSheet>,
+) -> BackgroundResource {
+    let image_sheets: HashMap<String, Handle<SpriteSheet>> = BACKGROUNDS
+        .image_paths()
+        .into_iter()
+        .map(|path| {
+            let sheet = load_image_sprite_sheet(world, &path);
+            (path, sheet)
+        })
+        .collect();
+    let background_resource = BackgroundResource {
+        image_sheets,
+        star_sheet: sprite_sheet_handle,
+        level: world.read_resource::<PlayState>().level,
+    };
 
-    let sprite_render = SpriteRender {
-        sprite_sheet: sprite_sheet_handle,
-        sprite_number: 0,
+    let pieces = {
+        let arena = world.read_resource::<Arena>();
+        let mut game_random = world.write_resource::<GameRandom>();
+        background_pieces(
+            &background_resource,
+            BACKGROUNDS.for_level(background_resource.level),
+            arena.width,
+            arena.height,
+            &mut game_random.rng,
+        )
     };
+    for (sprite_render, local_transform, scrolling) in pieces {
+        world
+            .create_entity()
+            .with(sprite_render)
+            .with(local_transform)
+            .with(scrolling)
+            .build();
+    }
 
-    // Position the centre of the background sprite to be
-    // the centre of the screen.
-    let mut local_transform = Transform::default();
-    local_transform.set_translation( Vector3::new(
-        screen_dimensions.height() / 2.,
-        screen_dimensions.width() / 2.,
-        0.
-    ));
+    world.insert(background_resource.clone());
+    background_resource
+}
 
-    world
-        .create_entity()
-        .with(sprite_render)
-        .with(local_transform)
-        .build()
+/// Works out the pieces (sprite, position and scrolling) that make up a level's background
+///
+/// This is kept apart from creating the entities, so the same pieces can be created
+/// straight into the world at the start of the game, or through a lazy update
+/// from the [BackgroundSystem](../../systems/struct.BackgroundSystem.html)
+/// when the level changes.
+///
+/// * **Image** layers are tiled across the whole screen, with one row of tiles to spare,
+///   so there is always a row ready to scroll in from the top;
+/// * **Stars** layers scatter star sprites at random, using the game's seeded
+///   random number generator so replays get the same stars.
+///
+/// Either way, each piece wraps back to the top once it has scrolled off the bottom,
+/// so the layers tile seamlessly whatever the size of the window.
+pub fn background_pieces(
+    background_resource: &BackgroundResource,
+    level_background: &LevelBackground,
+    arena_width: f32,
+    arena_height: f32,
+    random_number_generator: &mut StdRng,
+) -> Vec<(SpriteRender, Transform, Scrolling)> {
+    let mut pieces = Vec::new();
+    for (layer_number, layer) in level_background.layers.iter().enumerate() {
+        let depth = BACKGROUND_DEPTH + layer_number as f32 * LAYER_SPACING;
+        match layer {
+            BackgroundLayer::Image {
+                path,
+                tile_size,
+                speed,
+            } => {
+                let sprite_sheet = match background_resource.image_sheets.get(path) {
+                    Some(sprite_sheet) => sprite_sheet.clone(),
+                    None => continue,
+                };
+                let tile_size = tile_size.max(1.);
+                let columns = (arena_width / tile_size).ceil().max(1.) as usize;
+                let rows = (arena_height / tile_size).ceil() as usize + 1;
+                for row in 0..rows {
+                    for column in 0..columns {
+                        let mut local_transform = Transform::default();
+                        local_transform.set_translation(Vector3::new(
+                            (column as f32 + 0.5) * tile_size,
+                            (row as f32 + 0.5) * tile_size,
+                            depth,
+                        ));
+                        // the image sprites are one unit square, so we scale them up to the tile size
+                        local_transform.set_scale(Vector3::new(tile_size, tile_size, 1.));
+                        pieces.push((
+                            SpriteRender {
+                                sprite_sheet: sprite_sheet.clone(),
+                                sprite_number: 0,
+                            },
+                            local_transform,
+                            Scrolling {
+                                speed: *speed,
+                                wrap_height: rows as f32 * tile_size,
+                                half_height: tile_size / 2.,
+                            },
+                        ));
+                    }
+                }
+            }
+            BackgroundLayer::Stars {
+                count,
+                sprites,
+                scale,
+                speed,
+            } => {
+                if sprites.is_empty() {
+                    continue;
+                }
+                for _star in 0..*count {
+                    let sprite_number =
+                        sprite_number(&sprites[random_number_generator.gen_range(0..sprites.len())]);
+                    let star_scale =
+                        scale.0 + random_number_generator.gen::<f32>() * (scale.1 - scale.0);
+                    let half_height = STAR_HEIGHT * star_scale / 2.;
+                    let wrap_height = arena_height + 2. * half_height;
+                    let mut local_transform = Transform::default();
+                    local_transform.set_translation(Vector3::new(
+                        random_number_generator.gen::<f32>() * arena_width,
+                        random_number_generator.gen::<f32>() * wrap_height - half_height,
+                        depth,
+                    ));
+                    local_transform.set_scale(Vector3::new(star_scale, star_scale, 1.));
+                    pieces.push((
+                        SpriteRender {
+                            sprite_sheet: background_resource.star_sheet.clone(),
+                            sprite_number,
+                        },
+                        local_transform,
+                        Scrolling {
+                            speed: *speed,
+                            wrap_height,
+                            half_height,
+                        },
+                    ));
+                }
+            }
+        }
+    }
+    pieces
 }
 
-/// Loads a handle for the 'sprite sheet' for our background
+/// Loads a handle for the 'sprite sheet' of a background image
 ///
 /// A sprite sheet defines the position and size of a list of sprites
 /// within a texture file,
 /// as well as the size to display each sprite on the screen.
 ///
 /// Normally these will be loaded through a sprite sheet definition file,
-/// but that file does not offer the capability of 'scaling' the sprite
-/// when we load it.
-fn load_background_sprite_sheet_handle(world: &mut World) -> Handle<SpriteSheet> {
-    let texture_handle = load_background_texture(world);
-    let sprite_count = 1;
-    let mut sprites = Vec::with_capacity(sprite_count);
-
-    let sprite_width: f32 = 1024.; // width of our background sprite
-    let sprite_height: f32 = 1024.; // height of our background sprite
+/// but here the whole image is a single sprite, one unit square,
+/// which we scale up to the size of the tile when we draw it.
+fn load_image_sprite_sheet(world: &mut World, path: &str) -> Handle<SpriteSheet> {
+    let texture_handle = load_background_texture(world, path);
 
-    let dimensions = (sprite_width, sprite_height);
+    let dimensions = (1., 1.);
     let texture_coordinates: [f32; 4] = [
         /* left:   */ 0., /* right:  */ 1., /* bottom: */ 0., /* top:    */ 1.,
     ]; // i.e. the whole png image
 
-    sprites.push(Sprite::from((dimensions, texture_coordinates)));
-
     let sprite_sheet_storage = world.read_resource::<AssetStorage<SpriteSheet>>();
     let loader = world.read_resource::<Loader>();
     loader.load_from_data(
         SpriteSheet {
             texture: texture_handle,
-            sprites,
+            sprites: vec![Sprite::from((dimensions, texture_coordinates))],
         },
         (),
         &sprite_sheet_storage,
     )
 }
 
-/// Loads the texture (sprite image) of the background
+/// Loads the texture (sprite image) of a background image
 /// and returns a handle to it, which can then be used
 /// to create a sprite sheet.
-fn load_background_texture(world: &mut World) -> Handle<Texture> {
+fn load_background_texture(world: &mut World, path: &str) -> Handle<Texture> {
     let loader = world.read_resource::<Loader>();
     let texture_storage = world.read_resource::<AssetStorage<Texture>>();
-    loader.load(
-        "Backgrounds/darkPurple.png",
-        ImageFormat::default(),
-        (),
-        &texture_storage,
-    )
+    loader.load(path, ImageFormat::default(), (), &texture_storage)
 }
@@ -3,26 +3,27 @@ use amethyst::core::transform::Transform;
 use amethyst::ecs::prelude::{Entity, World, WorldExt};
 use amethyst::prelude::Builder;
 use amethyst::renderer::camera::Camera;
-use amethyst::window::ScreenDimensions;
+
+use crate::resources::Arena;
 
 /// Initialises a camera and adds it to the world.
 ///
 /// This game uses an orthographic projection with
-/// the lower left corner being (0.0, 0.0)
-/// and the upper right corner being (screen_dimensions.width(), screen_dimensions.height())
+/// the lower left corner of the [arena](../../resources/struct.Arena.html) being (0.0, 0.0)
+/// and the upper right corner being (arena.width, arena.height).
+///
+/// The camera starts out showing just the arena;
+/// the [CameraSystem](../../systems/struct.CameraSystem.html) then widens its view
+/// to take in the whole window, keeping the arena in the middle.
 ///
 /// Our sprite set happens to be scaled for a world of 1024 x 1024.
 pub fn initialise_camera(world: &mut World) -> Entity {
-    let screen_dimensions = (*world.read_resource::<ScreenDimensions>()).clone();
+    let arena = *world.read_resource::<Arena>();
     let mut transform = Transform::default();
-    transform.set_translation_xyz(
-        screen_dimensions.width() / 2.,
-        screen_dimensions.height() / 2.,
-        1.
-    );
+    transform.set_translation_xyz(arena.width / 2., arena.height / 2., 1.);
     world
         .create_entity()
-        .with(Camera::standard_2d(screen_dimensions.width(), screen_dimensions.height()))
+        .with(Camera::standard_2d(arena.width, arena.height))
         .with(transform)
         .build()
 }
@@ -0,0 +1,56 @@
+//! Manage the explosion entities
+//!
+//! Like the [lasers](../laser/index.html), this module uses a pooling pattern:
+//! the explosion template is created as a resource, a system takes an explosion
+//! from the explosion pool when the ship is destroyed, and the explosion system
+//! puts it back once its time is up.
+use amethyst::core::math::Vector3;
+use amethyst::core::transform::Transform;
+use amethyst::ecs::prelude::{Entities, Entity, LazyUpdate, World};
+
+use crate::components::Explosion;
+use crate::config::GAME_CONFIGURATION;
+use crate::prefabs::PREFABS;
+use crate::resources::{EntityPool, ExplosionResource};
+
+use amethyst::assets::Handle;
+use amethyst::renderer::SpriteSheet;
+
+/// Initialises the data we use to instantiate an explosion,
+/// from the explosion's [prefab](../../struct.Prefabs.html).
+pub fn initialise_explosion_resource(
+    world: &mut World,
+    sprite_sheet_handle: Handle<SpriteSheet>,
+) -> ExplosionResource {
+    let explosion_resource = ExplosionResource {
+        sprite_render: PREFABS.explosion.sprite_render(&sprite_sheet_handle),
+    };
+    world.insert(explosion_resource.clone());
+    explosion_resource
+}
+
+/// Sets off an explosion at the given position.
+///
+/// This uses the same lazy-update pattern as [fire_laser](../laser/fn.fire_laser.html).
+pub fn spawn_explosion(
+    entities: &Entities,
+    explosion_resource: &ExplosionResource,
+    position: Vector3<f32>,
+    explosion_pool: &mut EntityPool<Explosion>,
+    lazy_update: &LazyUpdate,
+) {
+    let explosion_entity: Entity = explosion_pool.acquire(entities, lazy_update);
+    let mut local_transform = Transform::default();
+    local_transform.set_translation(position);
+    // draw the explosion in front of everything else
+    local_transform.set_translation_z(0.5);
+    lazy_update.insert(
+        explosion_entity,
+        Explosion {
+            age: 0.0,
+            duration: GAME_CONFIGURATION.explosion_duration,
+        },
+    );
+    lazy_update.insert(explosion_entity, explosion_resource.sprite_render.clone());
+    lazy_update.insert(explosion_entity, local_transform);
+}
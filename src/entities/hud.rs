@@ -0,0 +1,135 @@
+//! Manage the HUD entities (other than the life icons)
+
+use amethyst::assets::Handle;
+use amethyst::ecs::prelude::{Entity, World, WorldExt};
+use amethyst::prelude::Builder;
+use amethyst::ui::{Anchor, FontAsset, LineMode, UiImage, UiText};
+
+use crate::components::{HudItem, HudLayout};
+use crate::hud::{HudElement, HUD};
+use crate::resources::Arena;
+
+/// Initialises the shared HUD items: the high score, the wave, the debug overlay's stats
+/// and the achievement pop-up
+///
+/// Each item is laid out by the [HUD layout](../../struct.Hud.html) ("hud.ron" in resources),
+/// and its [HudLayout](../../components/struct.HudLayout.html) keeps it over the arena
+/// when the window is resized.
+/// The [HudSystem](../../systems/struct.HudSystem.html) keeps the items up to date.
+pub fn initialise_hud(world: &mut World, font: Handle<FontAsset>) -> Vec<Entity> {
+    vec![
+        create_text(
+            world,
+            font.clone(),
+            "high_score".to_string(),
+            &HUD.high_score,
+            HUD.high_score.shared_layout(),
+            HudItem::HighScore,
+        ),
+        create_text(
+            world,
+            font.clone(),
+            "wave".to_string(),
+            &HUD.wave,
+            HUD.wave.shared_layout(),
+            HudItem::Wave,
+        ),
+        create_text(
+            world,
+            font.clone(),
+            "debug_stats".to_string(),
+            &HUD.debug,
+            HUD.debug.shared_layout(),
+            HudItem::DebugStats,
+        ),
+        create_text(
+            world,
+            font,
+            "achievement_toast".to_string(),
+            &HUD.achievement_toast,
+            HUD.achievement_toast.shared_layout(),
+            HudItem::AchievementToast,
+        ),
+    ]
+}
+
+/// Initialises a player's HUD items: their score, score multiplier,
+/// fire-cooldown bar and power-up timers
+///
+/// The items sit on the player's row of the HUD, alongside their
+/// [life icons](../lives/fn.initialise_lives.html).
+pub fn initialise_player_hud(
+    world: &mut World,
+    font: Handle<FontAsset>,
+    player: usize,
+) -> Vec<Entity> {
+    let row_height = HUD.player_row_height;
+    let arena = *world.read_resource::<Arena>();
+    let cooldown_layout = HUD.cooldown_bar.layout(player, row_height);
+    let cooldown_bar = world
+        .create_entity()
+        .with(cooldown_layout.ui_transform(
+            format!("cooldown_p{}", player + 1),
+            Anchor::MiddleLeft,
+            &arena,
+        ))
+        .with(cooldown_layout)
+        .with(UiImage::SolidColor(HUD.cooldown_bar.colour))
+        .with(HudItem::CooldownBar { player })
+        .build();
+    vec![
+        create_text(
+            world,
+            font.clone(),
+            format!("score_p{}", player + 1),
+            &HUD.score,
+            HUD.score.layout(player, row_height),
+            HudItem::Score { player },
+        ),
+        create_text(
+            world,
+            font.clone(),
+            format!("multiplier_p{}", player + 1),
+            &HUD.multiplier,
+            HUD.multiplier.layout(player, row_height),
+            HudItem::Multiplier { player },
+        ),
+        cooldown_bar,
+        create_text(
+            world,
+            font,
+            format!("power_ups_p{}", player + 1),
+            &HUD.power_ups,
+            HUD.power_ups.layout(player, row_height),
+            HudItem::PowerUps { player },
+        ),
+    ]
+}
+
+/// Creates a HUD text, left-aligned and starting out empty
+///
+/// The text wraps onto further lines if it doesn't fit the element's width.
+fn create_text(
+    world: &mut World,
+    font: Handle<FontAsset>,
+    id: String,
+    element: &HudElement,
+    layout: HudLayout,
+    item: HudItem,
+) -> Entity {
+    let arena = *world.read_resource::<Arena>();
+    world
+        .create_entity()
+        .with(layout.ui_transform(id, Anchor::MiddleLeft, &arena))
+        .with(layout)
+        .with(UiText::new(
+            font,
+            String::new(),
+            element.colour,
+            element.font_size * arena.scale,
+            LineMode::Wrap,
+            Anchor::MiddleLeft,
+        ))
+        .with(item)
+        .build()
+}
@@ -1,22 +1,24 @@
 //! Manage the laser entities
 //!
-//! This module uses a create-destroy pattern to manage the entities.
+//! This module uses a pooling pattern to manage the entities.
 //!
 //! The laser template is created as a resource that a system can access.
 //!
-//! The ship system then creates a laser on the fly,
+//! The ship system then takes a laser from the [laser pool](../../resources/struct.EntityPool.html)
+//! (which creates one if it has none to spare) and sets it up,
 //! using a reference to that resource,
 //! when the user presses the FIRE button.
 //!
-//! The laser movement system will destroy the laser when it runs out of
+//! The laser movement system will put the laser back in the pool when it runs out of
 //! camera range or hits an asteroid.
 use amethyst::core::math::Vector3;
 use amethyst::core::transform::Transform;
-use amethyst::ecs::prelude::{Entities, Entity, LazyUpdate, ReadExpect, World};
+use amethyst::ecs::prelude::{Entities, Entity, LazyUpdate, World};
 
 use crate::components::Laser as LaserComponent;
-use crate::config::GAME_CONFIGURATION;
-use crate::resources::LaserResource;
+use crate::resources::{EntityPool, LaserResource};
+use crate::roster::WeaponDefinition;
+use crate::sprites::sprite_number;
 
 use amethyst::assets::Handle;
 use amethyst::renderer::SpriteRender;
@@ -24,22 +26,25 @@ use amethyst::renderer::SpriteSheet;
 
 /// Initialises the data we use to instantiate a laser when fired.
 ///
-/// This function creates a mesh, a material and a component
+/// This function creates a sprite render and a component,
+/// from the weapon of the ship the player chose,
 /// that will be attached to the entity when we create it in
 /// [fire_laser](fn.fire_laser.html).
 pub fn initialise_laser_resource(
     world: &mut World,
     sprite_sheet_handle: Handle<SpriteSheet>,
+    weapon: &WeaponDefinition,
 ) -> LaserResource {
     let laser_resource = LaserResource {
         component: LaserComponent {
-            velocity: GAME_CONFIGURATION.laser_velocity,
-            width: 9.0,
-            height: 54.0,
+            velocity: weapon.velocity,
+            width: weapon.width,
+            height: weapon.height,
+            owner: 0,
         },
         sprite_render: SpriteRender {
             sprite_sheet: sprite_sheet_handle.clone(),
-            sprite_number: 1,
+            sprite_number: sprite_number(&weapon.sprite),
         },
     };
     world.insert(laser_resource.clone());
@@ -49,18 +54,22 @@ pub fn initialise_laser_resource(
 /// Fires the laser at the given position.
 ///
 /// This is a pattern for instantiating an entity from
-/// within a System. We use a lazy create on our list of
-/// entities to queue our create requests.
+/// within a System. We take an entity from the laser pool
+/// and use a lazy update to queue giving it a fresh set of components.
 ///
 /// When the Amethyst engine calls world.maintain(),
-/// it will create the laser entity.
+/// it will set up the laser entity.
+///
+/// The laser remembers which player fired it, so they get the points for what it hits.
 pub fn fire_laser(
     entities: &Entities,
-    laser_resource: &ReadExpect<LaserResource>,
+    laser_resource: &LaserResource,
     fire_position: Vector3</*Float*/ f32>,
-    lazy_update: &ReadExpect<LazyUpdate>,
+    owner: usize,
+    laser_pool: &mut EntityPool<LaserComponent>,
+    lazy_update: &LazyUpdate,
 ) {
-    let laser_entity: Entity = entities.create();
+    let laser_entity: Entity = laser_pool.acquire(entities, lazy_update);
     let local_transform = {
         let mut local_transform = Transform::default();
         local_transform.set_translation(fire_position);
@@ -69,7 +78,11 @@ pub fn fire_laser(
         local_transform.set_translation_x(p - (laser_resource.component.width / 2.0));
         local_transform
     };
-    lazy_update.insert(laser_entity, laser_resource.component.clone());
+    let laser_component = LaserComponent {
+        owner,
+        ..laser_resource.component.clone()
+    };
+    lazy_update.insert(laser_entity, laser_component);
     lazy_update.insert(laser_entity, laser_resource.sprite_render.clone());
     lazy_update.insert(laser_entity, local_transform);
 }
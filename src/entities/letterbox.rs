@@ -0,0 +1,81 @@
+//! Manage the letterbox entities
+
+use amethyst::assets::{AssetStorage, Handle, Loader};
+use amethyst::core::math::Vector3;
+use amethyst::core::transform::Transform;
+use amethyst::ecs::prelude::{Entity, World, WorldExt};
+use amethyst::prelude::Builder;
+use amethyst::renderer::loaders::load_from_srgba;
+use amethyst::renderer::palette::Srgba;
+use amethyst::renderer::types::TextureData;
+use amethyst::renderer::{Sprite, SpriteRender, SpriteSheet, Texture};
+
+use crate::resources::Arena;
+
+/// How far in front of the game the bars are drawn (the camera is at 1.0)
+const LETTERBOX_DEPTH: f32 = 0.5;
+/// How far the bars reach out from the arena, in arena sizes,
+/// so they cover the rest of the window, however wide or tall it is
+const LETTERBOX_REACH: f32 = 100.;
+
+/// Initialises the bars around the arena
+///
+/// When the window is not the same shape as the [arena](../../resources/struct.Arena.html),
+/// the camera shows more than the arena. Anything outside the arena
+/// (e.g. asteroids waiting to fall, or wrapping round the side walls)
+/// is covered up by four black bars, one along each side of the arena,
+/// which reach far enough out to cover the rest of the window.
+///
+/// The bars never move: the arena stays put in the world,
+/// and the camera does all the work of fitting it into the window.
+pub fn initialise_letterbox(world: &mut World) -> Vec<Entity> {
+    let arena = *world.read_resource::<Arena>();
+    let sprite_sheet = load_letterbox_sprite_sheet(world);
+
+    let reach = LETTERBOX_REACH * arena.width.max(arena.height);
+    let bars = [
+        // left and right, covering the corners as well
+        (-reach / 2., arena.height / 2., reach, arena.height + 2. * reach),
+        (arena.width + reach / 2., arena.height / 2., reach, arena.height + 2. * reach),
+        // below and above
+        (arena.width / 2., -reach / 2., arena.width, reach),
+        (arena.width / 2., arena.height + reach / 2., arena.width, reach),
+    ];
+    bars.iter()
+        .map(|&(x, y, width, height)| {
+            let mut local_transform = Transform::default();
+            local_transform.set_translation_xyz(x, y, LETTERBOX_DEPTH);
+            // the bar sprite is one unit square, so we scale it up to the size of the bar
+            local_transform.set_scale(Vector3::new(width, height, 1.));
+            world
+                .create_entity()
+                .with(SpriteRender {
+                    sprite_sheet: sprite_sheet.clone(),
+                    sprite_number: 0,
+                })
+                .with(local_transform)
+                .build()
+        })
+        .collect()
+}
+
+/// Loads a sprite sheet holding a single black sprite, one unit square
+///
+/// There's no image file for the bars: the texture is made on the fly
+/// from a single black pixel.
+fn load_letterbox_sprite_sheet(world: &mut World) -> Handle<SpriteSheet> {
+    let loader = world.read_resource::<Loader>();
+    let texture_handle: Handle<Texture> = loader.load_from_data(
+        TextureData::from(load_from_srgba(Srgba::new(0., 0., 0., 1.))),
+        (),
+        &world.read_resource::<AssetStorage<Texture>>(),
+    );
+    loader.load_from_data(
+        SpriteSheet {
+            texture: texture_handle,
+            sprites: vec![Sprite::from(((1., 1.), [0., 1., 0., 1.]))],
+        },
+        (),
+        &world.read_resource::<AssetStorage<SpriteSheet>>(),
+    )
+}
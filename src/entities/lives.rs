@@ -1,56 +1,100 @@
 //! Manage the life entities
 
 use amethyst::assets::Handle;
-use amethyst::ecs::prelude::{Entity, World, WorldExt};
+use amethyst::ecs::prelude::{Entities, Entity, LazyUpdate, World, WorldExt};
 use amethyst::prelude::Builder;
 use amethyst::renderer::SpriteRender;
 use amethyst::renderer::SpriteSheet;
 use amethyst::ui::{Anchor, UiImage, UiTransform};
 
-use crate::components::Life;
+use crate::components::{HudLayout, Life};
+use crate::config::GAME_CONFIGURATION;
+use crate::hud::HUD;
+use crate::resources::{Arena, LivesResource};
 
-const LIFE_WIDTH: f32 = 32.;
-const LIFE_HEIGHT: f32 = 26.;
-
-/// Initialises the three life entities
+/// Initialises the life entities of a player
 ///
 /// Like [initialise_asteroids](../asteroid/fn.initialise_asteroids.html),
-/// this function creates a list of life entities representing the player's lives.
+/// this function creates a list of life entities representing the player's lives,
+/// using the life icon of the ship the player chose.
+/// The number of lives each player starts with is set in the
+/// [game configuration](../../struct.GameConfiguration.html).
+///
+/// Each player gets their own row of life icons, one under the other,
+/// laid out by the [HUD layout](../../struct.Hud.html).
 ///
-/// Note that the number of lives is hard-coded to three. To make it a designer-controlled
-/// variable, you can add the item to [game configuration](../../struct.GameConfiguration.html),
-/// then modify the code below.
+/// The life icon is also kept in a [LivesResource](../../resources/struct.LivesResource.html),
+/// so the [LivesSystem](../../systems/struct.LivesSystem.html) can add icons
+/// when the player earns extra lives.
 ///
 /// Note as well that the co-ordination system for the UI layer is different. (0,0) is top-left instead of bottom-left,
 /// and the png sprites don't need to be scaled.
+/// Each icon's [HudLayout](../../components/struct.HudLayout.html) keeps it over the arena
+/// when the window is resized.
 pub fn initialise_lives(
     world: &mut World,
     sprite_sheet_handle: Handle<SpriteSheet>,
+    life_sprite_number: usize,
+    player: usize,
 ) -> Vec<Entity> {
     let sprite_render = SpriteRender {
         sprite_sheet: sprite_sheet_handle,
-        sprite_number: 3,
+        sprite_number: life_sprite_number,
     };
+    world.insert(LivesResource {
+        sprite_render: sprite_render.clone(),
+    });
 
+    let arena = *world.read_resource::<Arena>();
     let mut entity_list = Vec::<Entity>::new();
-    for i in 0u8..3u8 {
+    for life_number in 0..GAME_CONFIGURATION.starting_lives() {
+        let (ui_transform, layout, life) = life_icon(player, life_number, &arena);
         entity_list.push(
             world
                 .create_entity()
-                .with(UiTransform::new(
-                    format!("life{}", i),
-                    Anchor::TopLeft,
-                    Anchor::Middle,
-                    f32::from(i) * LIFE_WIDTH,
-                    -LIFE_HEIGHT,
-                    0.,
-                    LIFE_WIDTH,
-                    LIFE_HEIGHT,
-                ))
+                .with(ui_transform)
+                .with(layout)
                 .with(UiImage::Sprite(sprite_render.clone()))
-                .with(Life { life_number: i })
+                .with(life)
                 .build(),
         );
     }
     entity_list
 }
+
+/// Adds a life icon for a player from within a system
+///
+/// This uses the same lazy-update pattern as [fire_laser](../laser/fn.fire_laser.html).
+pub fn add_life_icon(
+    entities: &Entities,
+    lives_resource: &LivesResource,
+    player: usize,
+    life_number: u8,
+    arena: &Arena,
+    lazy_update: &LazyUpdate,
+) {
+    let (ui_transform, layout, life) = life_icon(player, life_number, arena);
+    lazy_update
+        .create_entity(entities)
+        .with(ui_transform)
+        .with(layout)
+        .with(UiImage::Sprite(lives_resource.sprite_render.clone()))
+        .with(life)
+        .build();
+}
+
+/// Lays out one of a player's life icons
+///
+/// The icons sit side by side, in the order of their life numbers, on the player's row.
+/// The icon is placed for the arena as it is currently fitted to the window,
+/// so icons added during the game line up with the others.
+fn life_icon(player: usize, life_number: u8, arena: &Arena) -> (UiTransform, HudLayout, Life) {
+    let mut layout = HUD.lives.layout(player, HUD.player_row_height);
+    layout.x += f32::from(life_number) * layout.width;
+    let ui_transform = layout.ui_transform(
+        format!("life{}_p{}", life_number, player + 1),
+        Anchor::Middle,
+        arena,
+    );
+    (ui_transform, layout, Life { player, life_number })
+}
@@ -5,62 +5,122 @@
 //!
 //! The entities used by the space_shooter game are:
 //!
-//! * **background** - a simple (untiled) background of stars
+//! * **background** - the level's background, layers of tiled images and stars scrolling at different speeds
 //! * **camera**     - a camera that encapsulates the scene
-//! * **ship**       - the player's ship, which responds to keypresses
-//! * **asteroid**   - the asteroids which collide with the ship (a fixed number, which are re-used)
-//! * **laser**      - the bullets the ship uses to fire on the asteroids (created and destroyed on demand)
-//! * **lives**      - the ships on the UI layer that represent the number of remaining lives.
+//! * **letterbox**  - the black bars covering the window outside the arena
+//! * **ship**       - each player's ship, which responds to that player's keypresses, with a damage overlay attached
+//! * **asteroid**   - the asteroids which collide with the ship (a fixed number, which are re-used,
+//!                    plus the fragments they split into, which are created and destroyed on demand)
+//! * **laser**      - the bullets the ship uses to fire on the asteroids (taken from a pool on demand, and put back)
+//! * **lives**      - the ships on the UI layer that represent the number of remaining lives of each player.
+//! * **hud**        - the rest of the HUD on the UI layer: each player's score, score multiplier,
+//!                    fire-cooldown bar and power-up timers, the high score and the wave.
+//! * **explosion**  - the flash when the ship is destroyed (taken from a pool on demand, and put back)
 //!
 //! Note each initialisation method returns the entity (or list of entities, or resource) it creates,
 //! in case you wanted to create entities that are related to other entities. That's just good practice.
 pub mod asteroid;
 pub mod background;
 pub mod camera;
+pub mod explosion;
+pub mod hud;
 pub mod laser;
+pub mod letterbox;
 pub mod lives;
 pub mod ship;
 
-use amethyst::assets::{AssetStorage, Loader};
+use amethyst::assets::{AssetStorage, Handle, Loader};
 use amethyst::ecs::prelude::World;
 use amethyst::ecs::prelude::WorldExt;
 use amethyst::renderer::formats::texture::ImageFormat;
 use amethyst::renderer::SpriteSheet;
-use amethyst::renderer::SpriteSheetFormat;
 use amethyst::renderer::Texture;
+use amethyst::ui::{FontAsset, TtfFormat};
 
+use crate::components::Player;
+use crate::config::GAME_CONFIGURATION;
+use crate::roster::{ShipDefinition, SHIP_ROSTER};
+use crate::sprites::{sprite_number, SPRITE_ATLAS};
+
+pub use self::asteroid::asteroid_hit_points;
+pub use self::asteroid::launch_asteroid;
 pub use self::asteroid::locate_asteroid;
+pub use self::asteroid::split_asteroid;
+pub use self::background::background_pieces;
+pub use self::explosion::spawn_explosion;
 pub use self::laser::fire_laser;
+pub use self::lives::add_life_icon;
+pub use self::ship::ship_start_position;
 
 /// Initialises all the entities (some are just set up as resources so the entities can be created later on demand)
+///
+/// The ships, their lasers and their life icons come from the ship chosen
+/// on the ship-select screen, or the first ship in the [roster](../struct.ShipRoster.html)
+/// if no ship was chosen.
+///
+/// Each player gets their own ship, life icons and row of the HUD.
 pub fn initialise_entities(world: &mut World) {
-    // Load the sprite sheet with all our entities
-    let sprite_sheet_handle = {
-        let texture_handle = {
-            let loader = world.read_resource::<Loader>();
-            let texture_storage = world.read_resource::<AssetStorage<Texture>>();
-            loader.load(
-                "Spritesheet/sheet.png",
-                ImageFormat::default(),
-                (),
-                &texture_storage,
-            )
-        };
+    let sprite_sheet_handle = load_sprite_sheet(world);
+    let font = load_font(world);
+    let ship_definition = world
+        .try_fetch::<ShipDefinition>()
+        .map(|ship_definition| ship_definition.clone())
+        .unwrap_or_else(|| SHIP_ROSTER.ships[0].clone());
+    let player_count = GAME_CONFIGURATION.player_count();
+    background::initialise_background(world, sprite_sheet_handle.clone());
+    for player_index in 0..player_count {
+        ship::initialise_ship(
+            world,
+            sprite_sheet_handle.clone(),
+            &ship_definition,
+            Player::new(player_index),
+            player_count,
+        );
+        lives::initialise_lives(
+            world,
+            sprite_sheet_handle.clone(),
+            sprite_number(&ship_definition.life_sprite),
+            player_index,
+        );
+        hud::initialise_player_hud(world, font.clone(), player_index);
+    }
+    hud::initialise_hud(world, font);
+    asteroid::initialise_asteroids(world, sprite_sheet_handle.clone());
+    camera::initialise_camera(world);
+    letterbox::initialise_letterbox(world);
+    laser::initialise_laser_resource(world, sprite_sheet_handle.clone(), &ship_definition.weapon);
+    explosion::initialise_explosion_resource(world, sprite_sheet_handle.clone());
+}
+
+/// Loads the font we use for all the text on the UI layer
+pub fn load_font(world: &mut World) -> Handle<FontAsset> {
+    let loader = world.read_resource::<Loader>();
+    let font_storage = world.read_resource::<AssetStorage<FontAsset>>();
+    loader.load("Bonus/kenvector_future.ttf", TtfFormat, (), &font_storage)
+}
+
+/// Loads the sprite sheet with all our entities
+///
+/// The sprites come from the [sprite atlas](../struct.SpriteAtlas.html)
+/// ("Spritesheet/sheet.xml"), which also names the image they are drawn from.
+pub fn load_sprite_sheet(world: &mut World) -> Handle<SpriteSheet> {
+    let texture_handle = {
         let loader = world.read_resource::<Loader>();
-        let sprite_sheet_store = world.read_resource::<AssetStorage<SpriteSheet>>();
+        let texture_storage = world.read_resource::<AssetStorage<Texture>>();
         loader.load(
-            "Spritesheet/sheet.ron",
-            SpriteSheetFormat(texture_handle),
+            format!("Spritesheet/{}", SPRITE_ATLAS.image_path),
+            ImageFormat::default(),
             (),
-            &sprite_sheet_store,
+            &texture_storage,
         )
     };
-    background::initialise_background(world);
-    ship::initialise_ship(world, sprite_sheet_handle.clone());
-    asteroid::initialise_asteroids(world, sprite_sheet_handle.clone());
-    camera::initialise_camera(world);
-    laser::initialise_laser_resource(world, sprite_sheet_handle.clone());
-    lives::initialise_lives(world, sprite_sheet_handle.clone());
+    let loader = world.read_resource::<Loader>();
+    let sprite_sheet_store = world.read_resource::<AssetStorage<SpriteSheet>>();
+    loader.load_from_data(
+        SPRITE_ATLAS.sprite_sheet(texture_handle),
+        (),
+        &sprite_sheet_store,
+    )
 }
 
 ///// Loads a material (png file) and creates a mesh (display object) that is the same size as the material,
@@ -1,60 +1,131 @@
 //! Manage the ship entity
 
-use amethyst::core::math::Vector3;
-use amethyst::core::transform::Transform;
+use amethyst::core::math::{Vector2, Vector3};
+use amethyst::core::transform::{Parent, Transform};
+use amethyst::core::Hidden;
 use amethyst::ecs::prelude::{Entity, World, WorldExt};
 use amethyst::assets::Handle;
 use amethyst::prelude::Builder;
 use amethyst::renderer::{SpriteSheet, SpriteRender};
-use amethyst::window::ScreenDimensions;
 
+use crate::components::{DamageOverlay, Player, Ship, ShipStatus};
+use crate::resources::Arena;
+use crate::roster::ShipDefinition;
+use crate::sprites::sprite_number;
 
-// The width and the height come from the png file
-const SHIP_WIDTH: f32 = 105.0;
-const SHIP_HEIGHT: f32 = 83.0;
+/// Initialises a player's ship at the bottom of the screen
+///
+/// The function creates a ship sprite and component
+/// from the ship the player chose on the ship-select screen,
+/// sets up the transform to position it
+/// at the bottom (y=0) of the screen, in the player's slot,
+/// then finally bundles all the components, with the player's bindings, into an entity.
+///
+/// It also creates the ship's [damage overlay](fn.initialise_damage_overlay.html).
+pub fn initialise_ship(
+    world: &mut World,
+    sprite_sheet_handle: Handle<SpriteSheet>,
+    ship_definition: &ShipDefinition,
+    player: Player,
+    player_count: usize,
+) -> Entity {
+    let arena_width = world.read_resource::<Arena>().width;
 
-use crate::components::Ship;
+    let ship = Ship {
+        velocity: Vector2::zeros(), // ship starts out stationary
+        width: ship_definition.width,
+        height: ship_definition.height,
+        thrust: ship_definition.thrust,
+        max_speed: ship_definition.max_speed,
+        trigger_reset_timeout: ship_definition.trigger_reset_timeout,
+        trigger_reset_timer: 0.0,
+        status: ShipStatus::Active,
+    };
 
-/// Initialises the player's ship at the bottom centre of the screen
-///
-/// The function creates a ship sprite (mesh and material),
-/// sets up the transform to scale the sprite and to position it
-/// at the bottom (y=0) centre of the screen,
-/// then finally bundles all the components into an entity.
-pub fn initialise_ship(world: &mut World, sprite_sheet_handle: Handle<SpriteSheet>) -> Entity {
-    let _screen_dimensions = (*world.read_resource::<ScreenDimensions>()).clone();
-
-    // Set the scale and position of our ship sprite
-    // so that it's just above the centre bottom of the screen
+    // Set the position of our ship sprite
+    // so that it's just above the bottom of the screen
     let mut local_transform = Transform::default();
-    local_transform.set_translation(Vector3::new(
-        _screen_dimensions.width() / 2.,
-        SHIP_HEIGHT / 2. + 0.1, // add a bit so it's not touching the bottom of the screen
-        0.
-    ));
-
-    // We also scale the sprite negatively so that we
-    // flip it to face up
-    local_transform.set_scale(Vector3::new(
-        1.,
-        -1.,
-        1.,
+    local_transform.set_translation(ship_start_position(
+        &ship,
+        player.index,
+        player_count,
+        arena_width,
     ));
 
     // Create a new entity by bundling the mesh, material, component and transforms together
     // then return the entity we created.
+    let ship = world
+        .create_entity()
+        .with(ship)
+        .with(player)
+        .with(local_transform)
+        .with(SpriteRender {
+            sprite_sheet: sprite_sheet_handle.clone(),
+            sprite_number: sprite_number(&ship_definition.sprite),
+        })
+        .build();
+
+    initialise_damage_overlay(
+        world,
+        sprite_sheet_handle,
+        ship,
+        &ship_definition
+            .damage_sprites
+            .iter()
+            .map(|name| sprite_number(name))
+            .collect::<Vec<_>>(),
+    );
+    ship
+}
+
+/// The position a player's ship starts from (and respawns at):
+/// just above the bottom of the screen.
+///
+/// The players' ships are spread evenly across the screen,
+/// so a single ship starts at the bottom centre.
+pub fn ship_start_position(
+    ship: &Ship,
+    player_index: usize,
+    player_count: usize,
+    arena_width: f32,
+) -> Vector3<f32> {
+    Vector3::new(
+        arena_width * (player_index + 1) as f32 / (player_count + 1) as f32,
+        ship.height / 2. + 0.1, // add a bit so it's not touching the bottom of the screen
+        0.,
+    )
+}
+
+/// Initialises the damage overlay drawn over the ship
+///
+/// This is a pattern for attaching one entity to another:
+/// the overlay has a `Parent` component pointing at the ship,
+/// so its transform is relative to the ship's and it moves with the ship.
+///
+/// The overlay starts out hidden, since the ship starts out undamaged.
+/// The [DamageSystem](../../systems/struct.DamageSystem.html) shows it and
+/// picks the right damage sprite as the ship takes hits.
+pub fn initialise_damage_overlay(
+    world: &mut World,
+    sprite_sheet_handle: Handle<SpriteSheet>,
+    ship: Entity,
+    damage_sprite_numbers: &[usize],
+) -> Entity {
+    // Sit the overlay just in front of the ship
+    let mut local_transform = Transform::default();
+    local_transform.set_translation_z(0.1);
+
     world
         .create_entity()
-        .with(Ship {
-            velocity: 0.0, // ship starts out stationary
-            width: SHIP_WIDTH.into(),
-            height: SHIP_HEIGHT.into(),
-            trigger_reset_timer: 0.0,
+        .with(DamageOverlay {
+            sprite_numbers: damage_sprite_numbers.to_vec(),
         })
+        .with(Parent::new(ship))
         .with(local_transform)
         .with(SpriteRender {
-            sprite_sheet: sprite_sheet_handle.clone(),
-            sprite_number: 0,
+            sprite_sheet: sprite_sheet_handle,
+            sprite_number: damage_sprite_numbers.first().cloned().unwrap_or_default(),
         })
+        .with(Hidden)
         .build()
 }
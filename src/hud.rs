@@ -0,0 +1,105 @@
+//! The layout of the HUD (the heads-up display over the arena)
+//!
+//! Like the [prefabs](struct.Prefabs.html), the HUD's layout is loaded
+//! from a RON file ("hud.ron" in resources), so the game designer can
+//! move, resize and recolour the HUD without having to recompile the code.
+//!
+//! Every element is laid out in arena units, from the arena's top-left corner
+//! (with y negative going down), so the HUD scales with the arena
+//! whatever the size of the window (see [HudLayout](components/struct.HudLayout.html)).
+//!
+//! Each player has their own row of lives, score, score multiplier, fire-cooldown bar
+//! and power-up timers, one under the other; the high score, the wave,
+//! the debug overlay's stats and the achievement pop-ups are shared.
+
+use amethyst::config::Config;
+use lazy_static::lazy_static;
+use serde_derive::{Deserialize, Serialize};
+
+use crate::components::HudLayout;
+
+/// White, the colour of HUD elements that don't set their own
+const WHITE: [f32; 4] = [1.0, 1.0, 1.0, 1.0];
+
+/// The place, size and look of one element of the HUD, in arena units
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct HudElement {
+    /// How far the element is from the left of the arena
+    pub x: f32,
+    /// How far the element is from the top of the arena (negative going down)
+    pub y: f32,
+    /// The width of the element
+    pub width: f32,
+    /// The height of the element
+    pub height: f32,
+    /// The size of the element's text, if it has any
+    #[serde(default)]
+    pub font_size: f32,
+    /// The colour of the element's text or bar (red, green, blue and alpha, from 0 to 1)
+    #[serde(default = "white")]
+    pub colour: [f32; 4],
+}
+
+impl HudElement {
+    /// The layout of the element on the given player's row,
+    /// moved down by the given distance between rows
+    pub fn layout(&self, player: usize, row_height: f32) -> HudLayout {
+        HudLayout {
+            x: self.x,
+            y: self.y - player as f32 * row_height,
+            width: self.width,
+            height: self.height,
+            font_size: if self.font_size > 0.0 {
+                Some(self.font_size)
+            } else {
+                None
+            },
+        }
+    }
+
+    /// The layout of a shared element (one that isn't on a player's row)
+    pub fn shared_layout(&self) -> HudLayout {
+        self.layout(0, 0.0)
+    }
+}
+
+/// The layout of the whole HUD
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct Hud {
+    /// How far each player's row is below the previous player's
+    pub player_row_height: f32,
+    /// The player's first life icon; the others follow to the right, one icon width apart
+    pub lives: HudElement,
+    /// The player's score
+    pub score: HudElement,
+    /// The player's score multiplier, earned by hitting asteroids without missing
+    pub multiplier: HudElement,
+    /// The bar showing how soon the player's ship can fire again (its full width when it can)
+    pub cooldown_bar: HudElement,
+    /// The time left on each of the player's active power-ups
+    pub power_ups: HudElement,
+    /// The best score so far
+    pub high_score: HudElement,
+    /// The wave being played
+    pub wave: HudElement,
+    /// The timings and entity counts of the debug overlay (shown only while the overlay is)
+    pub debug: HudElement,
+    /// The pop-up announcing a newly unlocked achievement
+    pub achievement_toast: HudElement,
+}
+
+lazy_static! {
+    /// The actual [HUD layout](struct.Hud.html).
+    ///
+    /// The layout is automatically loaded on startup
+    /// from the file "hud.ron" in resources.
+    pub static ref HUD: Hud = {
+        let hud_path = format!("{}/resources/hud.ron", env!("CARGO_MANIFEST_DIR"));
+        Hud::load(&hud_path).unwrap()
+    };
+}
+
+/// The default colour of a HUD element, for serde
+fn white() -> [f32; 4] {
+    WHITE
+}
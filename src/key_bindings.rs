@@ -0,0 +1,255 @@
+//! Loading, changing and saving the key bindings
+//!
+//! The game ships its bindings in "input.ron" in resources.
+//! When the player changes them on the [controls screen](struct.ControlsState.html),
+//! the result is saved to their own bindings file
+//! (see [user_config_path](fn.user_config_path.html)), which is then loaded
+//! in preference to the shipped one.
+
+use std::path::PathBuf;
+
+use amethyst::config::Config;
+use amethyst::input::{Axis, Bindings, Button, StringBindings};
+
+use crate::user_files::user_config_path;
+
+/// The name of the player's own bindings file
+const USER_BINDINGS_FILE: &str = "input.ron";
+
+/// Something a key or button can be bound to
+#[derive(Clone, Debug, PartialEq)]
+pub enum BindingSlot {
+    /// An action, e.g. "fire_p1"
+    Action(String),
+    /// The positive end of an emulated axis, e.g. right for "ship_p1"
+    AxisPositive(String),
+    /// The negative end of an emulated axis, e.g. left for "ship_p1"
+    AxisNegative(String),
+}
+
+impl BindingSlot {
+    /// The name of the slot, as shown on the controls screen
+    pub fn name(&self) -> String {
+        match self {
+            BindingSlot::Action(action) => action.clone(),
+            BindingSlot::AxisPositive(axis) => format!("{} +", axis),
+            BindingSlot::AxisNegative(axis) => format!("{} -", axis),
+        }
+    }
+}
+
+/// The path of the key bindings shipped with the game
+pub fn shipped_bindings_path() -> PathBuf {
+    PathBuf::from(format!(
+        "{}/resources/input.ron",
+        env!("CARGO_MANIFEST_DIR")
+    ))
+}
+
+/// The path of the player's own key bindings
+pub fn user_bindings_path() -> PathBuf {
+    user_config_path(USER_BINDINGS_FILE)
+}
+
+/// Loads the player's own key bindings if they have any, or else the shipped ones
+///
+/// A broken bindings file of the player's is skipped with a warning,
+/// so the player can't lock themselves out of the game.
+pub fn load_key_bindings() -> Result<Bindings<StringBindings>, amethyst::Error> {
+    let user_path = user_bindings_path();
+    if user_path.exists() {
+        match Bindings::<StringBindings>::load(&user_path) {
+            Ok(bindings) => return Ok(bindings),
+            Err(error) => log::warn!(
+                "Can't read key bindings {}, using the shipped ones: {}",
+                user_path.display(),
+                error
+            ),
+        }
+    }
+    Ok(Bindings::<StringBindings>::load(shipped_bindings_path())?)
+}
+
+/// Saves the key bindings as the player's own
+pub fn save_user_key_bindings(bindings: &Bindings<StringBindings>) -> Result<PathBuf, String> {
+    let path = user_bindings_path();
+    if let Some(directory) = path.parent() {
+        std::fs::create_dir_all(directory)
+            .map_err(|error| format!("Can't create {}: {}", directory.display(), error))?;
+    }
+    bindings
+        .write(&path)
+        .map_err(|error| format!("Can't save key bindings {}: {}", path.display(), error))?;
+    Ok(path)
+}
+
+/// Every slot a key or button can be bound to, sorted by name
+///
+/// Axes that aren't emulated with a pair of buttons (e.g. controller sticks)
+/// can't be rebound with a key press, so they aren't listed.
+pub fn binding_slots(bindings: &Bindings<StringBindings>) -> Vec<BindingSlot> {
+    let mut slots: Vec<BindingSlot> = bindings
+        .actions()
+        .map(|action| BindingSlot::Action(action.clone()))
+        .collect();
+    for axis_name in bindings.axes() {
+        if let Some(Axis::Emulated { .. }) = bindings.axis(axis_name) {
+            slots.push(BindingSlot::AxisPositive(axis_name.clone()));
+            slots.push(BindingSlot::AxisNegative(axis_name.clone()));
+        }
+    }
+    slots.sort_by_key(BindingSlot::name);
+    slots
+}
+
+/// The buttons bound to the slot
+pub fn bound_buttons(bindings: &Bindings<StringBindings>, slot: &BindingSlot) -> Vec<Button> {
+    match slot {
+        BindingSlot::Action(action) => bindings
+            .action_bindings(action)
+            .flat_map(|combination| combination.iter().cloned())
+            .collect(),
+        BindingSlot::AxisPositive(axis_name) => match bindings.axis(axis_name) {
+            Some(Axis::Emulated { pos, .. }) => vec![*pos],
+            _ => Vec::new(),
+        },
+        BindingSlot::AxisNegative(axis_name) => match bindings.axis(axis_name) {
+            Some(Axis::Emulated { neg, .. }) => vec![*neg],
+            _ => Vec::new(),
+        },
+    }
+}
+
+/// The slot, other than the given one, that the button is already bound to, if any
+pub fn find_conflict(
+    bindings: &Bindings<StringBindings>,
+    button: Button,
+    slot: &BindingSlot,
+) -> Option<BindingSlot> {
+    binding_slots(bindings)
+        .into_iter()
+        .filter(|other_slot| other_slot != slot)
+        .find(|other_slot| bound_buttons(bindings, other_slot).contains(&button))
+}
+
+/// Binds the button to the slot, in place of whatever it was bound to before
+pub fn rebind(
+    bindings: &mut Bindings<StringBindings>,
+    slot: &BindingSlot,
+    button: Button,
+) -> Result<(), String> {
+    match slot {
+        BindingSlot::Action(action) => {
+            let combinations: Vec<Vec<Button>> = bindings
+                .action_bindings(action)
+                .map(|combination| combination.to_vec())
+                .collect();
+            for combination in combinations {
+                bindings.remove_action_binding(action, &combination);
+            }
+            bindings
+                .insert_action_binding(action.clone(), vec![button])
+                .map_err(|error| format!("Can't bind {:?} to {}: {}", button, action, error))
+        }
+        BindingSlot::AxisPositive(axis_name) | BindingSlot::AxisNegative(axis_name) => {
+            let (pos, neg) = match bindings.axis(axis_name) {
+                Some(Axis::Emulated { pos, neg }) => (*pos, *neg),
+                _ => return Err(format!("{} can't be bound to a key", axis_name)),
+            };
+            let axis = match slot {
+                BindingSlot::AxisPositive(_) => Axis::Emulated { pos: button, neg },
+                _ => Axis::Emulated { pos, neg: button },
+            };
+            bindings.remove_axis(axis_name);
+            bindings
+                .insert_axis(axis_name.clone(), axis)
+                .map(|_previous_axis| ())
+                .map_err(|error| format!("Can't bind {:?} to {}: {}", button, axis_name, error))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use amethyst::winit::VirtualKeyCode;
+
+    /// Bindings with a ship steered with A and D, and a fire button on SPACE
+    fn test_bindings() -> Bindings<StringBindings> {
+        let mut bindings = Bindings::<StringBindings>::new();
+        let axis = Axis::Emulated {
+            pos: Button::Key(VirtualKeyCode::D),
+            neg: Button::Key(VirtualKeyCode::A),
+        };
+        bindings.insert_axis("ship_p1", axis).unwrap();
+        bindings
+            .insert_action_binding("fire_p1", vec![Button::Key(VirtualKeyCode::Space)])
+            .unwrap();
+        bindings
+    }
+
+    #[test]
+    fn every_key_bindable_slot_is_listed() {
+        let slots = binding_slots(&test_bindings());
+        assert_eq!(
+            slots,
+            vec![
+                BindingSlot::Action("fire_p1".to_string()),
+                BindingSlot::AxisPositive("ship_p1".to_string()),
+                BindingSlot::AxisNegative("ship_p1".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn a_button_bound_elsewhere_is_a_conflict() {
+        let bindings = test_bindings();
+        let fire = BindingSlot::Action("fire_p1".to_string());
+        let right = BindingSlot::AxisPositive("ship_p1".to_string());
+        let d_key = Button::Key(VirtualKeyCode::D);
+        assert_eq!(find_conflict(&bindings, d_key, &fire), Some(right.clone()));
+        // binding a slot to the button it already has is no conflict
+        assert_eq!(find_conflict(&bindings, d_key, &right), None);
+        assert_eq!(
+            find_conflict(&bindings, Button::Key(VirtualKeyCode::Return), &fire),
+            None
+        );
+    }
+
+    #[test]
+    fn rebinding_half_an_axis_keeps_the_other_half() {
+        let mut bindings = test_bindings();
+        let left = BindingSlot::AxisNegative("ship_p1".to_string());
+        let right = BindingSlot::AxisPositive("ship_p1".to_string());
+        rebind(&mut bindings, &left, Button::Key(VirtualKeyCode::Left)).unwrap();
+        assert_eq!(
+            bound_buttons(&bindings, &left),
+            vec![Button::Key(VirtualKeyCode::Left)]
+        );
+        assert_eq!(
+            bound_buttons(&bindings, &right),
+            vec![Button::Key(VirtualKeyCode::D)]
+        );
+    }
+
+    #[test]
+    fn rebinding_an_action_replaces_its_buttons() {
+        let mut bindings = test_bindings();
+        let fire = BindingSlot::Action("fire_p1".to_string());
+        rebind(&mut bindings, &fire, Button::Key(VirtualKeyCode::Return)).unwrap();
+        assert_eq!(
+            bound_buttons(&bindings, &fire),
+            vec![Button::Key(VirtualKeyCode::Return)]
+        );
+        let right = BindingSlot::AxisPositive("ship_p1".to_string());
+        let space_key = Button::Key(VirtualKeyCode::Space);
+        assert_eq!(find_conflict(&bindings, space_key, &right), None);
+    }
+
+    #[test]
+    fn only_emulated_axes_can_be_bound_to_a_key() {
+        let mut bindings = test_bindings();
+        let slot = BindingSlot::AxisPositive("no_such_axis".to_string());
+        assert!(rebind(&mut bindings, &slot, Button::Key(VirtualKeyCode::W)).is_err());
+    }
+}
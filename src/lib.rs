@@ -0,0 +1,395 @@
+//! A simple space shooter game using Component-Oriented Programming (COP) and Amethyst
+//!
+//! Each part of the game is documented, providing patterns you can use
+//! to solve various problems in creating games that run under Amethyst.
+//!
+//! To summarise a Component-Oriented Programming model (also called Entity-Component System or ECS model),
+//! a product has the following classes of items:
+//!
+//! <table>
+//!   <tr>
+//!     <th>Class</th>
+//!     <th>Role</th>
+//!   </tr>
+//!   <tr>
+//!     <td><a href="components/index.html">components</a></td>
+//!     <td>a basic game object, with the data specific to it.</td>
+//!   </tr>
+//!   <tr>
+//!     <td><a href="entities/index.html">entities</a></td>
+//!     <td>collection of related components e.g. a game object, its location, material, mesh, etc.</td>
+//!   </tr>
+//!   <tr>
+//!     <td><a href="systems/index.html">systems</a></td>
+//!     <td>the sets of rules that act on entities by changing their data.</td>
+//!   </tr>
+//!   <tr>
+//!     <td><a href="resources/index.html">resources</a></td>
+//!     <td>data "global" to the game that the entity needs, e.g. the screen bounds.</td>
+//!   </tr>
+//! </table>
+//!
+//! In our example, each of those items are put in a separate module.
+//!
+//! In an Amethyst game, there are three other common elements:
+//!
+//! <table>
+//!   <tr>
+//!    <th>Item</th>
+//!    <th>Role</th>
+//!   </tr>
+//!   <tr>
+//!     <td><a href="struct.GameState.html">game state</a></td>
+//!     <td>Actions to take at the start of the game, on each cycle, and at the end of the game.</td>
+//!   </tr>
+//!   <tr>
+//!     <td><a href="struct.MenuState.html">menu state</a></td>
+//!     <td>The ship-select screen shown before the game starts.</td>
+//!   </tr>
+//!   <tr>
+//!     <td><a href="struct.ControlsState.html">controls state</a></td>
+//!     <td>The controls screen, where the players rebind their keys and buttons.</td>
+//!   </tr>
+//!   <tr>
+//!     <td><a href="struct.AchievementsState.html">achievements state</a></td>
+//!     <td>The achievements screen, listing which achievements have been unlocked.</td>
+//!   </tr>
+//!   <tr>
+//!     <td><a href="struct.LobbyState.html">lobby state</a></td>
+//!     <td>The network screen, where the players host or join a network game.</td>
+//!   </tr>
+//!   <tr>
+//!     <td><a href="struct.GameBundle.html">game bundle</a></td>
+//!     <td>The collection of systems that make up the game.</td>
+//!   </tr>
+//!   <tr>
+//!     <td><a href="struct.GameConfiguration.html">game configuration</td>
+//!     <td>The set of items used by the Game Designer to tune the game after the coding is complete (e.g. asteroid speed)</td>
+//!   </tr>
+//!   <tr>
+//!     <td><a href="struct.ShipRoster.html">ship roster</td>
+//!     <td>The ships the player can choose from, with their look and handling (e.g. thrust, fire rate)</td>
+//!   </tr>
+//!   <tr>
+//!     <td><a href="struct.Prefabs.html">prefabs</td>
+//!     <td>The templates the asteroids and explosions are made from, with their sprites named
+//!     (see <a href="struct.SpriteNames.html">sprite names</a>)</td>
+//!   </tr>
+//!   <tr>
+//!     <td><a href="struct.Backgrounds.html">backgrounds</td>
+//!     <td>The scrolling layers of images and stars behind each level</td>
+//!   </tr>
+//!   <tr>
+//!     <td><a href="struct.Hud.html">HUD layout</td>
+//!     <td>Where the lives, scores, multipliers, cooldown bars, power-up timers, high score and wave
+//!     sit over the arena</td>
+//!   </tr>
+//!   <tr>
+//!     <td><a href="struct.AchievementList.html">achievements</td>
+//!     <td>The achievements the players can unlock, with their conditions</td>
+//!   </tr>
+//!   <tr>
+//!     <td><a href="struct.Catalogues.html">localisation</td>
+//!     <td>The text of every message the players see, in each language, with a fallback language</td>
+//!   </tr>
+//!   <tr>
+//!     <td><a href="struct.Replay.html">replay</td>
+//!     <td>A recording of the players' controls that plays a game out again exactly</td>
+//!   </tr>
+//!   <tr>
+//!     <td><a href="network/index.html">network game</td>
+//!     <td>Two or more copies of the game playing one game over UDP, in lockstep,
+//!     checking every frame that they still agree</td>
+//!   </tr>
+//!   <tr>
+//!     <td><a href="agent/index.html">agent environment</td>
+//!     <td>The game run without a window, a frame at a time, for bots to play (also over JSON Lines)</td>
+//!   </tr>
+//!   <tr>
+//!     <td><a href="struct.SavedGame.html">saved game</td>
+//!     <td>A snapshot of a suspended game (ships, asteroids, lasers, scores, clock and random numbers),
+//!     to carry it on later</td>
+//!   </tr>
+//!   <tr>
+//!     <td><a href="struct.SessionRecord.html">telemetry</td>
+//!     <td>The statistics of each game (and, optionally, its events), saved as JSON Lines for balancing</td>
+//!   </tr>
+//! </table>
+//!
+//! These items are put in their own Rust files.
+
+#![deny(missing_docs)]
+
+mod achievements;
+mod achievements_state;
+pub mod agent;
+mod backgrounds;
+mod bundle;
+pub mod components;
+mod config;
+mod controls_state;
+pub mod entities;
+mod hud;
+mod key_bindings;
+mod lobby_state;
+mod localisation;
+mod menu_state;
+pub mod network;
+mod prefabs;
+mod replay;
+pub mod resources;
+mod roster;
+mod save_game;
+mod sprites;
+mod state;
+pub mod systems;
+mod telemetry;
+mod user_files;
+
+// public use so these things get documented
+pub use crate::achievements::{Achievement, AchievementCondition, AchievementList, ACHIEVEMENTS};
+pub use crate::achievements_state::AchievementsState;
+pub use crate::backgrounds::{BackgroundLayer, Backgrounds, LevelBackground, BACKGROUNDS};
+pub use crate::bundle::GameBundle;
+pub use crate::config::GameConfiguration;
+pub use crate::config::GAME_CONFIGURATION;
+pub use crate::config::{configuration_hash, gameplay_hash};
+pub use crate::config::{
+    AxisHandling, DampedMovement, DirectMovement, InertialMovement, MovementModel,
+    ShipEdgeBehaviour, ValueRange, WallBehaviour,
+};
+pub use crate::controls_state::ControlsState;
+pub use crate::hud::{Hud, HudElement, HUD};
+pub use crate::key_bindings::{
+    binding_slots, bound_buttons, find_conflict, load_key_bindings, rebind,
+    save_user_key_bindings, shipped_bindings_path, user_bindings_path, BindingSlot,
+};
+pub use crate::lobby_state::LobbyState;
+pub use crate::localisation::{fill_in, Catalogue, Catalogues, CATALOGUES, WINDOW_TITLE};
+pub use crate::menu_state::MenuState;
+pub use crate::prefabs::{unknown_sprite_names, AsteroidPrefab, EntityPrefab, Prefabs, PREFABS};
+pub use crate::replay::{
+    exact_timestep, Replay, ReplayFrame, Session, SessionMode, REPLAY_TIMESTEP, REPLAY_VERSION,
+};
+pub use crate::roster::{ShipDefinition, ShipRoster, WeaponDefinition, SHIP_ROSTER};
+pub use crate::save_game::{SavedAsteroid, SavedGame, SavedLaser, SavedShip, SAVE_VERSION};
+pub use crate::sprites::{
+    sprite_number, SpriteAtlas, SpriteNames, SubTexture, SPRITE_ATLAS, SPRITE_NAMES,
+};
+pub use crate::state::GameState;
+pub use crate::telemetry::{
+    append_json_lines, events_path, sessions_path, SessionRecord, TelemetryEvent,
+    TelemetryEventKind,
+};
+pub use crate::user_files::{user_config_path, user_data_path};
+
+use amethyst::config::Config;
+use amethyst::core::frame_limiter::FrameRateLimitStrategy;
+use amethyst::core::transform::TransformBundle;
+use amethyst::input::InputBundle;
+#[cfg(feature = "gamepad")]
+use amethyst::input::SdlEventsSystemDesc;
+use amethyst::input::StringBindings;
+use amethyst::prelude::*;
+use amethyst::renderer::plugins::RenderDebugLines;
+use amethyst::renderer::plugins::RenderFlat2D;
+use amethyst::renderer::plugins::RenderToWindow;
+use amethyst::renderer::types::DefaultBackend;
+use amethyst::renderer::RenderingBundle;
+use amethyst::ui::RenderUi;
+use amethyst::ui::UiBundle;
+use amethyst::utils::application_root_dir;
+use amethyst::utils::fps_counter::FpsCounterBundle;
+use amethyst::window::DisplayConfig;
+use std::io::BufReader;
+use std::net::TcpListener;
+use std::path::PathBuf;
+
+use crate::agent::AgentEnvironment;
+use crate::resources::Localisation;
+
+const BACKGROUND_COLOUR: [f32; 4] = [0.25, 0.25, 0.25, 0.0]; // dark grey
+const USAGE: &str =
+    "Usage: space_shooter [--record <file> | --replay <file> | --agent | --agent-tcp <address>]";
+
+/// Run the game
+///
+/// This function does the following:
+///
+/// 1. Checks that the sprites named by the prefabs, ship roster and backgrounds
+///    are all in the sprite sheet;
+/// 2. Works out from the command line whether to record the game (`--record <file>`)
+///    or play back a replay (`--replay <file>`);
+/// 3. Loads up the language the players chose, and the display configuration
+///    from a RON file in the resources folder, with the window title in that language,
+///    and the input bindings from the player's own bindings file, if they have one,
+///    or else from the one in the resources folder;
+/// 4. Sets out the rendering pipeline: background rendering pass, sprite rendering pass,
+///    debug lines rendering pass (for the [debug overlay](resources/struct.DebugOverlay.html)) and UI rendering pass;
+/// 5. Creates a new Amethyst game data object with all the appropriate bundles;
+/// 6. Creates a new Amethyst game with the game data, the [Session], the [Localisation] and our [MenuState],
+///    limiting the frame rate to match the fixed timestep, if there is one;
+/// 7. Sets the game running. Control is now passed to the menu state,
+///    which hands over to the game state once the player has chosen a ship.
+pub fn run() -> Result<(), amethyst::Error> {
+    let _ = &config::GAME_CONFIGURATION; // initialises game constants
+    let _ = &roster::SHIP_ROSTER; // loads the ships the player can choose from
+    let _ = &backgrounds::BACKGROUNDS; // loads the backgrounds of the levels
+    let _ = &sprites::SPRITE_ATLAS; // reads the names and places of the sprites in the sprite sheet
+    let _ = &prefabs::PREFABS; // loads the templates of the asteroids and explosions
+    let _ = &hud::HUD; // loads the layout of the HUD
+    let _ = &achievements::ACHIEVEMENTS; // loads the achievements and their conditions
+    let _ = &localisation::CATALOGUES; // loads the text of the messages in every language
+    for language in CATALOGUES.languages.keys() {
+        let missing = CATALOGUES.missing_messages(language);
+        if !missing.is_empty() {
+            log::warn!(
+                "The {} catalogue falls back on these messages: {}",
+                language,
+                missing.join(", ")
+            );
+        }
+    }
+    let unknown_sprites = unknown_sprite_names();
+    if !unknown_sprites.is_empty() {
+        return Err(amethyst::Error::from_string(format!(
+            "These sprites aren't in the sprite sheet: {}",
+            unknown_sprites.join(", ")
+        )));
+    }
+    let session = session_from_arguments(std::env::args().skip(1))
+        .map_err(amethyst::Error::from_string)?;
+
+    let application_root = application_root_dir()?;
+
+    // Set the display configuration path to <package root>/resources/display_config.ron.
+    let display_config_path = application_root.join("resources/display_config.ron");
+    // The title in the display configuration is a message ID
+    let localisation = Localisation::load_or_default();
+    let mut display_config = DisplayConfig::load(display_config_path)?;
+    display_config.title = localisation.text(&display_config.title);
+
+    // Load up the player's key bindings (or the shipped ones) and the resources path
+    let key_bindings = load_key_bindings()?;
+
+    let resources_path = application_root.join("assets");
+
+    // Create a game data with all our systems bundled into it
+    let game_data = GameDataBuilder::default();
+    // With the gamepad feature, controller events (including plugging controllers in
+    // and pulling them out) are fed to the input handler alongside keyboard events
+    #[cfg(feature = "gamepad")]
+    let game_data =
+        game_data.with_thread_local_desc(SdlEventsSystemDesc::<StringBindings>::new(None));
+    let game_data = game_data
+        .with_bundle(
+            InputBundle::<StringBindings>::new().with_bindings(key_bindings),
+        )?
+        .with_bundle(GameBundle)?
+        .with_bundle(TransformBundle::new())?
+        .with_bundle(UiBundle::<StringBindings>::new())?
+        .with_bundle(FpsCounterBundle)?
+        .with_bundle(
+            RenderingBundle::<DefaultBackend>::new()
+                .with_plugin(
+                    RenderToWindow::from_config(display_config).with_clear(BACKGROUND_COLOUR),
+                )
+                .with_plugin(RenderFlat2D::default())
+                .with_plugin(RenderDebugLines::default())
+                .with_plugin(RenderUi::default()),
+        )?;
+
+    // Create a game with out game data, starting with the ship-select screen.
+    // With a fixed timestep, each frame moves the game on by the same amount of time,
+    // so we run the frames at the matching rate.
+    let mut game_builder = Application::build(resources_path, MenuState::default())?;
+    if let Some(timestep) = session.timestep() {
+        let frames_per_second = (1.0 / timestep).round().max(1.0) as u32;
+        game_builder =
+            game_builder.with_frame_limit(FrameRateLimitStrategy::Sleep, frames_per_second);
+    }
+    let mut game = game_builder
+        .with_resource(session)
+        .with_resource(localisation)
+        .build(game_data)?;
+
+    Ok(game.run())
+}
+
+/// Sets up the session from the command line arguments
+///
+/// * `--record <file>` records the game to the given replay file;
+/// * `--replay <file>` plays back the given replay file;
+/// * anything else is an error.
+///
+/// With no arguments, the game is simply played.
+fn session_from_arguments<I>(mut arguments: I) -> Result<Session, String>
+where
+    I: Iterator<Item = String>,
+{
+    let session = match (arguments.next(), arguments.next()) {
+        (None, _) => Session::live(),
+        (Some(option), Some(path)) if option == "--record" => {
+            Session::record(PathBuf::from(path))
+        }
+        (Some(option), Some(path)) if option == "--replay" => {
+            let replay = Replay::load_replay(&PathBuf::from(path))?;
+            if SHIP_ROSTER.ship_named(&replay.ship).is_none() {
+                return Err(format!("The replay's ship, {}, isn't in the roster", replay.ship));
+            }
+            if replay.player_count != GAME_CONFIGURATION.player_count() {
+                return Err(format!(
+                    "The replay has {} players, but the game is set up for {}",
+                    replay.player_count,
+                    GAME_CONFIGURATION.player_count()
+                ));
+            }
+            Session::play_back(replay)
+        }
+        _ => return Err(USAGE.to_string()),
+    };
+    if let Some(unexpected) = arguments.next() {
+        return Err(format!("Unexpected argument: {}", unexpected));
+    }
+    Ok(session)
+}
+
+/// Lets an [agent](agent/index.html) drive the game, without a window
+///
+/// * `--agent` answers the agent's requests on stdin, on stdout;
+/// * `--agent-tcp <address>` listens on the given address, and answers each agent that connects in turn.
+pub fn run_agent(arguments: &[String]) -> Result<(), String> {
+    let unknown_sprites = unknown_sprite_names();
+    if !unknown_sprites.is_empty() {
+        return Err(format!(
+            "These sprites aren't in the sprite sheet: {}",
+            unknown_sprites.join(", ")
+        ));
+    }
+    let assets_path = application_root_dir()
+        .map_err(|error| error.to_string())?
+        .join("assets");
+    let mut environment = AgentEnvironment::new(&assets_path).map_err(|error| error.to_string())?;
+    match arguments {
+        [option] if option == "--agent" => {
+            let stdin = std::io::stdin();
+            let stdout = std::io::stdout();
+            environment
+                .serve(stdin.lock(), stdout.lock())
+                .map_err(|error| error.to_string())
+        }
+        [option, address] if option == "--agent-tcp" => {
+            let listener = TcpListener::bind(address)
+                .map_err(|error| format!("Can't listen on {}: {}", address, error))?;
+            for stream in listener.incoming() {
+                let stream = stream.map_err(|error| error.to_string())?;
+                let reader = BufReader::new(stream.try_clone().map_err(|error| error.to_string())?);
+                if let Err(error) = environment.serve(reader, stream) {
+                    eprintln!("Lost the agent: {}", error);
+                }
+            }
+            Ok(())
+        }
+        _ => Err(USAGE.to_string()),
+    }
+}
@@ -0,0 +1,240 @@
+use amethyst::ecs::prelude::Entity;
+use amethyst::input::{is_close_requested, is_key_down};
+use amethyst::prelude::*;
+use amethyst::ui::UiText;
+use amethyst::winit::{Event, VirtualKeyCode, WindowEvent};
+use rand::{thread_rng, Rng};
+
+use crate::config::GAME_CONFIGURATION;
+use crate::entities::load_font;
+use crate::menu_state::{create_text, MenuState};
+use crate::network::{Lobby, LobbyStatus, NetMessage};
+use crate::replay::Session;
+use crate::resources::Localisation;
+use crate::roster::{ShipDefinition, SHIP_ROSTER};
+use crate::state::GameState;
+
+/// The network screen, where the players get together for a [network game](network/index.html)
+///
+/// This state demonstrates several standard patterns:
+///
+/// 1. Typing text in, through the window's character events
+/// 2. Polling something outside the game (the network) every frame, and reporting on it
+/// 3. Setting up the resources of the next state (the session, the lockstep and the ship)
+///    before switching to it
+///
+/// H hosts a game, flown with the ship chosen on the ship-select screen.
+/// J asks for the host's address (the game configuration's `network_join_address` to start with),
+/// and RETURN joins the host. ESC goes back to the ship-select screen.
+/// The game starts as soon as every player has joined.
+#[derive(Default)]
+pub struct LobbyState {
+    /// The gathering of the players, once the player has chosen to host or join
+    lobby: Option<Lobby>,
+    /// The address of the host to join, as typed so far
+    join_address: String,
+    /// Whether or not the player is typing in the address of the host
+    is_editing: bool,
+    /// The UI entity telling the player what's going on
+    status: Option<Entity>,
+    /// All the UI entities of the screen, so we can delete them when we leave
+    ui_entities: Vec<Entity>,
+}
+
+impl SimpleState for LobbyState {
+    fn on_start(&mut self, state_data: StateData<'_, GameData<'_, '_>>) {
+        let world = state_data.world;
+        let font = load_font(world);
+        let (title_text, instructions_text) = {
+            let localisation = world.read_resource::<Localisation>();
+            (
+                localisation.text("lobby-title"),
+                localisation.text("lobby-instructions"),
+            )
+        };
+        let title = create_text(world, &font, "lobby_title", &title_text, 120., 36.);
+        let instructions = create_text(
+            world,
+            &font,
+            "lobby_instructions",
+            &instructions_text,
+            -160.,
+            14.,
+        );
+        let status = create_text(world, &font, "lobby_status", "", -185., 14.);
+        self.join_address = GAME_CONFIGURATION.network_join_address.clone();
+        self.status = Some(status);
+        self.ui_entities = vec![title, instructions, status];
+        if GAME_CONFIGURATION.player_count() < 2 {
+            self.show_status(world, "lobby-one-player", &[]);
+        }
+    }
+
+    fn on_stop(&mut self, state_data: StateData<'_, GameData<'_, '_>>) {
+        let _result = state_data.world.delete_entities(&self.ui_entities);
+        self.ui_entities.clear();
+    }
+
+    fn handle_event(
+        &mut self,
+        state_data: StateData<'_, GameData<'_, '_>>,
+        event: StateEvent,
+    ) -> SimpleTrans {
+        let world = state_data.world;
+        if let StateEvent::Window(event) = event {
+            if is_close_requested(&event) {
+                return Trans::Quit;
+            }
+            if self.is_editing {
+                self.edit_address(world, &event);
+            } else if is_key_down(&event, VirtualKeyCode::Escape) {
+                return Trans::Switch(Box::new(MenuState::default()));
+            } else if GAME_CONFIGURATION.player_count() < 2 || self.lobby.is_some() {
+                // nothing else to do until the game starts
+            } else if is_key_down(&event, VirtualKeyCode::H) {
+                let ship_name = world.read_resource::<ShipDefinition>().name.clone();
+                let port = GAME_CONFIGURATION.network_port;
+                self.open_lobby(world, Lobby::host(port, &ship_name, thread_rng().gen()));
+            } else if is_key_down(&event, VirtualKeyCode::J) {
+                self.is_editing = true;
+                self.show_address(world);
+            }
+        }
+        Trans::None
+    }
+
+    // This code tells Amethyst to run all the systems in your game data.
+    fn update(&mut self, state_data: &mut StateData<'_, GameData<'_, '_>>) -> SimpleTrans {
+        state_data.data.update(&state_data.world);
+        let world = &mut *state_data.world;
+        let mut lobby = match self.lobby.take() {
+            Some(lobby) => lobby,
+            None => return Trans::None,
+        };
+        lobby.poll();
+        let start = match lobby.start() {
+            Some(NetMessage::Start {
+                seed,
+                timestep,
+                ship,
+                ..
+            }) => Some((*seed, *timestep, ship.clone())),
+            _ => None,
+        };
+        if let Some((seed, timestep, ship)) = start {
+            if let Ok(lockstep) = lobby.into_lockstep() {
+                // the host's choice of ship is flown by everyone
+                if let Some(ship_definition) = SHIP_ROSTER.ship_named(&ship) {
+                    world.insert(ship_definition.clone());
+                }
+                world.insert(Session::networked(seed, timestep));
+                world.insert(lockstep);
+                return Trans::Switch(Box::new(GameState::default()));
+            }
+            return Trans::None;
+        }
+        self.show_lobby_status(world, lobby.status());
+        // a lobby that was turned down or failed is dropped, so the player can try again
+        if let LobbyStatus::Rejected(_) | LobbyStatus::Failed(_) = lobby.status() {
+            return Trans::None;
+        }
+        self.lobby = Some(lobby);
+        Trans::None
+    }
+}
+
+impl LobbyState {
+    /// Adds the typed character to the host's address, or takes one away (BACKSPACE),
+    /// joins the host (RETURN) or stops typing (ESC)
+    fn edit_address(&mut self, world: &mut World, event: &Event) {
+        if is_key_down(event, VirtualKeyCode::Return) {
+            self.is_editing = false;
+            self.open_lobby(world, Lobby::join(&self.join_address));
+            return;
+        }
+        if is_key_down(event, VirtualKeyCode::Escape) {
+            self.is_editing = false;
+            self.show_message(world, String::new());
+            return;
+        }
+        if is_key_down(event, VirtualKeyCode::Back) {
+            self.join_address.pop();
+        } else if let Event::WindowEvent {
+            event: WindowEvent::ReceivedCharacter(character),
+            ..
+        } = event
+        {
+            // only what can make up an address and port: no J from the key that started the typing
+            if character.is_ascii_hexdigit() || ".:[]".contains(*character) {
+                self.join_address.push(*character);
+            }
+        }
+        self.show_address(world);
+    }
+
+    /// Starts hosting or joining, or says why we can't
+    fn open_lobby(&mut self, world: &mut World, lobby: Result<Lobby, String>) {
+        match lobby {
+            Ok(lobby) => {
+                self.show_lobby_status(world, lobby.status());
+                self.lobby = Some(lobby);
+            }
+            Err(error) => self.show_lobby_status(world, &LobbyStatus::Failed(error)),
+        }
+    }
+
+    /// Shows the address of the host, as typed so far
+    fn show_address(&self, world: &mut World) {
+        let arguments = [("address", self.join_address.clone())];
+        self.show_status(world, "lobby-address", &arguments);
+    }
+
+    /// Shows how the gathering of the players is going
+    fn show_lobby_status(&self, world: &mut World, status: &LobbyStatus) {
+        match status {
+            LobbyStatus::Hosting { joined, needed } => {
+                let arguments = [
+                    ("port", GAME_CONFIGURATION.network_port.to_string()),
+                    ("joined", joined.to_string()),
+                    ("needed", needed.to_string()),
+                ];
+                self.show_status(world, "lobby-hosting", &arguments);
+            }
+            LobbyStatus::Joining => {
+                let arguments = [("address", self.join_address.clone())];
+                self.show_status(world, "lobby-joining", &arguments);
+            }
+            LobbyStatus::Joined { player } => {
+                let arguments = [("player", (player + 1).to_string())];
+                self.show_status(world, "lobby-joined", &arguments);
+            }
+            LobbyStatus::Started => {}
+            LobbyStatus::Rejected(reason) => {
+                let (id, arguments) = reason.message();
+                let error = world.read_resource::<Localisation>().format(id, &arguments);
+                log::warn!("{}", error);
+                self.show_status(world, "lobby-failed", &[("error", error)]);
+            }
+            LobbyStatus::Failed(error) => {
+                log::warn!("{}", error);
+                let arguments = [("error", error.clone())];
+                self.show_status(world, "lobby-failed", &arguments);
+            }
+        }
+    }
+
+    /// Shows the given message under the instructions, in the chosen language
+    fn show_status(&self, world: &mut World, id: &str, arguments: &[(&str, String)]) {
+        let message = world.read_resource::<Localisation>().format(id, arguments);
+        self.show_message(world, message);
+    }
+
+    /// Shows the given text under the instructions, as it is
+    fn show_message(&self, world: &mut World, message: String) {
+        if let Some(status) = self.status {
+            if let Some(ui_text) = world.write_storage::<UiText>().get_mut(status) {
+                ui_text.text = message;
+            }
+        }
+    }
+}
@@ -0,0 +1,191 @@
+//! The text shown to the players, in each of the languages the game speaks
+//!
+//! Every piece of text the players see is looked up by its message ID
+//! (e.g. "menu-title") in the catalogue of the language they chose.
+//! Like the [HUD layout](struct.Hud.html), the catalogues are loaded from RON files,
+//! one per language in "locales" in resources (e.g. "en.ron" for English),
+//! so a translator can add a language without having to recompile the code.
+//!
+//! A message can leave gaps for the values it shows, named in braces,
+//! as in Fluent: "WAVE {wave}" is filled in with the number of the wave.
+//!
+//! A message missing from the chosen language's catalogue is taken from
+//! the fallback language's (see [GameConfiguration](struct.GameConfiguration.html)),
+//! and a message missing from both shows its message ID, so it is easy to spot.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use amethyst::config::Config;
+use lazy_static::lazy_static;
+use serde_derive::{Deserialize, Serialize};
+
+use crate::config::GAME_CONFIGURATION;
+
+/// The message ID of the window title, which is what "display_config.ron" in resources gives as the title
+pub const WINDOW_TITLE: &str = "window-title";
+
+/// The messages of one language, keyed by message ID
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct Catalogue {
+    /// The name of the language, in the language itself (e.g. "Français")
+    pub name: String,
+    /// The text of each message, keyed by message ID
+    pub messages: BTreeMap<String, String>,
+}
+
+/// The catalogues of all the languages, keyed by language code (e.g. "en")
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Catalogues {
+    /// The catalogues, keyed by the name of their file without the ".ron"
+    pub languages: BTreeMap<String, Catalogue>,
+}
+
+impl Catalogues {
+    /// Loads every catalogue in the given directory
+    pub fn load_directory(directory: &Path) -> Result<Catalogues, String> {
+        let entries = std::fs::read_dir(directory)
+            .map_err(|error| format!("Can't read {}: {}", directory.display(), error))?;
+        let mut languages = BTreeMap::new();
+        for entry in entries.filter_map(Result::ok) {
+            let path = entry.path();
+            if path.extension().map_or(true, |extension| extension != "ron") {
+                continue;
+            }
+            if let Some(language) = path.file_stem().and_then(|stem| stem.to_str()) {
+                let catalogue = Catalogue::load(&path)
+                    .map_err(|error| format!("Can't read {}: {}", path.display(), error))?;
+                languages.insert(language.to_string(), catalogue);
+            }
+        }
+        Ok(Catalogues { languages })
+    }
+
+    /// Whether or not there is a catalogue for the given language
+    pub fn has_language(&self, language: &str) -> bool {
+        self.languages.contains_key(language)
+    }
+
+    /// The text of the given message in the given language,
+    /// or else in the fallback language, or else the message ID itself
+    pub fn message<'a>(&'a self, language: &str, id: &'a str) -> &'a str {
+        [language, GAME_CONFIGURATION.fallback_language.as_str()]
+            .iter()
+            .filter_map(|language| self.languages.get(*language))
+            .find_map(|catalogue| catalogue.messages.get(id))
+            .map_or(id, String::as_str)
+    }
+
+    /// The language after the given one, in alphabetical order of language code,
+    /// going back to the first after the last
+    pub fn next_language(&self, language: &str) -> Option<&str> {
+        self.languages
+            .keys()
+            .find(|code| code.as_str() > language)
+            .or_else(|| self.languages.keys().next())
+            .map(String::as_str)
+    }
+
+    /// The message IDs of the fallback language that the given language doesn't have
+    pub fn missing_messages(&self, language: &str) -> Vec<&str> {
+        match (
+            self.languages.get(language),
+            self.languages.get(&GAME_CONFIGURATION.fallback_language),
+        ) {
+            (Some(catalogue), Some(fallback)) => fallback
+                .messages
+                .keys()
+                .filter(|id| !catalogue.messages.contains_key(*id))
+                .map(String::as_str)
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+}
+
+/// Fills in each `{name}` gap of the message with the value of the argument of that name
+///
+/// Gaps with no matching argument are left as they are.
+pub fn fill_in(message: &str, arguments: &[(&str, String)]) -> String {
+    arguments
+        .iter()
+        .fold(message.to_string(), |text, (name, value)| {
+            text.replace(&format!("{{{}}}", name), value)
+        })
+}
+
+lazy_static! {
+    /// The actual [catalogues](struct.Catalogues.html) of all the languages.
+    ///
+    /// The catalogues are automatically loaded on startup
+    /// from the files in "locales" in resources.
+    pub static ref CATALOGUES: Catalogues = {
+        let locales_path = format!("{}/resources/locales", env!("CARGO_MANIFEST_DIR"));
+        let catalogues = Catalogues::load_directory(Path::new(&locales_path)).unwrap();
+        assert!(
+            catalogues.has_language(&GAME_CONFIGURATION.fallback_language),
+            "there is no catalogue for the fallback language"
+        );
+        catalogues
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A catalogue with the given messages
+    fn catalogue(name: &str, messages: &[(&str, &str)]) -> Catalogue {
+        Catalogue {
+            name: name.to_string(),
+            messages: messages
+                .iter()
+                .map(|(id, text)| (id.to_string(), text.to_string()))
+                .collect(),
+        }
+    }
+
+    /// The fallback language, with a title and a score, French, with just a title,
+    /// and German, with nothing
+    fn test_catalogues() -> Catalogues {
+        let mut languages = BTreeMap::new();
+        languages.insert(
+            GAME_CONFIGURATION.fallback_language.clone(),
+            catalogue("Fallback", &[("title", "SPACE SHOOTER"), ("score", "SCORE {score}")]),
+        );
+        languages.insert("fr".to_string(), catalogue("Français", &[("title", "TIREUR SPATIAL")]));
+        languages.insert("de".to_string(), catalogue("Deutsch", &[]));
+        Catalogues { languages }
+    }
+
+    #[test]
+    fn gaps_are_filled_in_by_name() {
+        let arguments = [("wave", "3".to_string()), ("score", "120".to_string())];
+        assert_eq!(fill_in("WAVE {wave}", &arguments), "WAVE 3");
+        assert_eq!(fill_in("{score} / {score}", &arguments), "120 / 120");
+        assert_eq!(fill_in("LIVES {lives}", &arguments), "LIVES {lives}");
+    }
+
+    #[test]
+    fn missing_messages_fall_back_then_show_their_id() {
+        let catalogues = test_catalogues();
+        assert_eq!(catalogues.message("fr", "title"), "TIREUR SPATIAL");
+        assert_eq!(catalogues.message("fr", "score"), "SCORE {score}");
+        assert_eq!(catalogues.message("xx", "title"), "SPACE SHOOTER");
+        assert_eq!(catalogues.message("fr", "no-such-message"), "no-such-message");
+        assert_eq!(catalogues.missing_messages("fr"), vec!["score"]);
+        assert!(catalogues.missing_messages("xx").is_empty());
+    }
+
+    #[test]
+    fn the_next_language_wraps_around_to_the_first() {
+        let catalogues = test_catalogues();
+        // the codes come in alphabetical order
+        let codes: Vec<&str> = catalogues.languages.keys().map(String::as_str).collect();
+        let (first, last) = (codes[0], codes[codes.len() - 1]);
+        assert_eq!(catalogues.next_language(first), Some(codes[1]));
+        assert_eq!(catalogues.next_language(last), Some(first));
+        assert_eq!(catalogues.next_language("zz"), Some(first));
+        assert_eq!(Catalogues::default().next_language(first), None);
+    }
+}
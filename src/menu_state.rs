@@ -0,0 +1,323 @@
+use amethyst::assets::Handle;
+use amethyst::ecs::prelude::Entity;
+use amethyst::input::{is_close_requested, is_key_down};
+use amethyst::prelude::*;
+use amethyst::renderer::{SpriteRender, SpriteSheet};
+use amethyst::ui::{Anchor, FontAsset, LineMode, UiImage, UiText, UiTransform};
+use amethyst::window::Window;
+use amethyst::winit::VirtualKeyCode;
+
+use crate::achievements_state::AchievementsState;
+use crate::controls_state::ControlsState;
+use crate::entities::{load_font, load_sprite_sheet};
+use crate::lobby_state::LobbyState;
+use crate::localisation::WINDOW_TITLE;
+use crate::replay::{Session, SessionMode};
+use crate::resources::Localisation;
+use crate::roster::{ShipDefinition, SHIP_ROSTER};
+use crate::save_game::SavedGame;
+use crate::sprites::sprite_number;
+use crate::state::GameState;
+
+const TEXT_COLOUR: [f32; 4] = [1.0, 1.0, 1.0, 1.0]; // white
+
+/// The ship-select screen shown before the game starts
+///
+/// This menu state demonstrates several standard patterns:
+///
+/// 1. Building a simple screen out of UI entities, and deleting them when we leave the state
+/// 2. Changing what's on the screen in response to key presses
+/// 3. Passing a choice from one state to the next through a resource
+///    (the [ShipDefinition](struct.ShipDefinition.html) of the chosen ship)
+/// 4. Switching to another state (the [GameState](struct.GameState.html)) when the player is ready
+///    (or to the [ControlsState](struct.ControlsState.html) to rebind the keys,
+///    or the [AchievementsState](struct.AchievementsState.html) to see the achievements,
+///    or the [LobbyState](struct.LobbyState.html) to play over the network)
+/// 5. Switching language (L) on the fly: every message on the screen,
+///    and the window title, is looked up again in the newly chosen language
+///    (see [Localisation](resources/struct.Localisation.html))
+///
+/// If a game was suspended, R resumes it with the ship it was played with
+/// (see [saved games](struct.SavedGame.html)); the saved game is then deleted,
+/// so it can only be carried on once. Recordings and replays always start a new game.
+///
+/// When a replay is being played back, the menu is skipped,
+/// and the game starts straight away with the ship chosen in the replay.
+#[derive(Default)]
+pub struct MenuState {
+    /// The position in the ship roster of the ship currently shown
+    selected_ship: usize,
+    /// The sprite sheet holding the ship sprites
+    sprite_sheet_handle: Option<Handle<SpriteSheet>>,
+    /// The UI entity showing the selected ship
+    ship_image: Option<Entity>,
+    /// The UI entity describing the selected ship
+    ship_description: Option<Entity>,
+    /// The UI entities showing a fixed message, each with its message ID
+    messages: Vec<(Entity, &'static str)>,
+    /// The UI entity offering to resume a suspended game, or saying why it can't be
+    resume_status: Option<Entity>,
+    /// All the UI entities of the menu, so we can delete them when we leave
+    ui_entities: Vec<Entity>,
+    /// The ship chosen in the replay being played back, if any
+    replay_ship: Option<ShipDefinition>,
+}
+
+impl SimpleState for MenuState {
+    fn on_start(&mut self, state_data: StateData<'_, GameData<'_, '_>>) {
+        let world = state_data.world;
+        self.replay_ship = {
+            let session = world.read_resource::<Session>();
+            if session.mode == SessionMode::Replaying {
+                SHIP_ROSTER.ship_named(&session.replay.ship).cloned()
+            } else {
+                None
+            }
+        };
+        if self.replay_ship.is_some() {
+            return;
+        }
+        let sprite_sheet_handle = load_sprite_sheet(world);
+        let font = load_font(world);
+
+        let title = create_text(world, &font, "title", "", 120., 36.);
+        let ship_description = create_text(world, &font, "ship_description", "", -100., 20.);
+        let instructions = create_text(world, &font, "instructions", "", -160., 14.);
+        let more_instructions = create_text(world, &font, "more_instructions", "", -185., 14.);
+        let resume_status = create_text(world, &font, "resume_status", "", -210., 14.);
+        let ship_image = world
+            .create_entity()
+            .with(UiTransform::new(
+                "ship_image".to_string(),
+                Anchor::Middle,
+                Anchor::Middle,
+                0.,
+                0.,
+                0.,
+                0.,
+                0.,
+            ))
+            .with(UiImage::Sprite(SpriteRender {
+                sprite_sheet: sprite_sheet_handle.clone(),
+                sprite_number: 0,
+            }))
+            .build();
+
+        self.sprite_sheet_handle = Some(sprite_sheet_handle);
+        self.ship_image = Some(ship_image);
+        self.ship_description = Some(ship_description);
+        self.messages = vec![
+            (title, "menu-title"),
+            (instructions, "menu-instructions"),
+            (more_instructions, "menu-more-instructions"),
+        ];
+        if can_resume(world) {
+            self.messages.push((resume_status, "menu-resume"));
+        }
+        self.resume_status = Some(resume_status);
+        self.ui_entities = vec![
+            title,
+            ship_description,
+            instructions,
+            more_instructions,
+            resume_status,
+            ship_image,
+        ];
+        self.show_messages(world);
+        self.show_selected_ship(world);
+    }
+
+    fn on_stop(&mut self, state_data: StateData<'_, GameData<'_, '_>>) {
+        let _result = state_data.world.delete_entities(&self.ui_entities);
+        self.ui_entities.clear();
+    }
+
+    fn handle_event(
+        &mut self,
+        state_data: StateData<'_, GameData<'_, '_>>,
+        event: StateEvent,
+    ) -> SimpleTrans {
+        if let StateEvent::Window(event) = event {
+            let ship_count = SHIP_ROSTER.ships.len();
+            if is_close_requested(&event) || is_key_down(&event, VirtualKeyCode::Escape) {
+                return Trans::Quit;
+            } else if is_key_down(&event, VirtualKeyCode::A)
+                || is_key_down(&event, VirtualKeyCode::Left)
+            {
+                self.selected_ship = (self.selected_ship + ship_count - 1) % ship_count;
+                self.show_selected_ship(state_data.world);
+            } else if is_key_down(&event, VirtualKeyCode::D)
+                || is_key_down(&event, VirtualKeyCode::Right)
+            {
+                self.selected_ship = (self.selected_ship + 1) % ship_count;
+                self.show_selected_ship(state_data.world);
+            } else if is_key_down(&event, VirtualKeyCode::Space)
+                || is_key_down(&event, VirtualKeyCode::Return)
+            {
+                // hand the chosen ship over to the game state
+                state_data
+                    .world
+                    .insert(SHIP_ROSTER.ships[self.selected_ship].clone());
+                return Trans::Switch(Box::new(GameState::default()));
+            } else if is_key_down(&event, VirtualKeyCode::R) && can_resume(state_data.world) {
+                return self.resume(state_data.world);
+            } else if is_key_down(&event, VirtualKeyCode::C) {
+                return Trans::Switch(Box::new(ControlsState::default()));
+            } else if is_key_down(&event, VirtualKeyCode::T) {
+                return Trans::Switch(Box::new(AchievementsState::default()));
+            } else if is_key_down(&event, VirtualKeyCode::N) {
+                // the host's ship is flown in a network game
+                state_data
+                    .world
+                    .insert(SHIP_ROSTER.ships[self.selected_ship].clone());
+                return Trans::Switch(Box::new(LobbyState::default()));
+            } else if is_key_down(&event, VirtualKeyCode::L) {
+                switch_language(state_data.world);
+                self.show_messages(state_data.world);
+                self.show_selected_ship(state_data.world);
+            }
+        }
+        Trans::None
+    }
+
+    // This code tells Amethyst to run all the systems in your game data.
+    fn update(&mut self, state_data: &mut StateData<'_, GameData<'_, '_>>) -> SimpleTrans {
+        state_data.data.update(&state_data.world);
+        // a replay plays the ship it recorded
+        if let Some(ship_definition) = self.replay_ship.take() {
+            state_data.world.insert(ship_definition);
+            return Trans::Switch(Box::new(GameState::default()));
+        }
+        Trans::None
+    }
+}
+
+impl MenuState {
+    /// Carries on the suspended game, or says why it can't be
+    fn resume(&mut self, world: &mut World) -> SimpleTrans {
+        let path = SavedGame::path();
+        match SavedGame::load_saved_game(&path) {
+            Ok(saved_game) => {
+                let _result = std::fs::remove_file(&path);
+                if let Some(ship_definition) = SHIP_ROSTER.ship_named(&saved_game.ship) {
+                    world.insert(ship_definition.clone());
+                }
+                Trans::Switch(Box::new(GameState::resuming(saved_game)))
+            }
+            Err(error) => {
+                log::warn!("{}", error);
+                // the message stays put if the language changes, so it isn't on the list
+                self.messages.retain(|(_entity, id)| *id != "menu-resume");
+                let text = world
+                    .read_resource::<Localisation>()
+                    .format("menu-resume-failed", &[("error", error)]);
+                if let Some(resume_status) = self.resume_status {
+                    if let Some(ui_text) = world.write_storage::<UiText>().get_mut(resume_status) {
+                        ui_text.text = text;
+                    }
+                }
+                Trans::None
+            }
+        }
+    }
+
+    /// Writes each fixed message in the chosen language
+    fn show_messages(&self, world: &mut World) {
+        let localisation = world.read_resource::<Localisation>();
+        let arguments = [("language", localisation.language_name())];
+        let mut ui_texts = world.write_storage::<UiText>();
+        for (entity, id) in &self.messages {
+            if let Some(ui_text) = ui_texts.get_mut(*entity) {
+                ui_text.text = localisation.format(id, &arguments);
+            }
+        }
+    }
+
+    /// Updates the ship image and description to show the selected ship
+    fn show_selected_ship(&self, world: &mut World) {
+        let ship_definition: &ShipDefinition = &SHIP_ROSTER.ships[self.selected_ship];
+        if let (Some(ship_image), Some(sprite_sheet_handle)) =
+            (self.ship_image, self.sprite_sheet_handle.clone())
+        {
+            let _result = world.write_storage::<UiImage>().insert(
+                ship_image,
+                UiImage::Sprite(SpriteRender {
+                    sprite_sheet: sprite_sheet_handle,
+                    sprite_number: sprite_number(&ship_definition.sprite),
+                }),
+            );
+            if let Some(ui_transform) = world.write_storage::<UiTransform>().get_mut(ship_image) {
+                ui_transform.width = ship_definition.width;
+                ui_transform.height = ship_definition.height;
+            }
+        }
+        if let Some(ship_description) = self.ship_description {
+            let text = world.read_resource::<Localisation>().format(
+                "menu-ship",
+                &[
+                    ("name", ship_definition.name.clone()),
+                    ("thrust", ship_definition.thrust.to_string()),
+                    ("speed", ship_definition.max_speed.to_string()),
+                    ("timeout", ship_definition.trigger_reset_timeout.to_string()),
+                ],
+            );
+            if let Some(ui_text) = world.write_storage::<UiText>().get_mut(ship_description) {
+                ui_text.text = text;
+            }
+        }
+    }
+}
+
+/// Whether or not there is a suspended game the players can resume
+///
+/// Only live games are resumed, since a recording has to start from the beginning of a game.
+fn can_resume(world: &World) -> bool {
+    world.read_resource::<Session>().mode == SessionMode::Live && SavedGame::path().exists()
+}
+
+/// Moves on to the next language, saves the choice and retitles the window
+fn switch_language(world: &mut World) {
+    let title = {
+        let mut localisation = world.write_resource::<Localisation>();
+        localisation.switch_to_next_language();
+        if let Err(message) = localisation.save() {
+            log::warn!("{}", message);
+        }
+        localisation.text(WINDOW_TITLE)
+    };
+    if let Some(window) = world.try_fetch::<Window>() {
+        window.set_title(&title);
+    }
+}
+
+/// Creates a line of text centred across the screen, `y` pixels above the middle
+pub(crate) fn create_text(
+    world: &mut World,
+    font: &Handle<FontAsset>,
+    id: &str,
+    text: &str,
+    y: f32,
+    font_size: f32,
+) -> Entity {
+    world
+        .create_entity()
+        .with(UiTransform::new(
+            id.to_string(),
+            Anchor::Middle,
+            Anchor::Middle,
+            0.,
+            y,
+            0.,
+            500.,
+            font_size * 1.5,
+        ))
+        .with(UiText::new(
+            font.clone(),
+            text.to_string(),
+            TEXT_COLOUR,
+            font_size,
+            LineMode::Single,
+            Anchor::Middle,
+        ))
+        .build()
+}
@@ -0,0 +1,659 @@
+//! Playing over the local network, in lockstep
+//!
+//! Two or more copies of the game share a game over UDP. One copy hosts the game
+//! and the others join it; each copy flies one of the ships (the host is player one).
+//! The game is deterministic, given the seed of the
+//! [random number generator](../resources/struct.GameRandom.html), a fixed timestep
+//! and the players' controls (that's what makes [replays](../struct.Replay.html) work),
+//! so the copies only need to swap controls: each frame is played once every copy
+//! has every player's controls for it (see [Lockstep](../resources/struct.Lockstep.html)).
+//!
+//! The joining copies only talk to the host, which passes their messages on to the others.
+//! Every message is a datagram of JSON:
+//!
+//! 1. A joining copy sends `Join`, with its protocol version and [configuration hash](../fn.configuration_hash.html),
+//!    until it hears back. The host turns it down with `Reject` if either differs
+//!    or the game is full, giving the reason as a [code](enum.RejectReason.html)
+//!    that the joining copy explains in its own language.
+//!    Otherwise the host takes it on with `Welcome`, giving it its player number.
+//! 2. Once every player has joined (see `player_count` in the game configuration),
+//!    the host sends each copy `Start`, with the seed, timestep and ship of the game.
+//! 3. During the game, each copy sends `Input` every frame: its player's controls for the
+//!    frames to come, and the hashes of its game state for the frames just played.
+//!    The controls of the last few frames are sent again each time, so a lost datagram
+//!    doesn't hold the game up for long.
+//! 4. A copy that quits sends `Leave`.
+//!
+//! If the hashes of any frame differ between copies, the copies have drifted apart
+//! (desynchronised), and the game stops, saying at which frame.
+
+use std::io::ErrorKind;
+use std::net::{SocketAddr, UdpSocket};
+use std::time::{Duration, Instant};
+
+use serde_derive::{Deserialize, Serialize};
+
+use crate::config::{configuration_hash, GAME_CONFIGURATION};
+use crate::replay::exact_timestep;
+use crate::resources::{Lockstep, PlayerControls};
+
+/// The version of the network protocol
+pub const PROTOCOL_VERSION: u32 = 2;
+
+/// How often a joining copy asks to join until it hears back
+const JOIN_INTERVAL: Duration = Duration::from_millis(500);
+
+/// The largest datagram we expect to receive
+const MAX_DATAGRAM: usize = 8192;
+
+/// The controls of one player for one frame, as sent over the network
+///
+/// The stick positions are sent as whole numbers (-127 to 127), and every copy plays
+/// the numbers it sent or received, so every copy plays exactly the same controls.
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct FrameControls {
+    /// The frame the controls are for
+    pub frame: u64,
+    /// The side-to-side position of the player's 'joystick', from -127 (left) to 127 (right)
+    pub horizontal: i8,
+    /// The up-and-down position of the player's 'joystick', from -127 (down) to 127 (up)
+    pub vertical: i8,
+    /// Whether or not the player's fire button is down
+    pub fire: bool,
+}
+
+impl FrameControls {
+    /// The given controls for the given frame, rounded to what's sent over the network
+    pub fn new(frame: u64, controls: &PlayerControls) -> FrameControls {
+        let quantise = |value: f32| (value.max(-1.0).min(1.0) * 127.0).round() as i8;
+        FrameControls {
+            frame,
+            horizontal: quantise(controls.horizontal),
+            vertical: quantise(controls.vertical),
+            fire: controls.fire,
+        }
+    }
+
+    /// The controls to play
+    pub fn controls(&self) -> PlayerControls {
+        PlayerControls {
+            horizontal: f32::from(self.horizontal) / 127.0,
+            vertical: f32::from(self.vertical) / 127.0,
+            fire: self.fire,
+        }
+    }
+}
+
+/// The hash of a copy's game state once a frame has been played
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct FrameHash {
+    /// The frame that was played
+    pub frame: u64,
+    /// The hash of the game state after the frame
+    pub hash: u64,
+}
+
+/// Why the host turned a copy down
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+pub enum RejectReason {
+    /// The host speaks another version of the network protocol
+    ProtocolVersion {
+        /// The version of the network protocol the host speaks
+        host_version: u32,
+    },
+    /// The host has a different game configuration or ship roster
+    Configuration,
+    /// Every player has already joined
+    Full,
+}
+
+impl RejectReason {
+    /// The ID of the message explaining the reason, and the arguments to fill it in with
+    pub fn message(&self) -> (&'static str, Vec<(&'static str, String)>) {
+        match self {
+            RejectReason::ProtocolVersion { host_version } => (
+                "lobby-reject-protocol",
+                vec![
+                    ("host_version", host_version.to_string()),
+                    ("version", PROTOCOL_VERSION.to_string()),
+                ],
+            ),
+            RejectReason::Configuration => ("lobby-reject-configuration", Vec::new()),
+            RejectReason::Full => ("lobby-reject-full", Vec::new()),
+        }
+    }
+}
+
+/// A message between copies of the game
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(tag = "message")]
+pub enum NetMessage {
+    /// A copy asks the host to join its game
+    Join {
+        /// The version of the network protocol the copy speaks
+        protocol_version: u32,
+        /// The configuration hash of the copy, which must match the host's
+        config_hash: u64,
+    },
+    /// The host takes a copy on as one of the players
+    Welcome {
+        /// The player the copy flies (0 being player one)
+        player: usize,
+    },
+    /// The host turns a copy down
+    Reject {
+        /// Why the copy can't join
+        reason: RejectReason,
+    },
+    /// The host starts the game
+    Start {
+        /// The player the copy flies
+        player: usize,
+        /// The number of players
+        player_count: usize,
+        /// The seed of the game's random number generator
+        seed: u64,
+        /// How much game time passes each frame (sec)
+        timestep: f32,
+        /// The name of the ship every player flies
+        ship: String,
+    },
+    /// A player's controls for the frames to come, and the hashes of the frames just played
+    Input {
+        /// The player the controls belong to
+        player: usize,
+        /// The player's controls, one per frame
+        controls: Vec<FrameControls>,
+        /// The hashes of the sender's game state after the last few frames it played
+        hashes: Vec<FrameHash>,
+    },
+    /// A player has quit
+    Leave {
+        /// The player who quit
+        player: usize,
+    },
+}
+
+/// Sends a message to the given address
+pub fn send_message(socket: &UdpSocket, address: SocketAddr, message: &NetMessage) {
+    if let Ok(datagram) = serde_json::to_vec(message) {
+        // a lost datagram is made up for by the ones sent after it
+        let _result = socket.send_to(&datagram, address);
+    }
+}
+
+/// Receives every message waiting on the (non-blocking) socket, with its sender
+///
+/// Datagrams that aren't messages are skipped.
+pub fn receive_messages(socket: &UdpSocket) -> Vec<(SocketAddr, NetMessage, Vec<u8>)> {
+    let mut messages = Vec::new();
+    let mut buffer = [0; MAX_DATAGRAM];
+    loop {
+        match socket.recv_from(&mut buffer) {
+            Ok((length, sender)) => {
+                let datagram = buffer[..length].to_vec();
+                if let Ok(message) = serde_json::from_slice(&datagram) {
+                    messages.push((sender, message, datagram));
+                }
+            }
+            // on some systems, a datagram refused by the other end shows up as an error here
+            Err(ref error) if error.kind() == ErrorKind::ConnectionReset => continue,
+            Err(_) => break,
+        }
+    }
+    messages
+}
+
+/// Builds up the hash of a copy's game state, with FNV-1a
+///
+/// Like the [configuration hash](../fn.configuration_hash.html), we use FNV-1a
+/// rather than the standard library's hasher because its results are the same everywhere.
+#[derive(Clone, Copy, Debug)]
+pub struct StateHasher {
+    /// The hash so far
+    hash: u64,
+}
+
+impl Default for StateHasher {
+    fn default() -> Self {
+        StateHasher {
+            hash: 0xcbf2_9ce4_8422_2325,
+        }
+    }
+}
+
+impl StateHasher {
+    /// Adds the given bytes to the hash
+    pub fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.hash = (self.hash ^ u64::from(*byte)).wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+
+    /// Adds the exact bits of the given number to the hash
+    pub fn write_f32(&mut self, value: f32) {
+        self.write(&value.to_bits().to_le_bytes());
+    }
+
+    /// Adds the given number to the hash
+    pub fn write_u32(&mut self, value: u32) {
+        self.write(&value.to_le_bytes());
+    }
+
+    /// The hash
+    pub fn finish(&self) -> u64 {
+        self.hash
+    }
+}
+
+/// How the gathering of the players is going
+#[derive(Clone, Debug, PartialEq)]
+pub enum LobbyStatus {
+    /// The host is waiting for the other players
+    Hosting {
+        /// How many players have joined, counting the host
+        joined: usize,
+        /// How many players the game needs
+        needed: usize,
+    },
+    /// The copy is asking the host to let it join
+    Joining,
+    /// The host has taken the copy on, and is waiting for the other players
+    Joined {
+        /// The player the copy flies (0 being player one)
+        player: usize,
+    },
+    /// The host has started the game
+    Started,
+    /// The host has turned the copy down
+    Rejected(RejectReason),
+    /// The game can't go ahead
+    Failed(String),
+}
+
+/// Whether this copy is hosting the game or joining one
+#[derive(Debug)]
+enum LobbyRole {
+    /// Hosting a game for the given number of players,
+    /// with the addresses of the copies that have joined, in player order
+    Host {
+        player_count: usize,
+        peers: Vec<SocketAddr>,
+    },
+    /// Joining the host at the given address
+    Guest {
+        host: SocketAddr,
+        last_asked: Option<Instant>,
+    },
+}
+
+/// The gathering of the players before a network game
+///
+/// The [LobbyState](../struct.LobbyState.html) polls the lobby every frame until the game starts,
+/// then hands the game over to the [lockstep](../resources/struct.Lockstep.html).
+#[derive(Debug)]
+pub struct Lobby {
+    /// The socket the copy talks to the others through
+    socket: UdpSocket,
+    /// Whether this copy is hosting the game or joining one
+    role: LobbyRole,
+    /// How the gathering of the players is going
+    status: LobbyStatus,
+    /// The game, once it starts
+    start: Option<NetMessage>,
+}
+
+impl Lobby {
+    /// Hosts a game on the given port, for the game's ship and seed
+    ///
+    /// A game for one player has no one to wait for, so it can't be hosted
+    /// (see `player_count` in the game configuration).
+    pub fn host(port: u16, ship: &str, seed: u64) -> Result<Lobby, String> {
+        let address = format!("0.0.0.0:{}", port);
+        Lobby::host_on(&address, GAME_CONFIGURATION.player_count(), ship, seed)
+    }
+
+    /// Hosts a game for the given number of players on the given address
+    fn host_on(
+        address: &str,
+        player_count: usize,
+        ship: &str,
+        seed: u64,
+    ) -> Result<Lobby, String> {
+        if player_count < 2 {
+            return Err(format!(
+                "A network game needs 2 or more players, not {}",
+                player_count
+            ));
+        }
+        let socket = bind(address)?;
+        Ok(Lobby {
+            socket,
+            role: LobbyRole::Host {
+                player_count,
+                peers: Vec::new(),
+            },
+            status: LobbyStatus::Hosting {
+                joined: 1,
+                needed: player_count,
+            },
+            start: Some(NetMessage::Start {
+                player: 0,
+                player_count,
+                seed,
+                timestep: exact_timestep(),
+                ship: ship.to_string(),
+            }),
+        })
+    }
+
+    /// Joins the game hosted at the given address (e.g. "192.168.1.10:7777")
+    pub fn join(address: &str) -> Result<Lobby, String> {
+        let host = address
+            .parse()
+            .map_err(|error| format!("Can't join {}: {}", address, error))?;
+        let socket = bind("0.0.0.0:0")?;
+        Ok(Lobby {
+            socket,
+            role: LobbyRole::Guest {
+                host,
+                last_asked: None,
+            },
+            status: LobbyStatus::Joining,
+            start: None,
+        })
+    }
+
+    /// How the gathering of the players is going
+    pub fn status(&self) -> &LobbyStatus {
+        &self.status
+    }
+
+    /// The game that has started, if it has: the `Start` message for this copy
+    pub fn start(&self) -> Option<&NetMessage> {
+        match self.status {
+            LobbyStatus::Started => self.start.as_ref(),
+            _ => None,
+        }
+    }
+
+    /// Deals with the messages that have come in, and asks to join again if need be
+    pub fn poll(&mut self) {
+        match self.status {
+            LobbyStatus::Started | LobbyStatus::Rejected(_) | LobbyStatus::Failed(_) => return,
+            _ => {}
+        }
+        let messages = receive_messages(&self.socket);
+        match &mut self.role {
+            LobbyRole::Host {
+                player_count,
+                peers,
+            } => {
+                for (sender, message, _datagram) in messages {
+                    if let NetMessage::Join {
+                        protocol_version,
+                        config_hash,
+                    } = message
+                    {
+                        let reply = if protocol_version != PROTOCOL_VERSION {
+                            NetMessage::Reject {
+                                reason: RejectReason::ProtocolVersion {
+                                    host_version: PROTOCOL_VERSION,
+                                },
+                            }
+                        } else if config_hash != configuration_hash() {
+                            NetMessage::Reject {
+                                reason: RejectReason::Configuration,
+                            }
+                        } else if let Some(index) = peers.iter().position(|peer| *peer == sender) {
+                            NetMessage::Welcome { player: index + 1 }
+                        } else if peers.len() + 1 < *player_count {
+                            peers.push(sender);
+                            NetMessage::Welcome {
+                                player: peers.len(),
+                            }
+                        } else {
+                            NetMessage::Reject {
+                                reason: RejectReason::Full,
+                            }
+                        };
+                        send_message(&self.socket, sender, &reply);
+                    }
+                }
+                let needed = *player_count;
+                self.status = if peers.len() + 1 >= needed {
+                    if let Some(start) = &self.start {
+                        for (index, peer) in peers.iter().enumerate() {
+                            send_message(&self.socket, *peer, &start_for(start, index + 1));
+                        }
+                    }
+                    LobbyStatus::Started
+                } else {
+                    LobbyStatus::Hosting {
+                        joined: peers.len() + 1,
+                        needed,
+                    }
+                };
+            }
+            LobbyRole::Guest { host, last_asked } => {
+                for (sender, message, _datagram) in messages {
+                    if sender != *host {
+                        continue;
+                    }
+                    match message {
+                        NetMessage::Welcome { player } => {
+                            self.status = LobbyStatus::Joined { player };
+                        }
+                        NetMessage::Reject { reason } => {
+                            self.status = LobbyStatus::Rejected(reason);
+                        }
+                        NetMessage::Start { .. } => {
+                            self.start = Some(message);
+                            self.status = LobbyStatus::Started;
+                        }
+                        _ => {}
+                    }
+                }
+                let is_due = last_asked.map_or(true, |asked| asked.elapsed() >= JOIN_INTERVAL);
+                let is_waiting = matches!(
+                    self.status,
+                    LobbyStatus::Joining | LobbyStatus::Joined { .. }
+                );
+                if is_due && is_waiting {
+                    let join = NetMessage::Join {
+                        protocol_version: PROTOCOL_VERSION,
+                        config_hash: configuration_hash(),
+                    };
+                    send_message(&self.socket, *host, &join);
+                    *last_asked = Some(Instant::now());
+                }
+            }
+        }
+    }
+
+    /// Hands the started game over to a lockstep, or gives the lobby back if it hasn't started
+    pub fn into_lockstep(self) -> Result<Lockstep, Lobby> {
+        let (player, player_count) = match self.start() {
+            Some(NetMessage::Start {
+                player,
+                player_count,
+                ..
+            }) => (*player, *player_count),
+            _ => return Err(self),
+        };
+        let (peers, starts) = match &self.role {
+            LobbyRole::Host { peers, .. } => {
+                let start = self
+                    .start
+                    .as_ref()
+                    .expect("a started game has a start message");
+                let starts = (1..=peers.len())
+                    .map(|player| start_for(start, player))
+                    .collect();
+                (peers.clone(), starts)
+            }
+            LobbyRole::Guest { host, .. } => (vec![*host], Vec::new()),
+        };
+        Ok(Lockstep::new(
+            self.socket,
+            player,
+            player_count,
+            peers,
+            starts,
+        ))
+    }
+}
+
+/// The host's `Start` message, for the given player
+fn start_for(start: &NetMessage, for_player: usize) -> NetMessage {
+    match start.clone() {
+        NetMessage::Start {
+            player_count,
+            seed,
+            timestep,
+            ship,
+            ..
+        } => NetMessage::Start {
+            player: for_player,
+            player_count,
+            seed,
+            timestep,
+            ship,
+        },
+        message => message,
+    }
+}
+
+/// Opens a non-blocking UDP socket on the given address
+fn bind(address: &str) -> Result<UdpSocket, String> {
+    let socket =
+        UdpSocket::bind(address).map_err(|error| format!("Can't open {}: {}", address, error))?;
+    socket
+        .set_nonblocking(true)
+        .map_err(|error| format!("Can't set up {}: {}", address, error))?;
+    Ok(socket)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread::sleep;
+
+    /// Polls the lobbies, giving the datagrams time to arrive, until they've all started
+    /// or given up
+    fn poll_until_settled(lobbies: &mut [&mut Lobby]) {
+        for _ in 0..100 {
+            for lobby in lobbies.iter_mut() {
+                lobby.poll();
+            }
+            let is_settled = lobbies.iter().all(|lobby| {
+                matches!(
+                    lobby.status(),
+                    LobbyStatus::Started | LobbyStatus::Rejected(_) | LobbyStatus::Failed(_)
+                )
+            });
+            if is_settled {
+                return;
+            }
+            sleep(Duration::from_millis(10));
+        }
+    }
+
+    /// A host for two players on the loopback address, and the address to join it on
+    fn test_host() -> (Lobby, String) {
+        let host = Lobby::host_on("127.0.0.1:0", 2, "Test ship", 42).unwrap();
+        let address = host.socket.local_addr().unwrap().to_string();
+        (host, address)
+    }
+
+    #[test]
+    fn controls_sent_over_the_network_play_the_same_everywhere() {
+        let controls = PlayerControls {
+            horizontal: 0.5,
+            vertical: -2.0,
+            fire: true,
+        };
+        let frame_controls = FrameControls::new(7, &controls);
+        assert_eq!(
+            frame_controls,
+            FrameControls {
+                frame: 7,
+                horizontal: 64,
+                vertical: -127,
+                fire: true,
+            }
+        );
+        // the controls played come back to the same numbers when they're sent on
+        for value in -127..=127 {
+            let frame_controls = FrameControls {
+                frame: 0,
+                horizontal: value,
+                vertical: -value,
+                fire: false,
+            };
+            assert_eq!(FrameControls::new(0, &frame_controls.controls()), frame_controls);
+        }
+    }
+
+    #[test]
+    fn a_guest_joins_and_the_game_starts() {
+        let (mut host, address) = test_host();
+        let mut guest = Lobby::join(&address).unwrap();
+        assert_eq!(
+            host.status(),
+            &LobbyStatus::Hosting {
+                joined: 1,
+                needed: 2
+            }
+        );
+        poll_until_settled(&mut [&mut guest, &mut host]);
+        assert_eq!(host.status(), &LobbyStatus::Started);
+        assert_eq!(
+            guest.start(),
+            Some(&NetMessage::Start {
+                player: 1,
+                player_count: 2,
+                seed: 42,
+                timestep: exact_timestep(),
+                ship: "Test ship".to_string(),
+            })
+        );
+        let host_lockstep = host.into_lockstep().unwrap();
+        let guest_lockstep = guest.into_lockstep().unwrap();
+        assert_eq!((host_lockstep.local_player, host_lockstep.player_count), (0, 2));
+        assert_eq!((guest_lockstep.local_player, guest_lockstep.player_count), (1, 2));
+    }
+
+    #[test]
+    fn a_guest_is_turned_down_when_the_game_is_full() {
+        let (mut host, address) = test_host();
+        let mut first_guest = Lobby::join(&address).unwrap();
+        let mut second_guest = Lobby::join(&address).unwrap();
+        // both ask before the host hears either of them
+        first_guest.poll();
+        second_guest.poll();
+        sleep(Duration::from_millis(50));
+        poll_until_settled(&mut [&mut host, &mut first_guest, &mut second_guest]);
+        let mut statuses = vec![first_guest.status().clone(), second_guest.status().clone()];
+        statuses.retain(|status| *status != LobbyStatus::Started);
+        assert_eq!(statuses, vec![LobbyStatus::Rejected(RejectReason::Full)]);
+        assert_eq!(RejectReason::Full.message(), ("lobby-reject-full", Vec::new()));
+    }
+
+    #[test]
+    fn a_game_for_one_player_is_not_hosted() {
+        assert!(Lobby::host_on("127.0.0.1:0", 1, "Test ship", 42).is_err());
+    }
+
+    #[test]
+    fn reject_reasons_are_sent_as_codes() {
+        let message = NetMessage::Reject {
+            reason: RejectReason::ProtocolVersion { host_version: 9 },
+        };
+        let datagram = serde_json::to_vec(&message).unwrap();
+        assert_eq!(serde_json::from_slice::<NetMessage>(&datagram).unwrap(), message);
+        let (id, arguments) = RejectReason::ProtocolVersion { host_version: 9 }.message();
+        assert_eq!(id, "lobby-reject-protocol");
+        assert_eq!(arguments[0], ("host_version", "9".to_string()));
+    }
+}
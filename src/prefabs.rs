@@ -0,0 +1,128 @@
+//! The prefabs (templates) the game's entities are made from
+//!
+//! Like the [ship roster](struct.ShipRoster.html), the prefabs are loaded
+//! from a RON file ("prefabs.ron" in resources), so the game designer can
+//! change the look and size of the asteroids and explosions without having to recompile the code.
+//!
+//! Prefabs refer to their sprites by [name](struct.SpriteNames.html) rather than by number,
+//! so they don't break when sprites are added to the sprite sheet.
+//! The ships and their lasers are made from the [ship roster](struct.ShipRoster.html)
+//! in the same way.
+
+use amethyst::assets::Handle;
+use amethyst::config::Config;
+use amethyst::renderer::{SpriteRender, SpriteSheet};
+use lazy_static::lazy_static;
+use serde_derive::{Deserialize, Serialize};
+
+use crate::backgrounds::{BackgroundLayer, BACKGROUNDS};
+use crate::components::AsteroidSize;
+use crate::roster::SHIP_ROSTER;
+use crate::sprites::{sprite_number, SPRITE_NAMES};
+
+/// The look and size of an entity
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct EntityPrefab {
+    /// The name of the entity's sprite in the sprite sheet, e.g. "laserRed10"
+    pub sprite: String,
+    /// The width of the entity (e.g. of its collision box), if it needs one
+    #[serde(default)]
+    pub width: f32,
+    /// The height of the entity (e.g. of its collision box), if it needs one
+    #[serde(default)]
+    pub height: f32,
+}
+
+impl EntityPrefab {
+    /// The sprite render that draws the entity, from the given sprite sheet
+    pub fn sprite_render(&self, sprite_sheet: &Handle<SpriteSheet>) -> SpriteRender {
+        SpriteRender {
+            sprite_sheet: sprite_sheet.clone(),
+            sprite_number: sprite_number(&self.sprite),
+        }
+    }
+}
+
+/// One of the asteroids, with its size class
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct AsteroidPrefab {
+    /// The size class of the asteroid
+    pub size: AsteroidSize,
+    /// The look and size of the asteroid
+    pub entity: EntityPrefab,
+}
+
+/// The prefabs of the entities that aren't set by the ship roster
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct Prefabs {
+    /// The asteroids. Each time an asteroid of a given size is needed,
+    /// one of the asteroids of that size is picked at random.
+    pub asteroids: Vec<AsteroidPrefab>,
+    /// The flash when a ship is destroyed
+    pub explosion: EntityPrefab,
+}
+
+impl Prefabs {
+    /// The asteroids of the given size class
+    pub fn asteroids_of_size(&self, size: AsteroidSize) -> Vec<&EntityPrefab> {
+        self.asteroids
+            .iter()
+            .filter(|asteroid| asteroid.size == size)
+            .map(|asteroid| &asteroid.entity)
+            .collect()
+    }
+}
+
+lazy_static! {
+    /// The actual [prefabs](struct.Prefabs.html).
+    ///
+    /// The prefabs are automatically loaded on startup
+    /// from the file "prefabs.ron" in resources.
+    pub static ref PREFABS: Prefabs = {
+        let prefabs_path = format!("{}/resources/prefabs.ron", env!("CARGO_MANIFEST_DIR"));
+        let prefabs = Prefabs::load(&prefabs_path).unwrap();
+        for size in &[AsteroidSize::Big, AsteroidSize::Medium, AsteroidSize::Small, AsteroidSize::Tiny] {
+            assert!(
+                !prefabs.asteroids_of_size(*size).is_empty(),
+                "the prefabs need at least one {:?} asteroid",
+                size
+            );
+        }
+        prefabs
+    };
+}
+
+/// The sprite names used by the prefabs, the ship roster and the backgrounds
+/// that aren't in the sprite sheet
+///
+/// The game checks the names when it starts, so a sprite sheet change
+/// that breaks a name is caught straight away, rather than showing the wrong sprite.
+pub fn unknown_sprite_names() -> Vec<String> {
+    let mut names: Vec<&str> = PREFABS
+        .asteroids
+        .iter()
+        .map(|asteroid| asteroid.entity.sprite.as_str())
+        .collect();
+    names.push(&PREFABS.explosion.sprite);
+    for ship in &SHIP_ROSTER.ships {
+        names.push(&ship.sprite);
+        names.extend(ship.damage_sprites.iter().map(String::as_str));
+        names.push(&ship.life_sprite);
+        names.push(&ship.weapon.sprite);
+    }
+    for level in &BACKGROUNDS.levels {
+        for layer in &level.layers {
+            if let BackgroundLayer::Stars { sprites, .. } = layer {
+                names.extend(sprites.iter().map(String::as_str));
+            }
+        }
+    }
+    let mut unknown: Vec<String> = names
+        .into_iter()
+        .filter(|name| SPRITE_NAMES.sprite_number(name).is_none())
+        .map(String::from)
+        .collect();
+    unknown.sort();
+    unknown.dedup();
+    unknown
+}
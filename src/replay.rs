@@ -0,0 +1,326 @@
+//! Recording the players' controls, and playing them back
+//!
+//! A replay holds everything needed to play a game out again exactly:
+//! the seed of the [random number generator](resources/struct.GameRandom.html),
+//! the fixed timestep, the ship that was chosen, and the controls of every player
+//! for every frame. Given the same game configuration (checked with a hash),
+//! feeding those controls back into the game reproduces the run, which makes
+//! replays our main tool for reproducing the bugs players report.
+//!
+//! Replays are saved as RON files. To keep them compact, a run of frames
+//! with the same controls is stored once, with a repeat count.
+
+use std::path::{Path, PathBuf};
+
+use amethyst::config::Config;
+use rand::{thread_rng, Rng};
+use serde_derive::{Deserialize, Serialize};
+
+use crate::config::{configuration_hash, GAME_CONFIGURATION};
+use crate::resources::PlayerControls;
+
+/// The version of the replay file format
+pub const REPLAY_VERSION: u32 = 1;
+
+/// The timestep recordings use when the game configuration doesn't set one (sec)
+pub const REPLAY_TIMESTEP: f32 = 1.0 / 60.0;
+
+/// A run of frames during which every player's controls stayed the same
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct ReplayFrame {
+    /// How many frames in a row had these controls
+    pub repeat: u32,
+    /// The controls of each player, in player order
+    pub controls: Vec<PlayerControls>,
+}
+
+/// A recording of a game
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct Replay {
+    /// The version of the replay file format, see [REPLAY_VERSION](constant.REPLAY_VERSION.html)
+    pub version: u32,
+    /// The seed of the game's random number generator
+    pub seed: u64,
+    /// The [configuration hash](fn.configuration_hash.html) of the game that was recorded
+    pub config_hash: u64,
+    /// How much game time passed each frame (sec)
+    pub timestep: f32,
+    /// The name of the ship chosen from the roster
+    pub ship: String,
+    /// How many players played
+    pub player_count: usize,
+    /// The players' controls, frame by frame
+    pub frames: Vec<ReplayFrame>,
+}
+
+impl Replay {
+    /// Creates an empty replay for a game with the given seed and timestep
+    pub fn new(seed: u64, timestep: f32) -> Replay {
+        Replay {
+            version: REPLAY_VERSION,
+            seed,
+            config_hash: configuration_hash(),
+            timestep,
+            ..Replay::default()
+        }
+    }
+
+    /// Adds a frame of controls to the end of the replay
+    pub fn record(&mut self, controls: &[PlayerControls]) {
+        match self.frames.last_mut() {
+            Some(frame) if frame.controls == controls => frame.repeat += 1,
+            _ => self.frames.push(ReplayFrame {
+                repeat: 1,
+                controls: controls.to_vec(),
+            }),
+        }
+    }
+
+    /// Loads a replay, checking that it can be played back
+    ///
+    /// Replays in another file format version are rejected.
+    /// A replay recorded with a different game configuration is still played,
+    /// but with a warning, since it probably won't play out the same.
+    pub fn load_replay(path: &Path) -> Result<Replay, String> {
+        let replay = Replay::load(path)
+            .map_err(|error| format!("Can't read replay {}: {}", path.display(), error))?;
+        if replay.version != REPLAY_VERSION {
+            return Err(format!(
+                "Replay {} is version {}, but this game plays version {} replays",
+                path.display(),
+                replay.version,
+                REPLAY_VERSION
+            ));
+        }
+        if replay.config_hash != configuration_hash() {
+            log::warn!(
+                "Replay {} was recorded with a different game configuration \
+                 or ship roster, so it may not play out the same",
+                path.display()
+            );
+        }
+        Ok(replay)
+    }
+
+    /// Saves the replay
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        self.write(path)
+            .map_err(|error| format!("Can't save replay {}: {}", path.display(), error))
+    }
+}
+
+/// The timestep of games that must play out exactly the same every time (sec):
+/// the game configuration's fixed timestep if it has one, or else [REPLAY_TIMESTEP](constant.REPLAY_TIMESTEP.html)
+///
+/// Recordings, network games and agents' games use it.
+pub fn exact_timestep() -> f32 {
+    if GAME_CONFIGURATION.fixed_timestep > 0.0 {
+        GAME_CONFIGURATION.fixed_timestep
+    } else {
+        REPLAY_TIMESTEP
+    }
+}
+
+/// What the game does with the players' controls
+#[derive(Clone, Debug, PartialEq)]
+pub enum SessionMode {
+    /// The players play, and nothing is recorded
+    Live,
+    /// The players play, and their controls are recorded to the given file
+    Recording(PathBuf),
+    /// The controls are played back from a replay
+    Replaying,
+    /// The players play over the network, and their controls come from the
+    /// [lockstep](resources/struct.Lockstep.html)
+    Networked,
+    /// An [agent](agent/index.html) drives the game, and nothing it does is kept
+    Agent,
+}
+
+/// The resource that ties the game to its replay
+///
+/// The session is set up from the command line before the game starts
+/// (`--record <file>` or `--replay <file>`) and decides the seed of the
+/// random number generator and the timestep of the game.
+/// While recording, the [ControlSystem](systems/struct.ControlSystem.html)
+/// adds each frame's controls to the replay;
+/// while replaying, it takes each frame's controls from the replay instead.
+#[derive(Clone, Debug)]
+pub struct Session {
+    /// What the game does with the players' controls
+    pub mode: SessionMode,
+    /// The replay being recorded or played back
+    pub replay: Replay,
+    /// While replaying, the position in the replay's list of frames
+    next_frame: usize,
+    /// While replaying, how many times the current frame has been played
+    repeats_played: u32,
+}
+
+impl Session {
+    /// A session where the players play and nothing is recorded
+    pub fn live() -> Session {
+        Session::new(
+            SessionMode::Live,
+            Replay::new(thread_rng().gen(), GAME_CONFIGURATION.fixed_timestep.max(0.0)),
+        )
+    }
+
+    /// A session where the players' controls are recorded to the given file
+    pub fn record(path: PathBuf) -> Session {
+        Session::new(
+            SessionMode::Recording(path),
+            Replay::new(thread_rng().gen(), exact_timestep()),
+        )
+    }
+
+    /// A session where the players play over the network,
+    /// with the seed and timestep the host chose
+    pub fn networked(seed: u64, timestep: f32) -> Session {
+        Session::new(SessionMode::Networked, Replay::new(seed, timestep))
+    }
+
+    /// A session driven by an agent, with the given seed
+    pub fn agent(seed: u64) -> Session {
+        Session::new(SessionMode::Agent, Replay::new(seed, exact_timestep()))
+    }
+
+    /// A session where the controls are played back from the given replay
+    pub fn play_back(replay: Replay) -> Session {
+        Session::new(SessionMode::Replaying, replay)
+    }
+
+    fn new(mode: SessionMode, replay: Replay) -> Session {
+        Session {
+            mode,
+            replay,
+            next_frame: 0,
+            repeats_played: 0,
+        }
+    }
+
+    /// The seed for the game's random number generator
+    pub fn seed(&self) -> u64 {
+        self.replay.seed
+    }
+
+    /// The fixed amount of game time that passes each frame (sec),
+    /// or `None` if the game keeps up with the real time between frames
+    pub fn timestep(&self) -> Option<f32> {
+        if self.replay.timestep > 0.0 {
+            Some(self.replay.timestep)
+        } else {
+            None
+        }
+    }
+
+    /// Whether or not the game's high score, achievements and statistics are kept
+    ///
+    /// They aren't for a replay, which only repeats a game that was already played,
+    /// nor for an agent's game.
+    pub fn keeps_records(&self) -> bool {
+        self.mode != SessionMode::Replaying && self.mode != SessionMode::Agent
+    }
+
+    /// While replaying, takes the next frame of controls from the replay
+    pub fn next_controls(&mut self) -> Option<&[PlayerControls]> {
+        let frame = self.replay.frames.get(self.next_frame)?;
+        self.repeats_played += 1;
+        if self.repeats_played >= frame.repeat {
+            self.next_frame += 1;
+            self.repeats_played = 0;
+        }
+        Some(&frame.controls)
+    }
+
+    /// Whether or not a replay has been played all the way through
+    pub fn is_finished(&self) -> bool {
+        self.mode == SessionMode::Replaying && self.next_frame >= self.replay.frames.len()
+    }
+
+    /// Saves the replay, if we are recording one
+    pub fn save_recording(&self) -> Result<(), String> {
+        match &self.mode {
+            SessionMode::Recording(path) => self.replay.save(path),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl Default for Session {
+    fn default() -> Self {
+        Session::live()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A player's controls, pushing the stick the given way, with the fire button up or down
+    fn controls(horizontal: f32, fire: bool) -> Vec<PlayerControls> {
+        vec![PlayerControls {
+            horizontal,
+            vertical: 0.0,
+            fire,
+        }]
+    }
+
+    #[test]
+    fn runs_of_the_same_controls_are_stored_once() {
+        let mut replay = Replay::new(1, REPLAY_TIMESTEP);
+        for _ in 0..3 {
+            replay.record(&controls(0.0, false));
+        }
+        replay.record(&controls(-0.5, true));
+        replay.record(&controls(0.0, false));
+        let repeats: Vec<u32> = replay.frames.iter().map(|frame| frame.repeat).collect();
+        assert_eq!(repeats, vec![3, 1, 1]);
+    }
+
+    #[test]
+    fn a_saved_replay_plays_back_the_recorded_controls() {
+        let recorded: Vec<Vec<PlayerControls>> = (0..100)
+            .map(|frame| controls((frame / 10) as f32 / 10.0 - 0.5, frame % 7 == 0))
+            .collect();
+        let mut replay = Replay::new(42, REPLAY_TIMESTEP);
+        replay.ship = "Test ship".to_string();
+        replay.player_count = 1;
+        for frame_controls in &recorded {
+            replay.record(frame_controls);
+        }
+        let path = std::env::temp_dir().join(format!(
+            "space_shooter_replay_test_{}.ron",
+            std::process::id()
+        ));
+        replay.save(&path).unwrap();
+        let loaded = Replay::load_replay(&path);
+        let _result = std::fs::remove_file(&path);
+        let loaded = loaded.unwrap();
+        assert_eq!(loaded, replay);
+
+        let mut session = Session::play_back(loaded);
+        for frame_controls in &recorded {
+            assert!(!session.is_finished());
+            assert_eq!(session.next_controls(), Some(&frame_controls[..]));
+        }
+        assert!(session.is_finished());
+        assert_eq!(session.next_controls(), None);
+    }
+
+    #[test]
+    fn replays_in_another_version_are_rejected() {
+        let replay = Replay {
+            version: REPLAY_VERSION + 1,
+            ..Replay::new(1, REPLAY_TIMESTEP)
+        };
+        let path = std::env::temp_dir().join(format!(
+            "space_shooter_replay_version_test_{}.ron",
+            std::process::id()
+        ));
+        replay.save(&path).unwrap();
+        let loaded = Replay::load_replay(&path);
+        let _result = std::fs::remove_file(&path);
+        assert!(loaded.unwrap_err().contains("version"));
+    }
+}
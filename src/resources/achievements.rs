@@ -0,0 +1,74 @@
+use std::collections::VecDeque;
+use std::path::PathBuf;
+
+use amethyst::config::Config;
+use serde_derive::{Deserialize, Serialize};
+
+use crate::user_files::user_data_path;
+
+/// The name of the unlocked achievements file in the player's data directory
+const ACHIEVEMENTS_FILE: &str = "achievements.ron";
+
+/// The [achievements](../struct.AchievementList.html) the player has unlocked
+///
+/// Like the [high score](struct.HighScore.html), the unlocked achievements are kept in the player's
+/// data directory, so they carry over from game to game.
+/// They are saved as soon as an achievement is unlocked.
+/// If they can't be saved, they aren't tried again for the rest of the game,
+/// rather than reporting the same problem with every achievement.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct UnlockedAchievements {
+    /// The ids of the unlocked achievements, in the order they were unlocked
+    pub ids: Vec<String>,
+    /// Whether or not saving the unlocked achievements has failed during this game
+    #[serde(skip)]
+    pub save_failed: bool,
+}
+
+impl UnlockedAchievements {
+    /// The path of the unlocked achievements file
+    pub fn path() -> PathBuf {
+        user_data_path(ACHIEVEMENTS_FILE)
+    }
+
+    /// Loads the unlocked achievements, which are none if the player hasn't unlocked any yet
+    pub fn load_or_default() -> UnlockedAchievements {
+        let path = UnlockedAchievements::path();
+        if path.exists() {
+            UnlockedAchievements::load(&path).unwrap_or_default()
+        } else {
+            UnlockedAchievements::default()
+        }
+    }
+
+    /// Whether or not the achievement with the given id has been unlocked
+    pub fn is_unlocked(&self, id: &str) -> bool {
+        self.ids.iter().any(|unlocked| unlocked == id)
+    }
+
+    /// Saves the unlocked achievements
+    pub fn save(&self) -> Result<PathBuf, String> {
+        let path = UnlockedAchievements::path();
+        if let Some(directory) = path.parent() {
+            std::fs::create_dir_all(directory)
+                .map_err(|error| format!("Can't create {}: {}", directory.display(), error))?;
+        }
+        self.write(&path)
+            .map_err(|error| format!("Can't save the achievements {}: {}", path.display(), error))?;
+        Ok(path)
+    }
+}
+
+/// The pop-ups (toasts) announcing newly unlocked achievements
+///
+/// The toasts are shown one at a time, each for the time set in the
+/// [list of achievements](../struct.AchievementList.html).
+#[derive(Clone, Debug, Default)]
+pub struct AchievementToasts {
+    /// The message IDs of the names of the unlocked achievements still to be announced
+    pub queue: VecDeque<String>,
+    /// The message ID of the name of the achievement being announced, if any
+    pub showing: Option<String>,
+    /// How much longer the toast being shown stays up (sec)
+    pub time_left: f32,
+}
@@ -0,0 +1,120 @@
+use crate::config::GAME_CONFIGURATION;
+
+/// The arena the game is played in, and where it sits in the window
+///
+/// The game is always played in an arena of the same size
+/// (`arena_width` by `arena_height` in the [game configuration](../struct.GameConfiguration.html)),
+/// whatever the size or shape of the window, so the game plays the same everywhere,
+/// and a replay plays out the same in any window.
+///
+/// The arena is scaled up or down to fit the window, keeping its shape.
+/// Any space left over is split evenly either side of the arena:
+/// bars above and below (letterboxing) when the window is too tall,
+/// or bars to the left and right (pillarboxing) when the window is too wide.
+/// The [CameraSystem](../systems/struct.CameraSystem.html) fits the arena to the window
+/// whenever the window changes size.
+#[derive(Clone, Copy, Debug)]
+pub struct Arena {
+    /// The width of the arena, in game units
+    pub width: f32,
+    /// The height of the arena, in game units
+    pub height: f32,
+    /// The width of the window the arena was last fitted to, in pixels
+    pub screen_width: f32,
+    /// The height of the window the arena was last fitted to, in pixels
+    pub screen_height: f32,
+    /// How many pixels each game unit takes up on the screen
+    pub scale: f32,
+    /// The width of each bar to the left and right of the arena, in pixels
+    pub margin_x: f32,
+    /// The height of each bar above and below the arena, in pixels
+    pub margin_y: f32,
+}
+
+impl Arena {
+    /// An arena of the given size, not yet fitted to a window
+    pub fn new(width: f32, height: f32) -> Self {
+        Arena {
+            width: width.max(1.0),
+            height: height.max(1.0),
+            screen_width: 0.0,
+            screen_height: 0.0,
+            scale: 1.0,
+            margin_x: 0.0,
+            margin_y: 0.0,
+        }
+    }
+
+    /// Whether the arena was last fitted to a window of the given size
+    pub fn fits(&self, screen_width: f32, screen_height: f32) -> bool {
+        (self.screen_width - screen_width).abs() < f32::EPSILON
+            && (self.screen_height - screen_height).abs() < f32::EPSILON
+    }
+
+    /// Fits the arena to a window of the given size (in pixels)
+    pub fn fit(&mut self, screen_width: f32, screen_height: f32) {
+        self.screen_width = screen_width;
+        self.screen_height = screen_height;
+        self.scale = (screen_width / self.width)
+            .min(screen_height / self.height)
+            .max(f32::EPSILON);
+        self.margin_x = (screen_width - self.width * self.scale) / 2.0;
+        self.margin_y = (screen_height - self.height * self.scale) / 2.0;
+    }
+
+    /// The width and height of the whole window, in game units
+    pub fn view_size(&self) -> (f32, f32) {
+        (
+            self.screen_width.max(1.0) / self.scale,
+            self.screen_height.max(1.0) / self.scale,
+        )
+    }
+}
+
+impl Default for Arena {
+    /// The arena set out in the game configuration
+    fn default() -> Self {
+        Arena::new(GAME_CONFIGURATION.arena_width, GAME_CONFIGURATION.arena_height)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a_window_of_the_same_shape_has_no_bars() {
+        let mut arena = Arena::new(500.0, 500.0);
+        arena.fit(1000.0, 1000.0);
+        assert_eq!(arena.scale, 2.0);
+        assert_eq!((arena.margin_x, arena.margin_y), (0.0, 0.0));
+        assert!(arena.fits(1000.0, 1000.0));
+        assert!(!arena.fits(1000.0, 500.0));
+    }
+
+    #[test]
+    fn a_wide_window_is_pillarboxed() {
+        let mut arena = Arena::new(500.0, 500.0);
+        arena.fit(1000.0, 500.0);
+        assert_eq!(arena.scale, 1.0);
+        assert_eq!((arena.margin_x, arena.margin_y), (250.0, 0.0));
+        assert_eq!(arena.view_size(), (1000.0, 500.0));
+    }
+
+    #[test]
+    fn a_tall_window_is_letterboxed() {
+        let mut arena = Arena::new(500.0, 250.0);
+        arena.fit(1000.0, 1000.0);
+        assert_eq!(arena.scale, 2.0);
+        assert_eq!((arena.margin_x, arena.margin_y), (0.0, 250.0));
+    }
+
+    #[test]
+    fn an_empty_window_still_has_a_size() {
+        let mut arena = Arena::new(500.0, 500.0);
+        arena.fit(0.0, 0.0);
+        assert!(arena.scale > 0.0);
+        let (view_width, view_height) = arena.view_size();
+        assert!(view_width.is_finite() && view_height.is_finite());
+    }
+}
@@ -0,0 +1,13 @@
+use amethyst::assets::Handle;
+use amethyst::renderer::SpriteSheet;
+
+/// The resource containing data we need to create asteroid fragments.
+///
+/// Like the [LaserResource](struct.LaserResource.html), this lets a system
+/// create new asteroid entities on the fly (when a large asteroid splits)
+/// without having to load the sprite sheet again.
+#[derive(Clone)]
+pub struct AsteroidResource {
+    /// The sprite sheet holding all the asteroid sprites
+    pub sprite_sheet: Handle<SpriteSheet>,
+}
@@ -0,0 +1,18 @@
+use std::collections::HashMap;
+
+use amethyst::assets::Handle;
+use amethyst::renderer::SpriteSheet;
+
+/// The resources needed to build the background of a level
+///
+/// The [BackgroundSystem](../systems/struct.BackgroundSystem.html) uses this resource
+/// to rebuild the background when the players move on to another level.
+#[derive(Clone)]
+pub struct BackgroundResource {
+    /// The sprite sheet of each background image, by the image's path
+    pub image_sheets: HashMap<String, Handle<SpriteSheet>>,
+    /// The sprite sheet holding the star sprites
+    pub star_sheet: Handle<SpriteSheet>,
+    /// The level whose background is showing
+    pub level: u32,
+}
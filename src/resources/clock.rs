@@ -0,0 +1,17 @@
+use serde_derive::{Deserialize, Serialize};
+
+/// The game's own clock
+///
+/// The gameplay systems move things on by the time in this resource,
+/// rather than by the real time between frames, so that a game
+/// running on a fixed timestep plays out exactly the same every time.
+/// The [ClockSystem](../systems/struct.ClockSystem.html) winds it on each frame.
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct GameClock {
+    /// How much game time passed during this frame (sec)
+    pub delta_seconds: f32,
+    /// How many frames the game has run
+    pub frame: u64,
+    /// How much game time has passed since the game started (sec)
+    pub elapsed_seconds: f32,
+}
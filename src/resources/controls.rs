@@ -0,0 +1,24 @@
+use serde_derive::{Deserialize, Serialize};
+
+/// What one player is doing with their controls during a frame
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct PlayerControls {
+    /// The side-to-side position of the player's 'joystick', from -1.0 (left) to 1.0 (right)
+    pub horizontal: f32,
+    /// The up-and-down position of the player's 'joystick', from -1.0 (down) to 1.0 (up)
+    pub vertical: f32,
+    /// Whether or not the player's fire button is down
+    pub fire: bool,
+}
+
+/// The controls of every player for the current frame
+///
+/// The [ControlSystem](../systems/struct.ControlSystem.html) fills this resource in,
+/// either from the input handler or from a replay,
+/// and the [ShipSystem](../systems/struct.ShipSystem.html) steers the ships by it.
+/// That way the gameplay systems don't care where the controls come from.
+#[derive(Clone, Debug, Default)]
+pub struct Controls {
+    /// The controls of each player, in player order
+    pub players: Vec<PlayerControls>,
+}
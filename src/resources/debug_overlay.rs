@@ -0,0 +1,37 @@
+use serde_derive::{Deserialize, Serialize};
+
+/// The timings and entity counts of the current frame
+///
+/// These are kept up to date whether or not the overlay is shown,
+/// so a game run without a window can still check them.
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct DebugStats {
+    /// The number of frames drawn per second, averaged over the last few frames
+    pub fps: f32,
+    /// How much real time the last frame took (sec)
+    pub frame_time: f32,
+    /// The number of ships
+    pub ships: usize,
+    /// The number of asteroids, including fragments
+    pub asteroids: usize,
+    /// The number of lasers in flight (not counting those resting in the pool)
+    pub lasers: usize,
+    /// The number of explosions going off (not counting those resting in the pool)
+    pub explosions: usize,
+    /// The number of entities in the world, of every kind
+    pub entities: usize,
+}
+
+/// The debug overlay: the collision boxes, timings and entity counts
+/// we need to see while tuning the game
+///
+/// The [DebugSystem](../systems/struct.DebugSystem.html) fills in the stats every frame,
+/// and, while the overlay is visible, draws the collision boxes and shows the stats on the HUD.
+/// The game state shows or hides it when F3 is pressed.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct DebugOverlay {
+    /// Whether or not the overlay is shown
+    pub visible: bool,
+    /// The timings and entity counts of the current frame
+    pub stats: DebugStats,
+}
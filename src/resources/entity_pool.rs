@@ -0,0 +1,148 @@
+use std::marker::PhantomData;
+
+use amethyst::core::Hidden;
+use amethyst::ecs::prelude::{BitSet, Entities, Entity, LazyUpdate};
+
+use crate::components::Inactive;
+
+/// A pool of entities of one kind, which are re-used rather than created and deleted
+///
+/// Short-lived entities, such as lasers and explosions, come and go many times a second.
+/// Rather than creating a new entity each time one is needed, and deleting it again
+/// once it is finished with, the pool keeps finished entities to hand them out again:
+///
+/// * [acquire](#method.acquire) hands out a resting entity, or creates a new one
+///   if they are all in use;
+/// * [release](#method.release) puts the entity back in the pool.
+///
+/// While an entity rests in the pool, it keeps its components, but it is
+/// [Hidden](https://docs.amethyst.rs/stable/amethyst_core/struct.Hidden.html), so it isn't drawn,
+/// and [Inactive](../components/struct.Inactive.html), so the systems leave it alone.
+///
+/// Like [fire_laser](../entities/laser/fn.fire_laser.html), the pool works from within a system,
+/// so the changes to the entity's components are queued on the lazy update.
+///
+/// The type parameter is the component that marks out the kind of entity in the pool
+/// (e.g. `EntityPool<Laser>`), so each kind of entity gets its own pool.
+pub struct EntityPool<T> {
+    /// The entities resting in the pool, ready to be handed out
+    resting: Vec<Entity>,
+    /// The ids of the entities that have been handed out, and not yet put back
+    in_use: BitSet,
+    /// How many entities the pool has had to create
+    pub created: usize,
+    /// How many times the pool has handed out an entity it already had
+    pub reused: usize,
+    kind: PhantomData<T>,
+}
+
+impl<T> Default for EntityPool<T> {
+    fn default() -> Self {
+        EntityPool {
+            resting: Vec::new(),
+            in_use: BitSet::new(),
+            created: 0,
+            reused: 0,
+            kind: PhantomData,
+        }
+    }
+}
+
+impl<T> EntityPool<T> {
+    /// Hands out an entity from the pool, creating one if none are resting
+    ///
+    /// A re-used entity still has the components it had when it was released,
+    /// so the caller should insert fresh ones (through the lazy update) straight away.
+    pub fn acquire(&mut self, entities: &Entities, lazy_update: &LazyUpdate) -> Entity {
+        let entity = match self.resting.pop() {
+            Some(entity) => {
+                lazy_update.remove::<Hidden>(entity);
+                lazy_update.remove::<Inactive>(entity);
+                self.reused += 1;
+                entity
+            }
+            None => {
+                self.created += 1;
+                entities.create()
+            }
+        };
+        self.in_use.add(entity.id());
+        entity
+    }
+
+    /// Puts an entity back in the pool, hiding it and marking it inactive
+    ///
+    /// An entity that isn't in use (e.g. one released twice in the same frame,
+    /// by two different systems) is left alone.
+    pub fn release(&mut self, entity: Entity, lazy_update: &LazyUpdate) {
+        if self.in_use.remove(entity.id()) {
+            lazy_update.insert(entity, Hidden);
+            lazy_update.insert(entity, Inactive);
+            self.resting.push(entity);
+        }
+    }
+
+    /// How many entities are handed out
+    pub fn in_use(&self) -> usize {
+        self.created - self.resting.len()
+    }
+
+    /// How many entities are resting in the pool
+    pub fn resting(&self) -> usize {
+        self.resting.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use amethyst::ecs::prelude::{World, WorldExt};
+
+    /// The marker of the entities in the test pool
+    struct Pooled;
+
+    fn new_world() -> World {
+        let mut world = World::new();
+        world.register::<Hidden>();
+        world.register::<Inactive>();
+        world
+    }
+
+    #[test]
+    fn released_entities_are_handed_out_again() {
+        let mut world = new_world();
+        let mut pool = EntityPool::<Pooled>::default();
+        let entity = pool.acquire(&world.entities(), &world.read_resource::<LazyUpdate>());
+        assert_eq!((pool.created, pool.reused, pool.in_use()), (1, 0, 1));
+
+        pool.release(entity, &world.read_resource::<LazyUpdate>());
+        world.maintain();
+        assert_eq!((pool.in_use(), pool.resting()), (0, 1));
+        assert!(world.read_storage::<Inactive>().contains(entity));
+        assert!(world.read_storage::<Hidden>().contains(entity));
+
+        let reused = pool.acquire(&world.entities(), &world.read_resource::<LazyUpdate>());
+        world.maintain();
+        assert_eq!(reused, entity);
+        assert_eq!((pool.created, pool.reused, pool.in_use()), (1, 1, 1));
+        assert!(!world.read_storage::<Inactive>().contains(entity));
+        assert!(!world.read_storage::<Hidden>().contains(entity));
+    }
+
+    #[test]
+    fn releasing_an_entity_twice_rests_it_once() {
+        let mut world = new_world();
+        let mut pool = EntityPool::<Pooled>::default();
+        let entity = pool.acquire(&world.entities(), &world.read_resource::<LazyUpdate>());
+        pool.release(entity, &world.read_resource::<LazyUpdate>());
+        pool.release(entity, &world.read_resource::<LazyUpdate>());
+        world.maintain();
+        assert_eq!((pool.in_use(), pool.resting()), (0, 1));
+
+        let first = pool.acquire(&world.entities(), &world.read_resource::<LazyUpdate>());
+        let second = pool.acquire(&world.entities(), &world.read_resource::<LazyUpdate>());
+        assert_eq!(first, entity);
+        assert_ne!(second, entity);
+        assert_eq!(pool.created, 2);
+    }
+}
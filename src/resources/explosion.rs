@@ -0,0 +1,11 @@
+use amethyst::renderer::SpriteRender;
+
+/// The resource containing data we need to create an explosion entity.
+///
+/// Like the [LaserResource](struct.LaserResource.html), this lets a system
+/// create an explosion on the fly without loading the sprite again.
+#[derive(Clone)]
+pub struct ExplosionResource {
+    /// The render that locates the explosion sprite in a sprite sheet resource
+    pub sprite_render: SpriteRender,
+}
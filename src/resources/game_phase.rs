@@ -0,0 +1,21 @@
+/// Which part of the game we are in
+///
+/// The gameplay systems only run while the game is being played:
+/// they are made [pausable](https://docs.amethyst.rs/stable/amethyst_core/trait.SystemExt.html)
+/// on this resource when the [GameBundle](../struct.GameBundle.html) adds them.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum GamePhase {
+    /// The player is choosing a ship
+    Menu,
+    /// The game is being played
+    Playing,
+    /// The game is waiting for the other players' controls in a
+    /// [network game](../network/index.html)
+    Waiting,
+}
+
+impl Default for GamePhase {
+    fn default() -> Self {
+        GamePhase::Menu
+    }
+}
@@ -0,0 +1,50 @@
+use std::path::PathBuf;
+
+use amethyst::config::Config;
+use serde_derive::{Deserialize, Serialize};
+
+use crate::user_files::user_data_path;
+
+/// The name of the high score file in the player's data directory
+const HIGH_SCORE_FILE: &str = "high_score.ron";
+
+/// The best score of any game played so far
+///
+/// The high score is kept in the player's data directory
+/// (see [user_data_path](../fn.user_data_path.html)), so it carries over from game to game.
+/// It is loaded when the game starts, and saved when the game stops if a player beat it.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct HighScore {
+    /// The best score of any game played before this one
+    pub score: u32,
+}
+
+impl HighScore {
+    /// The path of the high score file
+    pub fn path() -> PathBuf {
+        user_data_path(HIGH_SCORE_FILE)
+    }
+
+    /// Loads the high score, which is zero if no game has been saved yet
+    pub fn load_or_default() -> HighScore {
+        let path = HighScore::path();
+        if path.exists() {
+            HighScore::load(&path).unwrap_or_default()
+        } else {
+            HighScore::default()
+        }
+    }
+
+    /// Saves the given score as the new high score
+    pub fn save(score: u32) -> Result<PathBuf, String> {
+        let path = HighScore::path();
+        if let Some(directory) = path.parent() {
+            std::fs::create_dir_all(directory)
+                .map_err(|error| format!("Can't create {}: {}", directory.display(), error))?;
+        }
+        HighScore { score }
+            .write(&path)
+            .map_err(|error| format!("Can't save the high score {}: {}", path.display(), error))?;
+        Ok(path)
+    }
+}
@@ -0,0 +1,12 @@
+use amethyst::renderer::SpriteRender;
+
+/// The resource containing data we need to create a life icon.
+///
+/// Like the [LaserResource](struct.LaserResource.html), this lets a system
+/// create life icons on the fly (e.g. when a player earns an extra life)
+/// without loading the sprite again.
+#[derive(Clone)]
+pub struct LivesResource {
+    /// The render that locates the life icon sprite in a sprite sheet resource
+    pub sprite_render: SpriteRender,
+}
@@ -0,0 +1,92 @@
+use std::path::PathBuf;
+
+use amethyst::config::Config;
+use serde_derive::{Deserialize, Serialize};
+
+use crate::config::GAME_CONFIGURATION;
+use crate::localisation::{fill_in, CATALOGUES};
+use crate::user_files::user_config_path;
+
+/// The name of the chosen language file in the player's configuration directory
+const LANGUAGE_FILE: &str = "language.ron";
+
+/// The language the players chose, and the text of each message in it
+///
+/// The players switch language on the ship-select screen. Like their
+/// [key bindings](../fn.load_key_bindings.html), their choice is kept in their configuration directory
+/// (see [user_config_path](../fn.user_config_path.html)), so the game starts in it next time.
+/// Until they choose, the game speaks the language set in the
+/// [game configuration](../struct.GameConfiguration.html).
+///
+/// The messages themselves come from the [catalogues](../struct.Catalogues.html).
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct Localisation {
+    /// The language code of the chosen language (e.g. "en")
+    pub language: String,
+}
+
+impl Default for Localisation {
+    fn default() -> Self {
+        Localisation {
+            language: GAME_CONFIGURATION.language.clone(),
+        }
+    }
+}
+
+impl Localisation {
+    /// The path of the chosen language file
+    pub fn path() -> PathBuf {
+        user_config_path(LANGUAGE_FILE)
+    }
+
+    /// Loads the language the player chose, unless it no longer has a catalogue
+    pub fn load_or_default() -> Localisation {
+        let path = Localisation::path();
+        if path.exists() {
+            if let Ok(localisation) = Localisation::load(&path) {
+                if CATALOGUES.has_language(&localisation.language) {
+                    return localisation;
+                }
+            }
+        }
+        Localisation::default()
+    }
+
+    /// Saves the chosen language as the player's own
+    pub fn save(&self) -> Result<PathBuf, String> {
+        let path = Localisation::path();
+        if let Some(directory) = path.parent() {
+            std::fs::create_dir_all(directory)
+                .map_err(|error| format!("Can't create {}: {}", directory.display(), error))?;
+        }
+        self.write(&path)
+            .map_err(|error| format!("Can't save the language {}: {}", path.display(), error))?;
+        Ok(path)
+    }
+
+    /// The text of the given message
+    pub fn text(&self, id: &str) -> String {
+        CATALOGUES.message(&self.language, id).to_string()
+    }
+
+    /// The text of the given message, with its gaps filled in from the given arguments
+    pub fn format(&self, id: &str, arguments: &[(&str, String)]) -> String {
+        fill_in(CATALOGUES.message(&self.language, id), arguments)
+    }
+
+    /// The name of the chosen language, in the language itself
+    pub fn language_name(&self) -> String {
+        CATALOGUES
+            .languages
+            .get(&self.language)
+            .map(|catalogue| catalogue.name.clone())
+            .unwrap_or_else(|| self.language.clone())
+    }
+
+    /// Moves on to the next language that has a catalogue
+    pub fn switch_to_next_language(&mut self) {
+        if let Some(language) = CATALOGUES.next_language(&self.language) {
+            self.language = language.to_string();
+        }
+    }
+}
@@ -0,0 +1,423 @@
+use std::collections::{BTreeMap, VecDeque};
+use std::net::{SocketAddr, UdpSocket};
+use std::time::{Duration, Instant};
+
+use crate::network::{receive_messages, send_message, FrameControls, FrameHash, NetMessage};
+use crate::resources::PlayerControls;
+
+/// How many frames after they are read the players' controls are played
+///
+/// The delay gives the controls time to reach the other copies of the game
+/// before they are needed, so the game doesn't stop to wait for them.
+pub const INPUT_DELAY: u64 = 3;
+
+/// How many frames of its controls (and hashes) a copy sends each time
+const RESEND_WINDOW: usize = 16;
+
+/// How long a copy waits to hear from another before giving up on it
+const PEER_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// How many frames of hashes a copy keeps to compare with the others
+const HASH_HISTORY: u64 = 600;
+
+/// The state of a game played over the local network, in lockstep
+/// (see [network games](../network/index.html))
+///
+/// Every frame, the [LockstepSystem](../systems/struct.LockstepSystem.html) reads the local player's
+/// controls and sends them on to the other copies of the game, to be played
+/// [INPUT_DELAY](constant.INPUT_DELAY.html) frames later.
+/// A frame is only played once every player's controls for it have arrived;
+/// until then the game waits (see [GamePhase](enum.GamePhase.html)).
+///
+/// By default there is no network game, and the lockstep leaves the game alone.
+#[derive(Debug)]
+pub struct Lockstep {
+    /// The socket the copy talks to the others through, or `None` when the game isn't networked
+    socket: Option<UdpSocket>,
+    /// The player this copy flies (0 being player one, who hosts the game)
+    pub local_player: usize,
+    /// How many players share the game
+    pub player_count: usize,
+    /// The addresses of the other copies this copy talks to:
+    /// for the host, every other copy in player order; for the others, just the host
+    peers: Vec<SocketAddr>,
+    /// When we last heard from each peer
+    last_heard: Vec<Instant>,
+    /// For the host, the `Start` message of each peer, sent again if the peer asks to join again
+    starts: Vec<NetMessage>,
+    /// The next frame to play
+    pub next_frame: u64,
+    /// The controls of every player for the frames to come, as far as they have arrived
+    inputs: BTreeMap<u64, Vec<Option<PlayerControls>>>,
+    /// The local player's controls for the last few frames, to send (again) to the others
+    sent_controls: VecDeque<FrameControls>,
+    /// How many frames have been played and hashed
+    frames_hashed: u64,
+    /// The hashes of this copy's game state after each of the last frames played
+    local_hashes: BTreeMap<u64, u64>,
+    /// The hashes of the other copies' game states, for the frames this copy hasn't played yet
+    remote_hashes: BTreeMap<u64, Vec<(usize, u64)>>,
+    /// Why the network game can't go on, if it can't
+    pub failure: Option<String>,
+}
+
+impl Default for Lockstep {
+    fn default() -> Self {
+        Lockstep {
+            socket: None,
+            local_player: 0,
+            player_count: 1,
+            peers: Vec::new(),
+            last_heard: Vec::new(),
+            starts: Vec::new(),
+            next_frame: 0,
+            inputs: BTreeMap::new(),
+            sent_controls: VecDeque::new(),
+            frames_hashed: 0,
+            local_hashes: BTreeMap::new(),
+            remote_hashes: BTreeMap::new(),
+            failure: None,
+        }
+    }
+}
+
+impl Lockstep {
+    /// The lockstep of a network game that has just started
+    ///
+    /// Nobody has touched their controls before the first frames are played,
+    /// so those frames are played with every player's controls let go.
+    pub fn new(
+        socket: UdpSocket,
+        local_player: usize,
+        player_count: usize,
+        peers: Vec<SocketAddr>,
+        starts: Vec<NetMessage>,
+    ) -> Lockstep {
+        let inputs = (0..INPUT_DELAY)
+            .map(|frame| (frame, vec![Some(PlayerControls::default()); player_count]))
+            .collect();
+        Lockstep {
+            socket: Some(socket),
+            local_player,
+            player_count,
+            last_heard: vec![Instant::now(); peers.len()],
+            peers,
+            starts,
+            inputs,
+            ..Lockstep::default()
+        }
+    }
+
+    /// Whether or not the game is being played over the network
+    pub fn is_networked(&self) -> bool {
+        self.socket.is_some()
+    }
+
+    /// Deals with the messages that have come in from the other copies
+    ///
+    /// The host passes every player's controls on to the other copies.
+    pub fn receive(&mut self) {
+        let socket = match &self.socket {
+            Some(socket) => socket,
+            None => return,
+        };
+        let is_host = self.local_player == 0;
+        let player_count = self.player_count;
+        for (sender, message, datagram) in receive_messages(socket) {
+            let peer = match self.peers.iter().position(|peer| *peer == sender) {
+                Some(peer) => peer,
+                None => continue,
+            };
+            self.last_heard[peer] = Instant::now();
+            match message {
+                // the peer didn't hear that the game started
+                NetMessage::Join { .. } if is_host => {
+                    if let Some(start) = self.starts.get(peer) {
+                        send_message(socket, sender, start);
+                    }
+                }
+                NetMessage::Input {
+                    player,
+                    controls,
+                    hashes,
+                } => {
+                    if player >= player_count || player == self.local_player {
+                        continue;
+                    }
+                    for frame_controls in controls {
+                        if frame_controls.frame >= self.next_frame {
+                            self.inputs
+                                .entry(frame_controls.frame)
+                                .or_insert_with(|| vec![None; player_count])[player] =
+                                Some(frame_controls.controls());
+                        }
+                    }
+                    for frame_hash in hashes {
+                        check_hash(
+                            &self.local_hashes,
+                            &mut self.remote_hashes,
+                            &mut self.failure,
+                            self.frames_hashed,
+                            player,
+                            frame_hash,
+                        );
+                    }
+                    if is_host {
+                        relay(socket, &self.peers, sender, &datagram);
+                    }
+                }
+                NetMessage::Leave { player } => {
+                    self.failure = Some(format!("Player {} has left the game", player + 1));
+                    if is_host {
+                        relay(socket, &self.peers, sender, &datagram);
+                    }
+                }
+                _ => {}
+            }
+        }
+        if self.failure.is_none() {
+            if let Some(peer) = self
+                .last_heard
+                .iter()
+                .position(|heard| heard.elapsed() > PEER_TIMEOUT)
+            {
+                self.failure = Some(format!("Lost touch with {}", self.peers[peer]));
+            }
+        }
+    }
+
+    /// Whether or not frames have been played since the game state was last hashed
+    pub fn is_hash_due(&self, frames_played: u64) -> bool {
+        frames_played > self.frames_hashed
+    }
+
+    /// Notes down the hash of the game state once the given number of frames have been played,
+    /// and checks it against the hashes the other copies sent for the same frame
+    pub fn record_hash(&mut self, frames_played: u64, hash: u64) {
+        if frames_played == 0 {
+            return;
+        }
+        let frame = frames_played - 1;
+        self.frames_hashed = frames_played;
+        self.local_hashes.insert(frame, hash);
+        for (player, remote_hash) in self.remote_hashes.remove(&frame).unwrap_or_default() {
+            if remote_hash != hash && self.failure.is_none() {
+                self.failure = Some(desync_message(player, frame));
+            }
+        }
+        let oldest = frame.saturating_sub(HASH_HISTORY);
+        self.local_hashes = self.local_hashes.split_off(&oldest);
+        self.remote_hashes = self.remote_hashes.split_off(&oldest);
+    }
+
+    /// The controls of every player for the next frame, if they have all arrived
+    pub fn is_next_frame_ready(&self) -> bool {
+        self.inputs
+            .get(&self.next_frame)
+            .map_or(false, |players| players.iter().all(Option::is_some))
+    }
+
+    /// Moves on to the next frame, returning every player's controls for it
+    ///
+    /// The local player's controls, just read, are sent to the other copies
+    /// to be played [INPUT_DELAY](constant.INPUT_DELAY.html) frames from now.
+    /// Every copy plays them as sent, rounded the same way.
+    pub fn advance(&mut self, local_controls: &PlayerControls) -> Vec<PlayerControls> {
+        let players = self
+            .inputs
+            .remove(&self.next_frame)
+            .unwrap_or_default()
+            .into_iter()
+            .map(Option::unwrap_or_default)
+            .collect();
+        let frame_controls = FrameControls::new(self.next_frame + INPUT_DELAY, local_controls);
+        let player_count = self.player_count;
+        self.inputs
+            .entry(frame_controls.frame)
+            .or_insert_with(|| vec![None; player_count])[self.local_player] =
+            Some(frame_controls.controls());
+        self.sent_controls.push_back(frame_controls);
+        while self.sent_controls.len() > RESEND_WINDOW {
+            self.sent_controls.pop_front();
+        }
+        self.next_frame += 1;
+        self.send_input();
+        players
+    }
+
+    /// Sends the local player's controls for the last few frames, and the latest hashes,
+    /// to the other copies
+    ///
+    /// While the game waits for the other players, this is sent again every frame,
+    /// in case the last one was lost.
+    pub fn send_input(&self) {
+        let socket = match &self.socket {
+            Some(socket) => socket,
+            None => return,
+        };
+        let message = NetMessage::Input {
+            player: self.local_player,
+            controls: self.sent_controls.iter().copied().collect(),
+            hashes: self
+                .local_hashes
+                .iter()
+                .rev()
+                .take(RESEND_WINDOW)
+                .map(|(frame, hash)| FrameHash {
+                    frame: *frame,
+                    hash: *hash,
+                })
+                .collect(),
+        };
+        for peer in &self.peers {
+            send_message(socket, *peer, &message);
+        }
+    }
+
+    /// Tells the other copies this player has quit
+    pub fn leave(&self) {
+        if let Some(socket) = &self.socket {
+            let message = NetMessage::Leave {
+                player: self.local_player,
+            };
+            for peer in &self.peers {
+                send_message(socket, *peer, &message);
+            }
+        }
+    }
+}
+
+/// Checks another copy's hash for a frame against ours,
+/// or keeps it until we have played that frame
+fn check_hash(
+    local_hashes: &BTreeMap<u64, u64>,
+    remote_hashes: &mut BTreeMap<u64, Vec<(usize, u64)>>,
+    failure: &mut Option<String>,
+    frames_hashed: u64,
+    player: usize,
+    frame_hash: FrameHash,
+) {
+    match local_hashes.get(&frame_hash.frame) {
+        Some(hash) => {
+            if *hash != frame_hash.hash && failure.is_none() {
+                *failure = Some(desync_message(player, frame_hash.frame));
+            }
+        }
+        // a hash for a frame we've played but no longer remember is too old to matter
+        None if frame_hash.frame >= frames_hashed => {
+            let hashes = remote_hashes.entry(frame_hash.frame).or_default();
+            if !hashes.contains(&(player, frame_hash.hash)) {
+                hashes.push((player, frame_hash.hash));
+            }
+        }
+        None => {}
+    }
+}
+
+/// Passes a datagram from one of the host's peers on to the others
+fn relay(socket: &UdpSocket, peers: &[SocketAddr], sender: SocketAddr, datagram: &[u8]) {
+    for peer in peers.iter().filter(|peer| **peer != sender) {
+        // a lost datagram is made up for by the ones sent after it
+        let _result = socket.send_to(datagram, *peer);
+    }
+}
+
+/// What to say when another player's game no longer matches ours
+fn desync_message(player: usize, frame: u64) -> String {
+    format!(
+        "The game has gone out of sync: player {}'s game differs from this one at frame {}",
+        player + 1,
+        frame
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread::sleep;
+
+    /// A non-blocking socket on the loopback address
+    fn test_socket() -> UdpSocket {
+        let socket = UdpSocket::bind("127.0.0.1:0").unwrap();
+        socket.set_nonblocking(true).unwrap();
+        socket
+    }
+
+    /// The lockstep of a host and a guest, playing a game for two
+    fn test_locksteps() -> (Lockstep, Lockstep) {
+        let (host_socket, guest_socket) = (test_socket(), test_socket());
+        let host_address = host_socket.local_addr().unwrap();
+        let guest_address = guest_socket.local_addr().unwrap();
+        (
+            Lockstep::new(host_socket, 0, 2, vec![guest_address], Vec::new()),
+            Lockstep::new(guest_socket, 1, 2, vec![host_address], Vec::new()),
+        )
+    }
+
+    /// Gives the datagrams sent so far time to arrive
+    fn wait_for_datagrams() {
+        sleep(Duration::from_millis(20));
+    }
+
+    #[test]
+    fn a_lost_datagram_is_made_up_for_by_the_next() {
+        let (mut host, mut guest) = test_locksteps();
+        let pushed_right = PlayerControls {
+            horizontal: 1.0,
+            vertical: 0.0,
+            fire: true,
+        };
+        // the controls for frame 3 are lost on the way
+        host.advance(&pushed_right);
+        wait_for_datagrams();
+        let lost = receive_messages(guest.socket.as_ref().unwrap());
+        assert_eq!(lost.len(), 1);
+
+        for _ in 0..INPUT_DELAY {
+            assert!(guest.is_next_frame_ready());
+            let players = guest.advance(&PlayerControls::default());
+            assert_eq!(players, vec![PlayerControls::default(); 2]);
+        }
+        guest.receive();
+        assert!(!guest.is_next_frame_ready());
+
+        // and sent again with the controls for frame 4
+        host.advance(&PlayerControls::default());
+        wait_for_datagrams();
+        guest.receive();
+        assert!(guest.is_next_frame_ready());
+        let players = guest.advance(&PlayerControls::default());
+        assert_eq!(players[0], FrameControls::new(0, &pushed_right).controls());
+        assert_eq!(guest.next_frame, INPUT_DELAY + 1);
+        assert_eq!(guest.failure, None);
+    }
+
+    #[test]
+    fn a_different_hash_is_a_desync() {
+        /// Checks player two's hash for the frame
+        fn check(lockstep: &mut Lockstep, frame: u64, hash: u64) {
+            check_hash(
+                &lockstep.local_hashes,
+                &mut lockstep.remote_hashes,
+                &mut lockstep.failure,
+                lockstep.frames_hashed,
+                1,
+                FrameHash { frame, hash },
+            );
+        }
+
+        let mut lockstep = Lockstep::default();
+        lockstep.record_hash(1, 100);
+        check(&mut lockstep, 0, 100);
+        // a hash for a frame yet to be played is kept until it has been
+        check(&mut lockstep, 1, 201);
+        assert_eq!(lockstep.failure, None);
+        lockstep.record_hash(2, 200);
+        assert_eq!(lockstep.failure, Some(desync_message(1, 1)));
+
+        let mut lockstep = Lockstep::default();
+        lockstep.record_hash(1, 100);
+        check(&mut lockstep, 0, 101);
+        assert_eq!(lockstep.failure, Some(desync_message(1, 0)));
+    }
+}
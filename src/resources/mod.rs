@@ -7,24 +7,116 @@
 //!
 //! The resources used by the space_shooter game are:
 //!
-//! * **PlayState** the number of lives the player has left
+//! * **PlayState** the number of lives each player has left, the damage their ship has taken, their score and combo,
+//!   and the wave being played
+//! * **HighScore** the best score of the games played before this one.
+//! * **UnlockedAchievements** the achievements the player has unlocked, in this game or before.
+//! * **AchievementToasts** the pop-ups announcing newly unlocked achievements.
 //! * **LaserResource** the texture and material used to create a laser entity on the fly.
+//! * **AsteroidResource** the sprite sheet used to create asteroid fragments on the fly.
+//! * **ExplosionResource** the sprite used to create an explosion on the fly.
+//! * **LivesResource** the sprite used to create a life icon on the fly.
+//! * **BackgroundResource** the sprite sheets used to build the background of a level.
+//! * **EntityPool** the lasers and explosions that are resting, ready to be re-used.
+//! * **ShipDefinition** the ship the player chose on the ship-select screen
+//!   (see the [ship roster](../struct.ShipRoster.html)).
+//! * **Controls** what each player is doing with their controls this frame.
+//! * **Arena** the size of the arena the game is played in, and where it sits in the window.
+//! * **GameClock** how much game time passes this frame.
+//! * **GameRandom** the seeded random number generator behind every random decision.
+//! * **Telemetry** the shots, hits, destroyed asteroids and lost lives so far, for the game's
+//!   [session record](../struct.SessionRecord.html).
+//! * **GamePhase** whether the gameplay systems should run.
+//! * **DebugOverlay** whether the debug overlay is shown, and the timings and entity counts it shows.
+//! * **Localisation** the language the players chose, and the text of each message in it
+//!   (see the [catalogues](../struct.Catalogues.html)).
+//! * **Lockstep** the players' controls swapped with the other copies of the game
+//!   in a [network game](../network/index.html).
+//! * **Session** whether the players' controls are live, recorded or played back
+//!   (see [replays](../struct.Replay.html)).
 //!
 //! The resources demonstrate two standard patterns in COP/ECS systems:
 //!
 //! * Using a resource to transfer information from the entity-creation phase to the system phase (LaserResource) and
 //! * Using a resource to transfer information between systems and to the game state (PlayState)
 
+mod achievements;
+mod arena;
+mod asteroid;
+mod background;
+mod clock;
+mod controls;
+mod debug_overlay;
+mod entity_pool;
+mod explosion;
+mod game_phase;
+mod high_score;
 mod laser;
+mod lives;
+mod localisation;
+mod lockstep;
 mod play_state;
+mod random;
+mod telemetry;
 
 use amethyst::ecs::prelude::World;
 
+use crate::components::{Explosion, Laser};
+use crate::config::GAME_CONFIGURATION;
+use crate::replay::Session;
+
+pub use self::achievements::{AchievementToasts, UnlockedAchievements};
+pub use self::arena::Arena;
+pub use self::asteroid::AsteroidResource;
+pub use self::background::BackgroundResource;
+pub use self::clock::GameClock;
+pub use self::controls::{Controls, PlayerControls};
+pub use self::debug_overlay::{DebugOverlay, DebugStats};
+pub use self::entity_pool::EntityPool;
+pub use self::explosion::ExplosionResource;
+pub use self::game_phase::GamePhase;
+pub use self::high_score::HighScore;
 pub use self::laser::LaserResource;
+pub use self::lives::LivesResource;
+pub use self::localisation::Localisation;
+pub use self::lockstep::{Lockstep, INPUT_DELAY};
 pub use self::play_state::PlayState;
+pub use self::play_state::PlayerState;
+pub use self::random::GameRandom;
+pub use self::telemetry::{Telemetry, WaveStats};
 
 /// Add all the resources needed at the start to the world
-/// Note that [laserResource] is not added here, but when the laser component is created.
+/// Note that [laserResource], [asteroidResource], [explosionResource], [livesResource] and [backgroundResource]
+/// are not added here, but when the laser, asteroid, explosion, life and background entities are initialised.
+///
+/// The random number generator is seeded from the [Session](../struct.Session.html),
+/// so a replay gets the same random numbers as the game it recorded.
+/// The seed also tells the games apart in the [telemetry](../struct.SessionRecord.html).
 pub fn add_resources(world: &mut World) {
-    world.insert(PlayState { lives: 3 });
+    let (seed, keeps_records) = {
+        let session = world.read_resource::<Session>();
+        (session.seed(), session.keeps_records())
+    };
+    let player_count = GAME_CONFIGURATION.player_count();
+    world.insert(PlayState::new(player_count, GAME_CONFIGURATION.starting_lives()));
+    world.insert(HighScore::load_or_default());
+    world.insert(UnlockedAchievements::load_or_default());
+    world.insert(AchievementToasts::default());
+    world.insert(GameRandom::new(seed));
+    world.insert(GameClock::default());
+    // a replay repeats a game that was already played, so its events aren't streamed again
+    world.insert(Telemetry::new(
+        seed,
+        GAME_CONFIGURATION.stream_telemetry_events && keeps_records,
+    ));
+    world.insert(Arena::default());
+    world.insert(EntityPool::<Laser>::default());
+    world.insert(EntityPool::<Explosion>::default());
+    world.insert(DebugOverlay {
+        visible: GAME_CONFIGURATION.debug_overlay,
+        stats: DebugStats::default(),
+    });
+    world.insert(Controls {
+        players: vec![PlayerControls::default(); player_count],
+    });
 }
@@ -1,16 +1,124 @@
+use serde_derive::{Deserialize, Serialize};
+
+use crate::config::GAME_CONFIGURATION;
+
+/// The state of one player in the game
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct PlayerState {
+    /// Number of lives the player has currently
+    pub lives: u8,
+    /// Number of hits the player's ship has taken since it was last repaired
+    pub damage: u8,
+    /// The player's score
+    pub score: u32,
+    /// How many extra lives the player has been awarded for their score
+    pub extra_lives_awarded: usize,
+    /// How many asteroids the player has destroyed in a row,
+    /// without a laser missing or their ship being destroyed
+    pub combo: u32,
+}
+
+impl PlayerState {
+    /// The number the player's points are multiplied by, which grows with their combo
+    pub fn multiplier(&self) -> u32 {
+        GAME_CONFIGURATION.score_multiplier(self.combo)
+    }
+}
+
 /// The play state of our game
 ///
-/// This resource stores the number of lives the player has currently.
+/// This resource stores, for each player, the number of lives they have currently,
+/// how much damage their ship has taken, and their score.
+///
+/// Every time a ship collides with an asteroid, the number of lives
+/// of its player is reduced and the damage is increased.
+/// An extra life repairs the ship, clearing the damage.
+/// Every time a laser destroys an asteroid, the player who fired it scores,
+/// and the wave moves on once enough asteroids have been destroyed.
 ///
-/// Every time the ship collides with an asteroid, the number of lives
-/// is reduced.
+/// The lives system uses this resource to award extra lives as the players score,
+/// and to determine how many life icons to display on the UI level for each player.
 ///
-/// The lives system uses this resource to determine how many
-/// life icons to display on the UI level.
+/// The damage system uses this resource to determine which damage
+/// overlay to draw over each ship.
 ///
-/// The game state uses this resource to determine when to end the game.
-#[derive(Clone, Default)]
+/// The background system uses this resource to show the background of the current level.
+///
+/// The HUD system uses this resource to show each player's score and multiplier, and the wave.
+///
+/// The game state uses this resource to determine when to end the game:
+/// the game is over once every player is out of lives.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
 pub struct PlayState {
-    /// Number of lives the player has currently
-    pub lives: u8,
+    /// The state of each player, in player order
+    pub players: Vec<PlayerState>,
+    /// The level (or wave) being played (1 being the first), which chooses the background
+    pub level: u32,
+    /// How many asteroids have been destroyed since the wave started
+    pub wave_progress: u32,
+}
+
+impl PlayState {
+    /// Creates the play state for the given number of players,
+    /// each starting with the given number of lives
+    pub fn new(player_count: usize, lives: u8) -> PlayState {
+        PlayState {
+            players: vec![
+                PlayerState {
+                    lives,
+                    damage: 0,
+                    score: 0,
+                    extra_lives_awarded: 0,
+                    combo: 0,
+                };
+                player_count
+            ],
+            level: 1,
+            wave_progress: 0,
+        }
+    }
+
+    /// Whether or not every player is out of lives
+    pub fn is_game_over(&self) -> bool {
+        self.players.iter().all(|player| player.lives == 0)
+    }
+
+    /// The best score of any player
+    pub fn best_score(&self) -> u32 {
+        self.players
+            .iter()
+            .map(|player| player.score)
+            .max()
+            .unwrap_or_default()
+    }
+
+    /// Scores an asteroid destroyed by the given player's laser
+    ///
+    /// The asteroid adds to the player's combo, then scores its points
+    /// times the player's multiplier.
+    /// Once enough asteroids have been destroyed (see the
+    /// [game configuration](../struct.GameConfiguration.html)), the next wave starts.
+    pub fn score_asteroid(&mut self, player: usize) {
+        if let Some(player_state) = self.players.get_mut(player) {
+            player_state.combo = player_state.combo.saturating_add(1);
+            let points = GAME_CONFIGURATION
+                .asteroid_points
+                .saturating_mul(player_state.multiplier());
+            player_state.score = player_state.score.saturating_add(points);
+        }
+        self.wave_progress += 1;
+        if GAME_CONFIGURATION.wave_asteroids > 0
+            && self.wave_progress >= GAME_CONFIGURATION.wave_asteroids
+        {
+            self.level += 1;
+            self.wave_progress = 0;
+        }
+    }
+
+    /// Ends the given player's combo (when their laser misses or their ship is destroyed)
+    pub fn break_combo(&mut self, player: usize) {
+        if let Some(player_state) = self.players.get_mut(player) {
+            player_state.combo = 0;
+        }
+    }
 }
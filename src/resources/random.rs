@@ -0,0 +1,49 @@
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+/// The game's random number generator
+///
+/// Every random decision in the game (where asteroids appear, how they spin...)
+/// is drawn from this one seeded generator, so the same seed and the same
+/// controls always give the same game. That's what makes replays possible.
+///
+/// Note the algorithm behind `StdRng` may change with new versions of rand,
+/// so a replay is only certain to play out the same with the build that recorded it.
+///
+/// The generator's inner state can't be saved, so a [saved game](../struct.SavedGame.html)
+/// keeps a fresh seed drawn from the generator instead (see [reseed](#method.reseed)).
+pub struct GameRandom {
+    /// The seed the generator started from
+    pub seed: u64,
+    /// The generator itself
+    pub rng: StdRng,
+}
+
+impl GameRandom {
+    /// Creates the random number generator from the given seed
+    pub fn new(seed: u64) -> GameRandom {
+        GameRandom {
+            seed,
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+
+    /// Creates the random number generator of a game with the given seed,
+    /// carrying on from the given seed drawn part way through (see [reseed](#method.reseed))
+    pub fn resume(seed: u64, resume_seed: u64) -> GameRandom {
+        GameRandom {
+            seed,
+            rng: StdRng::seed_from_u64(resume_seed),
+        }
+    }
+
+    /// Draws a new seed from the generator, and restarts the generator from it
+    ///
+    /// A generator [resumed](#method.resume) from the new seed draws the same numbers
+    /// as this one does from now on.
+    pub fn reseed(&mut self) -> u64 {
+        let resume_seed = self.rng.gen();
+        self.rng = StdRng::seed_from_u64(resume_seed);
+        resume_seed
+    }
+}
@@ -0,0 +1,142 @@
+use serde_derive::{Deserialize, Serialize};
+
+use crate::config::configuration_hash;
+use crate::resources::{GameClock, PlayState};
+use crate::telemetry::{seconds_since_epoch, SessionRecord, TelemetryEvent, TelemetryEventKind};
+
+/// The shots fired and hits of one wave
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct WaveStats {
+    /// How many lasers the players fired during the wave
+    pub shots_fired: u32,
+    /// How many of those lasers hit an asteroid
+    pub hits: u32,
+}
+
+impl WaveStats {
+    /// The share of lasers fired during the wave that hit an asteroid (0 to 1; 0 if none were fired)
+    pub fn accuracy(&self) -> f32 {
+        accuracy(self.shots_fired, self.hits)
+    }
+}
+
+/// The running statistics of the game, for its [session record](../struct.SessionRecord.html)
+///
+/// The gameplay systems record each shot, hit, destroyed asteroid, lost or extra life,
+/// and new wave here as it happens.
+/// The counts go into the session record when the game stops;
+/// if the events are being streamed, the [TelemetrySystem](../systems/struct.TelemetrySystem.html)
+/// also writes out each frame's events.
+/// The [AchievementSystem](../systems/struct.AchievementSystem.html) checks the statistics
+/// against the achievements' conditions.
+///
+/// The statistics are kept in [saved games](../struct.SavedGame.html), but the events waiting
+/// to be written out aren't.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct Telemetry {
+    /// The seed of the game, which tells the games' events apart
+    pub seed: u64,
+    /// Whether or not each event is written out as it happens
+    #[serde(skip)]
+    pub stream_events: bool,
+    /// How many lasers the players have fired
+    pub shots_fired: u32,
+    /// How many of those lasers have hit an asteroid
+    pub hits: u32,
+    /// How many asteroids the players have destroyed
+    pub asteroids_destroyed: u32,
+    /// How many lives the players have lost
+    pub lives_lost: u32,
+    /// The game time at which a player last lost a life (sec), or 0 if no lives have been lost
+    pub last_life_lost_at: f32,
+    /// The shots fired and hits of the wave being played
+    pub current_wave: WaveStats,
+    /// The shots fired and hits of the last wave to be finished, if any
+    pub last_completed_wave: Option<WaveStats>,
+    /// The events still to be written out, if they are being streamed
+    #[serde(skip)]
+    pub pending_events: Vec<TelemetryEvent>,
+}
+
+impl Telemetry {
+    /// Creates the statistics of a game with the given seed
+    pub fn new(seed: u64, stream_events: bool) -> Telemetry {
+        Telemetry {
+            seed,
+            stream_events,
+            ..Telemetry::default()
+        }
+    }
+
+    /// Counts something that happened during the game, at the time on the game clock
+    pub fn record(&mut self, game_clock: &GameClock, kind: TelemetryEventKind) {
+        match kind {
+            TelemetryEventKind::ShotFired { .. } => {
+                self.shots_fired += 1;
+                self.current_wave.shots_fired += 1;
+            }
+            TelemetryEventKind::AsteroidHit { .. } => {
+                self.hits += 1;
+                self.current_wave.hits += 1;
+            }
+            TelemetryEventKind::AsteroidDestroyed { .. } => self.asteroids_destroyed += 1,
+            TelemetryEventKind::LifeLost { .. } => {
+                self.lives_lost += 1;
+                self.last_life_lost_at = game_clock.elapsed_seconds;
+            }
+            TelemetryEventKind::WaveStarted { .. } => {
+                self.last_completed_wave = Some(self.current_wave);
+                self.current_wave = WaveStats::default();
+            }
+            TelemetryEventKind::ExtraLife { .. } => {}
+        }
+        if self.stream_events {
+            self.pending_events.push(TelemetryEvent {
+                seed: self.seed,
+                frame: game_clock.frame,
+                time: game_clock.elapsed_seconds,
+                kind,
+            });
+        }
+    }
+
+    /// The session record of the game so far, played with the given ship
+    pub fn session_record(
+        &self,
+        play_state: &PlayState,
+        game_clock: &GameClock,
+        ship: &str,
+    ) -> SessionRecord {
+        let scores: Vec<u32> = play_state.players.iter().map(|player| player.score).collect();
+        SessionRecord {
+            finished_at: seconds_since_epoch(),
+            seed: self.seed,
+            config_hash: format!("{:016x}", configuration_hash()),
+            ship: ship.to_string(),
+            player_count: play_state.players.len(),
+            time_survived: game_clock.elapsed_seconds,
+            shots_fired: self.shots_fired,
+            hits: self.hits,
+            accuracy: self.accuracy(),
+            asteroids_destroyed: self.asteroids_destroyed,
+            lives_lost: self.lives_lost,
+            score: scores.iter().sum(),
+            scores,
+            wave: play_state.level,
+        }
+    }
+
+    /// The share of lasers fired that hit an asteroid (0 to 1; 0 if no lasers were fired)
+    pub fn accuracy(&self) -> f32 {
+        accuracy(self.shots_fired, self.hits)
+    }
+}
+
+/// The share of the given shots that were hits (0 to 1; 0 if no shots were fired)
+fn accuracy(shots_fired: u32, hits: u32) -> f32 {
+    if shots_fired == 0 {
+        0.0
+    } else {
+        hits as f32 / shots_fired as f32
+    }
+}
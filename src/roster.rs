@@ -0,0 +1,81 @@
+//! The roster of ships the player can choose from
+//!
+//! Like the [game configuration](struct.GameConfiguration.html), the roster
+//! is loaded from a RON file ("ships.ron" in resources), so the game designer
+//! can add ships and balance them without having to recompile the code.
+//!
+//! Each ship is, in effect, the prefab of the player's ship and its lasers:
+//! like the other [prefabs](struct.Prefabs.html), it refers to its sprites by
+//! [name](struct.SpriteNames.html).
+
+use amethyst::config::Config;
+use lazy_static::lazy_static;
+use serde_derive::{Deserialize, Serialize};
+
+/// The weapon a ship fires
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct WeaponDefinition {
+    /// The name of the laser's sprite, e.g. "laserRed01"
+    pub sprite: String,
+    /// The width of the laser's collision box
+    pub width: f32,
+    /// The height of the laser's collision box
+    pub height: f32,
+    /// The laser's vertical velocity
+    pub velocity: f32,
+}
+
+/// A ship the player can fly, with its look and its handling
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct ShipDefinition {
+    /// The name shown on the ship-select screen
+    pub name: String,
+    /// The name of the ship's sprite, e.g. "playerShip1_blue"
+    pub sprite: String,
+    /// The names of the ship's damage overlay sprites, from the lightest damage to the heaviest
+    pub damage_sprites: Vec<String>,
+    /// The name of the life icon's sprite shown on the UI layer
+    pub life_sprite: String,
+    /// The width of the ship's collision box
+    pub width: f32,
+    /// The height of the ship's collision box
+    pub height: f32,
+    /// The effect each key press has on the ship's speed
+    pub thrust: f32,
+    /// The fastest the ship can go
+    pub max_speed: f32,
+    /// How long to wait after firing a laser before can fire again (sec)
+    pub trigger_reset_timeout: f32,
+    /// The lasers the ship fires
+    pub weapon: WeaponDefinition,
+}
+
+/// The list of ships the player can choose from
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct ShipRoster {
+    /// The ships, in the order they're shown on the ship-select screen
+    pub ships: Vec<ShipDefinition>,
+}
+
+impl ShipRoster {
+    /// The ship with the given name, if there is one
+    pub fn ship_named(&self, name: &str) -> Option<&ShipDefinition> {
+        self.ships.iter().find(|ship| ship.name == name)
+    }
+}
+
+lazy_static! {
+    /// The actual [ship roster](struct.ShipRoster.html).
+    ///
+    /// The roster is automatically loaded on startup
+    /// from the file "ships.ron" in resources.
+    pub static ref SHIP_ROSTER: ShipRoster = {
+        let ship_roster_path = format!(
+            "{}/resources/ships.ron",
+            env!("CARGO_MANIFEST_DIR")
+        );
+        let ship_roster = ShipRoster::load(&ship_roster_path).unwrap();
+        assert!(!ship_roster.ships.is_empty(), "the ship roster needs at least one ship");
+        ship_roster
+    };
+}
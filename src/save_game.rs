@@ -0,0 +1,442 @@
+//! Suspending a game part way through, and resuming it later
+//!
+//! A saved game holds a snapshot of everything that carries a game on:
+//! each ship (position, velocity, trigger timer and status), every asteroid,
+//! the lasers in flight, the [play state](resources/struct.PlayState.html) (scores, lives, wave),
+//! the [statistics](resources/struct.Telemetry.html) so far, the game clock
+//! and the state of the [random number generator](resources/struct.GameRandom.html).
+//! Explosions only last a moment, so they aren't kept.
+//!
+//! Games are saved as RON files in the player's data directory, with the version of the file format.
+//! A save in another version is rejected with a message saying so; when the format changes,
+//! older saves should be converted in [load_saved_game](struct.SavedGame.html#method.load_saved_game)
+//! rather than rejected.
+
+use std::path::{Path, PathBuf};
+
+use amethyst::config::Config;
+use amethyst::core::math::Vector2;
+use amethyst::core::transform::Transform;
+use amethyst::ecs::prelude::{Entity, Join, LazyUpdate, World, WorldExt};
+use amethyst::prelude::Builder;
+use amethyst::renderer::SpriteRender;
+use serde_derive::{Deserialize, Serialize};
+
+use crate::components::{Asteroid, AsteroidSize, Inactive, Laser, Player, Ship, ShipStatus};
+use crate::config::{configuration_hash, GAME_CONFIGURATION};
+use crate::resources::{
+    AsteroidResource, EntityPool, GameClock, GameRandom, LaserResource, PlayState, Telemetry,
+};
+use crate::roster::SHIP_ROSTER;
+use crate::sprites::{sprite_number, SPRITE_NAMES};
+use crate::user_files::user_data_path;
+
+/// The version of the saved game file format
+pub const SAVE_VERSION: u32 = 1;
+
+/// The name of the suspended game file in the player's data directory
+const SAVED_GAME_FILE: &str = "suspended_game.ron";
+
+/// A player's ship, as it was when the game was saved
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct SavedShip {
+    /// The index of the player flying the ship
+    pub player: usize,
+    /// Where the ship was
+    pub position: (f32, f32),
+    /// How fast the ship was going
+    pub velocity: (f32, f32),
+    /// How much time was left before the ship could fire again (sec)
+    pub trigger_reset_timer: f32,
+    /// Whether the ship was flying, destroyed or invulnerable
+    pub status: ShipStatus,
+}
+
+/// An asteroid, as it was when the game was saved
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct SavedAsteroid {
+    /// The name of the asteroid's sprite
+    pub sprite: String,
+    /// Where the asteroid was
+    pub position: (f32, f32),
+    /// How far the asteroid had turned (radians)
+    pub rotation: f32,
+    /// How fast the asteroid was moving
+    pub velocity: (f32, f32),
+    /// How fast the asteroid was spinning (radians per second)
+    pub angular_velocity: f32,
+    /// The width of the asteroid
+    pub width: f32,
+    /// The height of the asteroid
+    pub height: f32,
+    /// The size class of the asteroid
+    pub size: AsteroidSize,
+    /// How many more laser hits the asteroid could take
+    pub hit_points: u8,
+    /// Whether the asteroid was a fragment of a larger asteroid
+    pub is_fragment: bool,
+    /// Whether the asteroid had been destroyed and was waiting to be relocated
+    pub is_destroyed: bool,
+}
+
+/// A laser in flight, as it was when the game was saved
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct SavedLaser {
+    /// The index of the player who fired the laser
+    pub owner: usize,
+    /// Where the laser was
+    pub position: (f32, f32),
+}
+
+/// The part of a saved game that every version of the file format has
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+struct SaveHeader {
+    /// The version of the saved game file format
+    version: u32,
+}
+
+/// A snapshot of a game, to carry it on later
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct SavedGame {
+    /// The version of the saved game file format, see [SAVE_VERSION](constant.SAVE_VERSION.html)
+    pub version: u32,
+    /// The [configuration hash](fn.configuration_hash.html) of the game that was saved
+    pub config_hash: u64,
+    /// The name of the ship chosen from the roster
+    pub ship: String,
+    /// The seed the game's random number generator started from
+    pub seed: u64,
+    /// The seed the random number generator carries on from
+    /// (see [GameRandom](resources/struct.GameRandom.html#method.reseed))
+    pub resume_seed: u64,
+    /// The game clock: how many frames and how much game time had passed
+    pub clock: GameClock,
+    /// The scores, lives, combos and wave
+    pub play_state: PlayState,
+    /// The statistics of the game so far
+    pub telemetry: Telemetry,
+    /// Each player's ship
+    pub ships: Vec<SavedShip>,
+    /// Every asteroid, including the fragments
+    pub asteroids: Vec<SavedAsteroid>,
+    /// The lasers in flight
+    pub lasers: Vec<SavedLaser>,
+}
+
+impl SavedGame {
+    /// The path of the suspended game file
+    pub fn path() -> PathBuf {
+        user_data_path(SAVED_GAME_FILE)
+    }
+
+    /// Takes a snapshot of the game being played
+    ///
+    /// The random number generator is restarted from the seed that is saved,
+    /// so the game carries on the same whether it goes on now or is resumed later.
+    pub fn capture(world: &World, ship_name: &str) -> SavedGame {
+        let ships = world.read_storage::<Ship>();
+        let players = world.read_storage::<Player>();
+        let asteroids = world.read_storage::<Asteroid>();
+        let lasers = world.read_storage::<Laser>();
+        let inactives = world.read_storage::<Inactive>();
+        let transforms = world.read_storage::<Transform>();
+        let sprite_renders = world.read_storage::<SpriteRender>();
+        let (seed, resume_seed) = {
+            let mut game_random = world.write_resource::<GameRandom>();
+            (game_random.seed, game_random.reseed())
+        };
+        SavedGame {
+            version: SAVE_VERSION,
+            config_hash: configuration_hash(),
+            ship: ship_name.to_string(),
+            seed,
+            resume_seed,
+            clock: *world.read_resource::<GameClock>(),
+            play_state: world.read_resource::<PlayState>().clone(),
+            telemetry: world.read_resource::<Telemetry>().clone(),
+            ships: (&ships, &players, &transforms)
+                .join()
+                .map(|(ship, player, transform)| SavedShip {
+                    player: player.index,
+                    position: (transform.translation().x, transform.translation().y),
+                    velocity: (ship.velocity.x, ship.velocity.y),
+                    trigger_reset_timer: ship.trigger_reset_timer,
+                    status: ship.status,
+                })
+                .collect(),
+            asteroids: (&asteroids, &transforms, &sprite_renders)
+                .join()
+                .map(|(asteroid, transform, sprite_render)| SavedAsteroid {
+                    sprite: SPRITE_NAMES
+                        .sprite_name(sprite_render.sprite_number)
+                        .unwrap_or_default()
+                        .to_string(),
+                    position: (transform.translation().x, transform.translation().y),
+                    rotation: transform.rotation().euler_angles().2,
+                    velocity: (asteroid.velocity.x, asteroid.velocity.y),
+                    angular_velocity: asteroid.angular_velocity,
+                    width: asteroid.width,
+                    height: asteroid.height,
+                    size: asteroid.size,
+                    hit_points: asteroid.hit_points,
+                    is_fragment: asteroid.is_fragment,
+                    is_destroyed: asteroid.is_destroyed,
+                })
+                .collect(),
+            lasers: (&lasers, &transforms, !&inactives)
+                .join()
+                .map(|(laser, transform, _)| SavedLaser {
+                    owner: laser.owner,
+                    position: (transform.translation().x, transform.translation().y),
+                })
+                .collect(),
+        }
+    }
+
+    /// Puts the saved game into a world that has just been set up for a new game
+    ///
+    /// The ships are moved to where they were, the asteroids set up for a new game
+    /// are swapped for the saved ones, the lasers are taken from the laser pool,
+    /// and the play state, statistics, clock and random number generator are replaced.
+    /// The lives, damage overlays, HUD and background catch up with the play state by themselves.
+    pub fn restore(&self, world: &mut World) {
+        {
+            let mut ships = world.write_storage::<Ship>();
+            let players = world.read_storage::<Player>();
+            let mut transforms = world.write_storage::<Transform>();
+            for (ship, player, transform) in (&mut ships, &players, &mut transforms).join() {
+                if let Some(saved) = self.ships.iter().find(|saved| saved.player == player.index) {
+                    transform.set_translation_x(saved.position.0);
+                    transform.set_translation_y(saved.position.1);
+                    ship.velocity = Vector2::new(saved.velocity.0, saved.velocity.1);
+                    ship.trigger_reset_timer = saved.trigger_reset_timer;
+                    ship.status = saved.status;
+                }
+            }
+        }
+
+        let new_asteroids: Vec<Entity> = {
+            let entities = world.entities();
+            let asteroids = world.read_storage::<Asteroid>();
+            (&*entities, &asteroids).join().map(|(entity, _)| entity).collect()
+        };
+        let _result = world.delete_entities(&new_asteroids);
+        let sprite_sheet = world.read_resource::<AsteroidResource>().sprite_sheet.clone();
+        for saved in &self.asteroids {
+            let mut local_transform = Transform::default();
+            local_transform.set_translation_xyz(saved.position.0, saved.position.1, 0.0);
+            local_transform.set_rotation_2d(saved.rotation);
+            let asteroid = Asteroid {
+                velocity: Vector2::new(saved.velocity.0, saved.velocity.1),
+                angular_velocity: saved.angular_velocity,
+                width: saved.width,
+                height: saved.height,
+                size: saved.size,
+                hit_points: saved.hit_points,
+                is_fragment: saved.is_fragment,
+                is_destroyed: saved.is_destroyed,
+            };
+            world
+                .create_entity()
+                .with(asteroid)
+                .with(local_transform)
+                .with(SpriteRender {
+                    sprite_sheet: sprite_sheet.clone(),
+                    sprite_number: sprite_number(&saved.sprite),
+                })
+                .build();
+        }
+
+        {
+            let entities = world.entities();
+            let lazy_update = world.read_resource::<LazyUpdate>();
+            let laser_resource = world.read_resource::<LaserResource>();
+            let mut laser_pool = world.write_resource::<EntityPool<Laser>>();
+            for saved in &self.lasers {
+                let laser_entity = laser_pool.acquire(&entities, &lazy_update);
+                let mut local_transform = Transform::default();
+                local_transform.set_translation_xyz(saved.position.0, saved.position.1, 0.0);
+                lazy_update.insert(
+                    laser_entity,
+                    Laser {
+                        owner: saved.owner,
+                        ..laser_resource.component.clone()
+                    },
+                );
+                lazy_update.insert(laser_entity, laser_resource.sprite_render.clone());
+                lazy_update.insert(laser_entity, local_transform);
+            }
+        }
+        world.maintain();
+
+        let stream_events = world.read_resource::<Telemetry>().stream_events;
+        world.insert(Telemetry {
+            stream_events,
+            ..self.telemetry.clone()
+        });
+        world.insert(self.play_state.clone());
+        world.insert(self.clock);
+        world.insert(GameRandom::resume(self.seed, self.resume_seed));
+    }
+
+    /// Loads a saved game, checking that it can be resumed
+    ///
+    /// Saves in another file format version, for another number of players,
+    /// or with a ship that's no longer in the roster are rejected.
+    /// A game saved with a different game configuration is still resumed,
+    /// but with a warning, since it won't play out quite as it would have.
+    pub fn load_saved_game(path: &Path) -> Result<SavedGame, String> {
+        let saved_game = match SavedGame::load(path) {
+            Ok(saved_game) => saved_game,
+            Err(error) => {
+                // a save in another version may not read as this version's,
+                // so see if its version is to blame
+                return Err(match SaveHeader::load(path) {
+                    Ok(header) if header.version != SAVE_VERSION => {
+                        version_mismatch(path, header.version)
+                    }
+                    _ => format!("Can't read saved game {}: {}", path.display(), error),
+                });
+            }
+        };
+        if saved_game.version != SAVE_VERSION {
+            return Err(version_mismatch(path, saved_game.version));
+        }
+        if SHIP_ROSTER.ship_named(&saved_game.ship).is_none() {
+            return Err(format!(
+                "The saved game's ship, {}, isn't in the roster",
+                saved_game.ship
+            ));
+        }
+        if saved_game.play_state.players.len() != GAME_CONFIGURATION.player_count() {
+            return Err(format!(
+                "The saved game has {} players, but the game is set up for {}",
+                saved_game.play_state.players.len(),
+                GAME_CONFIGURATION.player_count()
+            ));
+        }
+        if saved_game.config_hash != configuration_hash() {
+            log::warn!(
+                "Saved game {} was saved with a different game configuration \
+                 or ship roster, so it may not carry on quite the same",
+                path.display()
+            );
+        }
+        Ok(saved_game)
+    }
+
+    /// Saves the game
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        if let Some(directory) = path.parent() {
+            std::fs::create_dir_all(directory)
+                .map_err(|error| format!("Can't create {}: {}", directory.display(), error))?;
+        }
+        self.write(path)
+            .map_err(|error| format!("Can't save the game {}: {}", path.display(), error))
+    }
+}
+
+/// The message rejecting a saved game in another file format version
+fn version_mismatch(path: &Path, version: u32) -> String {
+    let made_by = if version > SAVE_VERSION {
+        "a newer"
+    } else {
+        "an older"
+    };
+    format!(
+        "Saved game {} is version {} (made by {} version of the game), \
+         but this game resumes version {} saves",
+        path.display(),
+        version,
+        made_by,
+        SAVE_VERSION
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A game part way through, with the first ship in the roster and a laser in flight
+    fn test_saved_game() -> SavedGame {
+        let mut play_state = PlayState::new(GAME_CONFIGURATION.player_count(), 3);
+        play_state.players[0].score = 120;
+        SavedGame {
+            version: SAVE_VERSION,
+            config_hash: configuration_hash(),
+            ship: SHIP_ROSTER.ships[0].name.clone(),
+            seed: 42,
+            resume_seed: 7,
+            clock: GameClock::default(),
+            play_state,
+            telemetry: Telemetry::default(),
+            ships: vec![SavedShip {
+                player: 0,
+                position: (250.0, 40.0),
+                velocity: (12.5, 0.0),
+                trigger_reset_timer: 0.25,
+                status: ShipStatus::Active,
+            }],
+            asteroids: Vec::new(),
+            lasers: vec![SavedLaser {
+                owner: 0,
+                position: (250.0, 120.0),
+            }],
+        }
+    }
+
+    /// A path in the temporary directory for the test with the given name
+    fn test_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!(
+            "space_shooter_save_test_{}_{}.ron",
+            name,
+            std::process::id()
+        ))
+    }
+
+    #[test]
+    fn a_saved_game_loads_as_it_was_saved() {
+        let saved_game = test_saved_game();
+        let path = test_path("round_trip");
+        saved_game.save(&path).unwrap();
+        let loaded = SavedGame::load_saved_game(&path);
+        let _result = std::fs::remove_file(&path);
+        let loaded = loaded.unwrap();
+        assert_eq!(loaded.ship, saved_game.ship);
+        assert_eq!((loaded.seed, loaded.resume_seed), (42, 7));
+        assert_eq!(loaded.play_state, saved_game.play_state);
+        assert_eq!(loaded.ships, saved_game.ships);
+        assert_eq!(loaded.lasers, saved_game.lasers);
+    }
+
+    #[test]
+    fn saves_in_another_version_are_rejected() {
+        let path = test_path("version");
+        let old_game = SavedGame {
+            version: 0,
+            ..test_saved_game()
+        };
+        old_game.save(&path).unwrap();
+        let old_game_result = SavedGame::load_saved_game(&path);
+        // a save that doesn't read as this version's is still recognised by its version
+        std::fs::write(&path, "(version: 0)").unwrap();
+        let old_header_result = SavedGame::load_saved_game(&path);
+        let _result = std::fs::remove_file(&path);
+        assert_eq!(old_game_result.unwrap_err(), version_mismatch(&path, 0));
+        assert_eq!(old_header_result.unwrap_err(), version_mismatch(&path, 0));
+        assert!(version_mismatch(&path, 0).contains("an older version"));
+    }
+
+    #[test]
+    fn unreadable_saves_are_rejected() {
+        let path = test_path("unreadable");
+        std::fs::write(&path, "not a saved game").unwrap();
+        let garbage_result = SavedGame::load_saved_game(&path);
+        let _result = std::fs::remove_file(&path);
+        let missing_result = SavedGame::load_saved_game(&path);
+        let garbage_error = garbage_result.unwrap_err();
+        assert!(garbage_error.starts_with("Can't read saved game"), "{}", garbage_error);
+        let missing_error = missing_result.unwrap_err();
+        assert!(missing_error.starts_with("Can't read saved game"), "{}", missing_error);
+    }
+}
@@ -0,0 +1,302 @@
+//! The named sprites of our sprite sheet
+//!
+//! Kenney's sprite sheet comes with a TexturePacker atlas ("Spritesheet/sheet.xml" in assets)
+//! listing every sprite in the sheet by name, with its place in the image:
+//!
+//! ```xml
+//! <TextureAtlas imagePath="sheet.png">
+//!     <SubTexture name="laserRed01.png" x="858" y="230" width="9" height="54"/>
+//!     ...
+//! </TextureAtlas>
+//! ```
+//!
+//! We read the atlas directly into a [sprite atlas](struct.SpriteAtlas.html),
+//! which builds the sprite sheet, numbering the sprites in the order the atlas lists them.
+//! Since those numbers change whenever the atlas does, the [prefabs](struct.Prefabs.html),
+//! the [ship roster](struct.ShipRoster.html) and the [backgrounds](struct.Backgrounds.html)
+//! refer to sprites by name instead, and look the numbers up in the [sprite names](struct.SpriteNames.html).
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use amethyst::assets::Handle;
+use amethyst::renderer::{Sprite, SpriteSheet, Texture};
+use lazy_static::lazy_static;
+
+/// One sprite in a TexturePacker atlas
+#[derive(Clone, Debug, PartialEq)]
+pub struct SubTexture {
+    /// The sprite's name, e.g. "laserRed01.png"
+    pub name: String,
+    /// The pixel column of the sprite's left edge in the image
+    pub x: u32,
+    /// The pixel row of the sprite's top edge in the image
+    pub y: u32,
+    /// The width of the sprite, in pixels
+    pub width: u32,
+    /// The height of the sprite, in pixels
+    pub height: u32,
+}
+
+/// A TexturePacker atlas: an image, and the named sprites within it
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SpriteAtlas {
+    /// The path of the image, relative to the atlas
+    pub image_path: String,
+    /// The width of the image, in pixels
+    pub image_width: u32,
+    /// The height of the image, in pixels
+    pub image_height: u32,
+    /// The sprites, in the order the atlas lists them
+    pub sub_textures: Vec<SubTexture>,
+}
+
+impl SpriteAtlas {
+    /// Reads an atlas file, and the size of the PNG image it refers to
+    pub fn load(atlas_path: &Path) -> Result<SpriteAtlas, String> {
+        let xml = fs::read_to_string(atlas_path)
+            .map_err(|error| format!("Couldn't read {}: {}", atlas_path.display(), error))?;
+        let mut atlas = SpriteAtlas::parse(&xml)
+            .map_err(|error| format!("Couldn't read {}: {}", atlas_path.display(), error))?;
+        let image_path = atlas_path.with_file_name(&atlas.image_path);
+        let (image_width, image_height) = png_dimensions(&image_path)?;
+        atlas.image_width = image_width;
+        atlas.image_height = image_height;
+        Ok(atlas)
+    }
+
+    /// Reads the sprites out of the text of an atlas
+    ///
+    /// The atlas is simple enough that we pick out the attributes of each tag ourselves.
+    /// The size of the image isn't in the atlas, so it is left at zero.
+    pub fn parse(xml: &str) -> Result<SpriteAtlas, String> {
+        let mut atlas = SpriteAtlas::default();
+        for tag in xml.split('<').skip(1) {
+            let tag = tag.split('>').next().unwrap_or_default();
+            if tag.starts_with("TextureAtlas") {
+                atlas.image_path = attribute(tag, "imagePath")?.to_string();
+            } else if tag.starts_with("SubTexture") {
+                let number = |name: &str| {
+                    attribute(tag, name)?
+                        .parse::<u32>()
+                        .map_err(|error| format!("bad {} in <{}>: {}", name, tag, error))
+                };
+                atlas.sub_textures.push(SubTexture {
+                    name: attribute(tag, "name")?.to_string(),
+                    x: number("x")?,
+                    y: number("y")?,
+                    width: number("width")?,
+                    height: number("height")?,
+                });
+            }
+        }
+        if atlas.image_path.is_empty() {
+            return Err("no <TextureAtlas imagePath=...> tag".to_string());
+        }
+        Ok(atlas)
+    }
+
+    /// The sprite sheet of the atlas, drawing on the given texture (the atlas's image)
+    ///
+    /// Each sprite is displayed at its size in pixels.
+    pub fn sprite_sheet(&self, texture: Handle<Texture>) -> SpriteSheet {
+        SpriteSheet {
+            texture,
+            sprites: self
+                .sub_textures
+                .iter()
+                .map(|sub_texture| {
+                    Sprite::from_pixel_values(
+                        self.image_width,
+                        self.image_height,
+                        sub_texture.width,
+                        sub_texture.height,
+                        sub_texture.x,
+                        sub_texture.y,
+                        [0.0, 0.0],
+                        false,
+                        false,
+                    )
+                })
+                .collect(),
+        }
+    }
+
+    /// The sprite number of each sprite in the atlas's sprite sheet, by name
+    pub fn sprite_names(&self) -> SpriteNames {
+        SpriteNames {
+            names: self
+                .sub_textures
+                .iter()
+                .enumerate()
+                .map(|(sprite_number, sub_texture)| {
+                    (without_extension(&sub_texture.name).to_string(), sprite_number)
+                })
+                .collect(),
+        }
+    }
+}
+
+/// The sprite number of each named sprite in the sprite sheet
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SpriteNames {
+    /// The sprite numbers, by sprite name (without the ".png")
+    pub names: HashMap<String, usize>,
+}
+
+impl SpriteNames {
+    /// The sprite number of the sprite with the given name, if there is one
+    ///
+    /// The name can be given with or without its ".png", e.g. "laserRed01.png" or "laserRed01".
+    pub fn sprite_number(&self, name: &str) -> Option<usize> {
+        self.names.get(without_extension(name)).copied()
+    }
+
+    /// The name of the sprite with the given number, if there is one
+    pub fn sprite_name(&self, number: usize) -> Option<&str> {
+        self.names
+            .iter()
+            .find(|(_name, sprite_number)| **sprite_number == number)
+            .map(|(name, _sprite_number)| name.as_str())
+    }
+}
+
+lazy_static! {
+    /// The [atlas](struct.SpriteAtlas.html) of our sprite sheet.
+    ///
+    /// The atlas is automatically loaded on startup
+    /// from the file "Spritesheet/sheet.xml" in assets.
+    pub static ref SPRITE_ATLAS: SpriteAtlas = {
+        let atlas_path = format!(
+            "{}/assets/Spritesheet/sheet.xml",
+            env!("CARGO_MANIFEST_DIR")
+        );
+        SpriteAtlas::load(Path::new(&atlas_path)).unwrap()
+    };
+
+    /// The actual [sprite names](struct.SpriteNames.html), from the [sprite atlas](static.SPRITE_ATLAS.html).
+    pub static ref SPRITE_NAMES: SpriteNames = SPRITE_ATLAS.sprite_names();
+}
+
+/// The sprite number of the sprite with the given name
+///
+/// The names are all checked when the game starts (see [unknown_sprite_names](fn.unknown_sprite_names.html)),
+/// so an unknown name can only turn up here if that check was skipped;
+/// it gets the first sprite in the sheet.
+pub fn sprite_number(name: &str) -> usize {
+    SPRITE_NAMES.sprite_number(name).unwrap_or_default()
+}
+
+/// The value of the named attribute in the text of a tag, e.g. `x="858"`
+fn attribute<'a>(tag: &'a str, name: &str) -> Result<&'a str, String> {
+    let start = format!(" {}=\"", name);
+    tag.find(&start)
+        .map(|position| &tag[position + start.len()..])
+        .and_then(|rest| rest.split('"').next())
+        .ok_or_else(|| format!("no {} in <{}>", name, tag))
+}
+
+/// The name of a sprite without its ".png"
+fn without_extension(name: &str) -> &str {
+    name.trim_end_matches(".png")
+}
+
+/// Reads the width and height of a PNG image from its header
+///
+/// They are the first two values in the image's first chunk (IHDR),
+/// just after the 8-byte PNG signature and the chunk's length and type.
+fn png_dimensions(image_path: &Path) -> Result<(u32, u32), String> {
+    let bytes = fs::read(image_path)
+        .map_err(|error| format!("Couldn't read {}: {}", image_path.display(), error))?;
+    if bytes.len() < 24 || &bytes[12..16] != b"IHDR" {
+        return Err(format!("{} isn't a PNG image", image_path.display()));
+    }
+    let read_u32 = |start: usize| {
+        u32::from_be_bytes([bytes[start], bytes[start + 1], bytes[start + 2], bytes[start + 3]])
+    };
+    Ok((read_u32(16), read_u32(20)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ATLAS: &str = r#"<TextureAtlas imagePath="sheet.png">
+    <SubTexture name="laserRed01.png" x="858" y="230" width="9" height="54"/>
+    <SubTexture name="meteorBrown_big1.png" x="651" y="447" width="101" height="84"/>
+</TextureAtlas>"#;
+
+    #[test]
+    fn a_minimal_atlas_is_read_in_order() {
+        let atlas = SpriteAtlas::parse(ATLAS).unwrap();
+        assert_eq!(atlas.image_path, "sheet.png");
+        assert_eq!((atlas.image_width, atlas.image_height), (0, 0));
+        assert_eq!(
+            atlas.sub_textures[0],
+            SubTexture {
+                name: "laserRed01.png".to_string(),
+                x: 858,
+                y: 230,
+                width: 9,
+                height: 54,
+            }
+        );
+        let sprite_names = atlas.sprite_names();
+        assert_eq!(sprite_names.sprite_number("meteorBrown_big1"), Some(1));
+        assert_eq!(sprite_names.sprite_number("laserRed01.png"), Some(0));
+        assert_eq!(sprite_names.sprite_name(1), Some("meteorBrown_big1"));
+        assert_eq!(sprite_names.sprite_number("laserBlue01"), None);
+    }
+
+    #[test]
+    fn a_sprite_without_a_height_is_an_error() {
+        let xml = r#"<TextureAtlas imagePath="sheet.png">
+    <SubTexture name="laserRed01.png" x="858" y="230" width="9"/>
+</TextureAtlas>"#;
+        let error = SpriteAtlas::parse(xml).unwrap_err();
+        assert!(error.starts_with("no height in <SubTexture"), "{}", error);
+    }
+
+    #[test]
+    fn a_sprite_with_a_non_numeric_position_is_an_error() {
+        let xml = r#"<TextureAtlas imagePath="sheet.png">
+    <SubTexture name="laserRed01.png" x="left" y="230" width="9" height="54"/>
+</TextureAtlas>"#;
+        let error = SpriteAtlas::parse(xml).unwrap_err();
+        assert!(error.starts_with("bad x in <SubTexture"), "{}", error);
+    }
+
+    #[test]
+    fn an_atlas_without_an_image_is_an_error() {
+        let xml = r#"<SubTexture name="laserRed01.png" x="858" y="230" width="9" height="54"/>"#;
+        assert_eq!(
+            SpriteAtlas::parse(xml),
+            Err("no <TextureAtlas imagePath=...> tag".to_string())
+        );
+    }
+
+    #[test]
+    fn the_size_of_a_png_image_is_read_from_its_header() {
+        let path = std::env::temp_dir().join(format!(
+            "space_shooter_png_test_{}.png",
+            std::process::id()
+        ));
+        let mut header = b"\x89PNG\r\n\x1a\n\0\0\0\x0dIHDR".to_vec();
+        header.extend_from_slice(&1024u32.to_be_bytes());
+        header.extend_from_slice(&512u32.to_be_bytes());
+        fs::write(&path, &header).unwrap();
+        let dimensions = png_dimensions(&path);
+        fs::write(&path, b"GIF89a").unwrap();
+        let not_a_png = png_dimensions(&path);
+        let _result = fs::remove_file(&path);
+        assert_eq!(dimensions, Ok((1024, 512)));
+        assert!(not_a_png.is_err());
+    }
+
+    #[test]
+    fn the_shipped_atlas_has_a_sized_image() {
+        assert!(!SPRITE_ATLAS.sub_textures.is_empty());
+        assert!(SPRITE_ATLAS.image_width > 0 && SPRITE_ATLAS.image_height > 0);
+    }
+}
@@ -4,8 +4,14 @@ use amethyst::winit::VirtualKeyCode;
 
 use crate::components::register_components;
 use crate::entities::initialise_entities;
+use crate::replay::{Session, SessionMode};
 use crate::resources::add_resources;
-use crate::resources::PlayState;
+use crate::resources::{
+    DebugOverlay, GameClock, GamePhase, HighScore, Lockstep, PlayState, Telemetry,
+};
+use crate::roster::ShipDefinition;
+use crate::save_game::SavedGame;
+use crate::telemetry::{append_json_lines, sessions_path};
 
 /// The rules on what to do at each point of the game
 ///
@@ -15,8 +21,59 @@ use crate::resources::PlayState;
 /// 2. Handling the Escape key to stop the game
 /// 3. Monitoring a resource to determine the end of a game
 /// (and passing information from the system to the game state through a resource).
+/// 4. Switching the gameplay systems on and off through a resource
+/// (the [GamePhase](resources/enum.GamePhase.html)).
+/// 5. Saving the recording of the game (see [replays](struct.Replay.html)), any new high score,
+/// and the game's [session record](struct.SessionRecord.html), when it stops.
+/// 6. Showing or hiding the [debug overlay](resources/struct.DebugOverlay.html) when F3 is pressed.
+/// 7. Suspending the game when F5 is pressed, saving it to carry on later
+/// (see [saved games](struct.SavedGame.html)), and resuming a suspended game on start.
+/// 8. Stopping a [network game](network/index.html) when it can't go on
+/// (another player has left, or the copies of the game no longer agree),
+/// and telling the other players when this one quits.
 #[derive(Default)]
-pub struct GameState;
+pub struct GameState {
+    /// The saved game to carry on from, if the game is being resumed
+    resume: Option<SavedGame>,
+    /// Whether or not the game has been suspended, to be finished later
+    is_suspended: bool,
+}
+
+impl GameState {
+    /// The state of a game that carries on from the given saved game
+    pub fn resuming(saved_game: SavedGame) -> GameState {
+        GameState {
+            resume: Some(saved_game),
+            is_suspended: false,
+        }
+    }
+
+    /// Saves the game to carry on later, and stops it
+    ///
+    /// A replay can't be suspended, since it only repeats a game that was already played,
+    /// and neither can a network game, since the other players would have to resume it too.
+    /// If the game can't be saved, it carries on.
+    fn suspend(&mut self, world: &mut World) -> SimpleTrans {
+        let ship_name = {
+            let session = world.read_resource::<Session>();
+            if session.mode == SessionMode::Replaying || session.mode == SessionMode::Networked {
+                return Trans::None;
+            }
+            session.replay.ship.clone()
+        };
+        let saved_game = SavedGame::capture(world, &ship_name);
+        match saved_game.save(&SavedGame::path()) {
+            Ok(()) => {
+                self.is_suspended = true;
+                Trans::Quit
+            }
+            Err(message) => {
+                log::warn!("{}", message);
+                Trans::None
+            }
+        }
+    }
+}
 
 impl SimpleState for GameState {
     fn on_start(&mut self, state_data: StateData<'_, GameData<'_, '_>>) {
@@ -24,26 +81,85 @@ impl SimpleState for GameState {
         register_components(world);
         add_resources(world);
         initialise_entities(world);
+        if let Some(saved_game) = self.resume.take() {
+            saved_game.restore(world);
+        }
+
+        // note down what is being played, in case we're recording it
+        let ship_name = world.read_resource::<ShipDefinition>().name.clone();
+        let player_count = world.read_resource::<PlayState>().players.len();
+        {
+            let mut session = world.write_resource::<Session>();
+            session.replay.ship = ship_name;
+            session.replay.player_count = player_count;
+        }
+        world.insert(GamePhase::Playing);
+    }
+
+    fn on_stop(&mut self, state_data: StateData<'_, GameData<'_, '_>>) {
+        let world = state_data.world;
+        world.insert(GamePhase::Menu);
+        world.read_resource::<Lockstep>().leave();
+        let session = world.read_resource::<Session>();
+        if let Err(message) = session.save_recording() {
+            log::error!("{}", message);
+        }
+        // a replay only repeats a game that was already played,
+        // so it can't set a high score, and isn't recorded again
+        if !session.keeps_records() {
+            return;
+        }
+        let play_state = world.read_resource::<PlayState>();
+        let best_score = play_state.best_score();
+        if best_score > world.read_resource::<HighScore>().score {
+            if let Err(message) = HighScore::save(best_score) {
+                log::error!("{}", message);
+            }
+        }
+        // a suspended game's session record is written once the game is finished
+        if self.is_suspended {
+            return;
+        }
+        let session_record = world.read_resource::<Telemetry>().session_record(
+            &play_state,
+            &world.read_resource::<GameClock>(),
+            &session.replay.ship,
+        );
+        if let Err(message) = append_json_lines(&sessions_path(), &[session_record]) {
+            log::error!("{}", message);
+        }
     }
 
     fn handle_event(
         &mut self,
-        _: StateData<'_, GameData<'_, '_>>,
+        state_data: StateData<'_, GameData<'_, '_>>,
         event: StateEvent,
     ) -> SimpleTrans {
         if let StateEvent::Window(event) = event {
             if is_close_requested(&event) || is_key_down(&event, VirtualKeyCode::Escape) {
                 return Trans::Quit;
             }
+            if is_key_down(&event, VirtualKeyCode::F3) {
+                let mut debug_overlay = state_data.world.write_resource::<DebugOverlay>();
+                debug_overlay.visible = !debug_overlay.visible;
+            }
+            if is_key_down(&event, VirtualKeyCode::F5) {
+                return self.suspend(state_data.world);
+            }
         }
         Trans::None
     }
 
-    // Stop the game if the ship runs out of lives
+    // Stop the game once every player runs out of lives, the replay runs out,
+    // or the network game can't go on
     fn fixed_update(&mut self, state_data: StateData<'_, GameData<'_, '_>>) -> SimpleTrans {
         let world = state_data.world;
+        if let Some(failure) = &world.read_resource::<Lockstep>().failure {
+            log::error!("{}", failure);
+            return Trans::Quit;
+        }
         let play_state = world.read_resource::<PlayState>();
-        if play_state.lives == 0 {
+        if play_state.is_game_over() || world.read_resource::<Session>().is_finished() {
             Trans::Quit
         } else {
             Trans::None
@@ -56,3 +172,4 @@ impl SimpleState for GameState {
         Trans::None
     }
 }
+
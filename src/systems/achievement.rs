@@ -0,0 +1,96 @@
+use amethyst::ecs::prelude::{Join, Read, ReadStorage, System, Write, WriteStorage};
+use amethyst::ui::UiText;
+
+use crate::achievements::ACHIEVEMENTS;
+use crate::components::HudItem;
+use crate::replay::Session;
+use crate::resources::{
+    AchievementToasts, GameClock, Localisation, PlayState, Telemetry, UnlockedAchievements,
+};
+
+/// Unlocks the achievements whose conditions are met, and announces them with a pop-up
+///
+/// Every frame, this system checks each achievement that hasn't been unlocked yet
+/// against the game's [statistics](../resources/struct.Telemetry.html) and play state.
+/// Newly unlocked achievements are saved straight away (unless saving has already failed
+/// during this game), and queued up to be announced, one at a time, on the HUD.
+///
+/// A replay only repeats a game that was already played, so it can't unlock anything.
+pub struct AchievementSystem;
+
+impl<'s> System<'s> for AchievementSystem {
+    /// The data for each pass of the achievement system
+    /// We need:
+    ///
+    /// * **HudItems**:      read access to the HUD items so we can find the achievement pop-up
+    /// * **UiTexts**:       write access to the UI texts so we can show the pop-up
+    /// * **Telemetry**:     read access to the game's statistics, to check against the conditions
+    /// * **PlayState**:     read access to the play state, to check the scores and the wave
+    /// * **GameClock**:     read access to the game clock, to check how long the players have survived
+    ///                        and to time the pop-ups
+    /// * **Session**:       read access to the session so we know whether we're replaying a game
+    /// * **Localisation**:  read access to the chosen language, to write the pop-up in
+    /// * **UnlockedAchievements**: write access to the unlocked achievements so we can add to them
+    /// * **AchievementToasts**:    write access to the pop-ups so we can queue and time them
+    type SystemData = (
+        ReadStorage<'s, HudItem>,
+        WriteStorage<'s, UiText>,
+        Read<'s, Telemetry>,
+        Read<'s, PlayState>,
+        Read<'s, GameClock>,
+        Read<'s, Session>,
+        Read<'s, Localisation>,
+        Write<'s, UnlockedAchievements>,
+        Write<'s, AchievementToasts>,
+    );
+
+    /// Runs a pass of the system on our selected components
+    ///
+    /// This function is given a list of the components described under [SystemData](#associatedtype.SystemData).
+    fn run(
+        &mut self,
+        (hud_items, mut ui_texts, telemetry, play_state, game_clock, session, localisation, mut unlocked, mut toasts): Self::SystemData,
+    ) {
+        if session.keeps_records() {
+            let newly_unlocked: Vec<_> = ACHIEVEMENTS
+                .achievements
+                .iter()
+                .filter(|achievement| {
+                    !unlocked.is_unlocked(&achievement.id)
+                        && achievement.condition.is_met(&telemetry, &play_state, &game_clock)
+                })
+                .collect();
+            if !newly_unlocked.is_empty() {
+                for achievement in newly_unlocked {
+                    unlocked.ids.push(achievement.id.clone());
+                    toasts.queue.push_back(achievement.name.clone());
+                }
+                if !unlocked.save_failed {
+                    if let Err(message) = unlocked.save() {
+                        log::warn!("{}", message);
+                        unlocked.save_failed = true;
+                    }
+                }
+            }
+        }
+
+        // show each toast for its time, then move on to the next one
+        toasts.time_left -= game_clock.delta_seconds;
+        if toasts.time_left <= 0.0 {
+            toasts.showing = toasts.queue.pop_front();
+            toasts.time_left = ACHIEVEMENTS.toast_duration;
+        }
+        let text = toasts
+            .showing
+            .as_ref()
+            .map(|name| {
+                localisation.format("achievement-unlocked", &[("name", localisation.text(name))])
+            })
+            .unwrap_or_default();
+        for (hud_item, ui_text) in (&hud_items, &mut ui_texts).join() {
+            if *hud_item == HudItem::AchievementToast && ui_text.text != text {
+                ui_text.text = text.clone();
+            }
+        }
+    }
+}
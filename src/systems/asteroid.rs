@@ -1,29 +1,45 @@
-use amethyst::core::timing::Time;
 use amethyst::core::transform::Transform;
-use amethyst::ecs::prelude::{Join, Read, System, WriteStorage};
-use rand::thread_rng;
+use amethyst::ecs::prelude::{
+    Entities, Join, Read, System, Write, WriteStorage,
+};
 
 use crate::components::Asteroid;
-use crate::entities::locate_asteroid;
+use crate::config::{WallBehaviour, GAME_CONFIGURATION};
+use crate::entities::{asteroid_hit_points, launch_asteroid, locate_asteroid};
+use crate::resources::{Arena, GameClock, GameRandom};
 
-/// Moves the asteroid, either down by its velocity
-/// or to a new random location if it was marked for repositioning.
+/// Moves and spins the asteroid by its velocities,
+/// or moves it to a new random location if it was marked for repositioning.
+///
+/// Asteroids that drift into the side walls either wrap around to the other side
+/// or bounce off the wall, depending on the game configuration.
+///
+/// Asteroid fragments are not repositioned: they are deleted instead.
 pub struct AsteroidSystem;
 
 impl<'s> System<'s> for AsteroidSystem {
     /// The data for each pass of the asteroid system
     /// We need:
     ///
+    /// * **Entities**:          the list of entities so we can delete the asteroid fragments
+    ///                            once they are destroyed or out of bounds
     /// * **Asteroids**:         write access to the list of asteroid components
     ///                            so we can turn off the "destroyed" flag once the asteroid is relocated.
     /// * **Transforms**:        write access to the list of positions
     ///                            so we can update the asteroid's position
-    /// * **Time**:              read access to the time resource so we can know how much time
+    /// * **GameClock**:         read access to the game clock so we can know how much game time
     ///                            has elapsed since we last ran this system
+    /// * **Arena**:             read access to the arena so we know
+    ///                            where the walls and the bottom of the arena are
+    /// * **GameRandom**:        write access to the game's random number generator
+    ///                            so we can pick the relocated asteroid's new position
     type SystemData = (
+        Entities<'s>,
         WriteStorage<'s, Asteroid>,
         WriteStorage<'s, Transform>,
-        Read<'s, Time>,
+        Read<'s, GameClock>,
+        Read<'s, Arena>,
+        Option<Write<'s, GameRandom>>,
     );
 
     /// Runs a pass of the system on our selected components
@@ -31,30 +47,74 @@ impl<'s> System<'s> for AsteroidSystem {
     /// This function is given a list of the components described under [SystemData](#associatedtype.SystemData).
     ///
     /// It selects all the asteroids with their prospective locations from the list.
-    /// For each asteroid, it updates the asteroid's position.
+    /// For each asteroid, it updates the asteroid's position and rotation,
+    /// then wraps it around or bounces it off the side walls.
     ///
     /// If the asteroid has fallen belows the screen or is marked for respawn/relocation,
     /// It calls the [locate_asteroid](../entities/fn.locate_asteroid.html) function
     /// to determine a new position for the asteroid, and moves it there.
-    fn run(&mut self, (mut asteroids, mut transforms, time): Self::SystemData) {
-        for (asteroid, transform) in (&mut asteroids, &mut transforms).join() {
-            let (screen_width, screen_height) = { (1024., 1024.) };
-            // move the asteroid by its velocity
-            transform.prepend_translation_y(-asteroid.velocity * time.delta_seconds());
+    /// The relocated asteroid gets back its full hit points, and is launched
+    /// with a new velocity and spin.
+    ///
+    /// If the asteroid is a fragment, it asks the entity list to delete it instead.
+    fn run(
+        &mut self,
+        (entities, mut asteroids, mut transforms, game_clock, arena, game_random): Self::SystemData,
+    ) {
+        // the screens before the game dispatch the game's systems too
+        let mut game_random = match game_random {
+            Some(game_random) => game_random,
+            None => return,
+        };
+        let (arena_width, arena_height) = (arena.width, arena.height);
+        for (asteroid_entity, asteroid, transform) in
+            (&*entities, &mut asteroids, &mut transforms).join()
+        {
+            // move and spin the asteroid by its velocities
+            transform.prepend_translation_x(asteroid.velocity[0] * game_clock.delta_seconds);
+            transform.prepend_translation_y(asteroid.velocity[1] * game_clock.delta_seconds);
+            transform.rotate_2d(asteroid.angular_velocity * game_clock.delta_seconds);
+
+            // deal with the asteroid drifting into the side walls
+            let half_width = asteroid.width / 2.;
+            let position_x = transform.translation()[0];
+            match GAME_CONFIGURATION.asteroid_wall_behaviour {
+                WallBehaviour::Wrap => {
+                    // once the asteroid is completely off one side, bring it back on the other
+                    if position_x < -half_width {
+                        transform.set_translation_x(position_x + arena_width + asteroid.width);
+                    } else if position_x > arena_width + half_width {
+                        transform.set_translation_x(position_x - arena_width - asteroid.width);
+                    }
+                }
+                WallBehaviour::Bounce => {
+                    if position_x < half_width {
+                        transform.set_translation_x(half_width);
+                        asteroid.velocity[0] = asteroid.velocity[0].abs(); // bounce off the left wall
+                    } else if position_x > arena_width - half_width {
+                        transform.set_translation_x(arena_width - half_width);
+                        asteroid.velocity[0] = -asteroid.velocity[0].abs(); // bounce off the right wall
+                    }
+                }
+            }
 
             // If the asteroid falls below the bottom of the screen,
             // or if it got destroyed in another system,
             // "respawn" it somewhere way up
             // in an ECS, it's more efficient to re-use entities than to
             // destroy and re-create them.
-            if asteroid.is_destroyed
-                || transform.translation()[1] < (0. - (screen_height + asteroid.height)).into()
-            {
-                let mut rng = thread_rng();
-                let local_transform =
-                    locate_asteroid(asteroid, screen_width, screen_height, &mut rng);
+            if asteroid.is_destroyed || transform.translation()[1] < -asteroid.height {
+                if asteroid.is_fragment {
+                    // fragments only last until they are destroyed or fall off the screen
+                    let _result = entities.delete(asteroid_entity);
+                    continue;
+                }
+                let rng = &mut game_random.rng;
+                launch_asteroid(asteroid, rng);
+                let local_transform = locate_asteroid(asteroid, arena_width, arena_height, rng);
                 transform.set_translation_x(local_transform.translation()[0]);
                 transform.set_translation_y(local_transform.translation()[1]);
+                asteroid.hit_points = asteroid_hit_points(asteroid.size);
                 asteroid.is_destroyed = false;
             }
         }
@@ -0,0 +1,96 @@
+use amethyst::core::transform::Transform;
+use amethyst::ecs::prelude::{
+    Entities, Join, LazyUpdate, Read, ReadExpect, System, Write, WriteStorage,
+};
+
+use crate::backgrounds::BACKGROUNDS;
+use crate::components::Scrolling;
+use crate::entities::background_pieces;
+use crate::resources::{Arena, BackgroundResource, GameClock, GameRandom, PlayState};
+
+/// Scrolls the background, and changes it when the players reach another level
+///
+/// Each piece of the background scrolls down the screen at the speed of its layer.
+/// Once a piece has scrolled off the bottom of the screen, it jumps back up
+/// above the top, so the layers scroll on forever without any gaps.
+pub struct BackgroundSystem;
+
+impl<'s> System<'s> for BackgroundSystem {
+    /// The data for each pass of the background system
+    /// We need:
+    ///
+    /// * **Entities**:            the list of entities so we can delete the old background
+    /// * **Scrollings**:          write access to the pieces of the background
+    /// * **Transforms**:          write access to the positions of the pieces of the background
+    ///                              so we can scroll them
+    /// * **GameClock**:           read access to the game clock so we can know how much game time
+    ///                              has elapsed since we last ran this system
+    /// * **PlayState**:           read access to the play state so we know which level the players are on
+    /// * **BackgroundResource**:  write access to the background resources we set up in
+    ///                              <a href="../entities/background/fn.initialise_background.html">initialise_background</a>
+    /// * **GameRandom**:          write access to the game's random number generator,
+    ///                              so we can scatter the stars of a new background
+    /// * **Arena**:               read access to the arena so the new background fills it
+    /// * **Lazy Update**:         a mechanism that queues changes to the world
+    ///                              until after all the systems have run. We use this
+    ///                              to create the pieces of a new background.
+    type SystemData = (
+        Entities<'s>,
+        WriteStorage<'s, Scrolling>,
+        WriteStorage<'s, Transform>,
+        Read<'s, GameClock>,
+        Read<'s, PlayState>,
+        Option<Write<'s, BackgroundResource>>,
+        Option<Write<'s, GameRandom>>,
+        Read<'s, Arena>,
+        ReadExpect<'s, LazyUpdate>,
+    );
+
+    /// Runs a pass of the system on our selected components
+    ///
+    /// This function is given a list of the components described under [SystemData](#associatedtype.SystemData).
+    ///
+    /// If the players have moved on to another level, the function deletes the old background
+    /// and queues the pieces of the new level's background to be created.
+    /// Otherwise, it scrolls each piece of the background down the screen,
+    /// wrapping it back to the top once it has gone off the bottom.
+    fn run(
+        &mut self,
+        (entities, mut scrollings, mut transforms, game_clock, play_state, background_resource, game_random, arena, lazy_update): Self::SystemData,
+    ) {
+        // the screens before the game dispatch the game's systems too
+        let (mut background_resource, mut game_random) = match (background_resource, game_random) {
+            (Some(background_resource), Some(game_random)) => (background_resource, game_random),
+            _ => return,
+        };
+        if play_state.level != background_resource.level {
+            for (entity, _scrolling) in (&*entities, &scrollings).join() {
+                let _result = entities.delete(entity);
+            }
+            background_resource.level = play_state.level;
+            let pieces = background_pieces(
+                &background_resource,
+                BACKGROUNDS.for_level(play_state.level),
+                arena.width,
+                arena.height,
+                &mut game_random.rng,
+            );
+            for (sprite_render, local_transform, scrolling) in pieces {
+                lazy_update
+                    .create_entity(&entities)
+                    .with(sprite_render)
+                    .with(local_transform)
+                    .with(scrolling)
+                    .build();
+            }
+            return;
+        }
+
+        for (scrolling, transform) in (&mut scrollings, &mut transforms).join() {
+            transform.prepend_translation_y(-scrolling.speed * game_clock.delta_seconds);
+            if transform.translation()[1] < -scrolling.half_height {
+                transform.prepend_translation_y(scrolling.wrap_height);
+            }
+        }
+    }
+}
@@ -0,0 +1,84 @@
+use amethyst::ecs::prelude::{Join, ReadExpect, ReadStorage, System, Write, WriteStorage};
+use amethyst::renderer::camera::{Camera, Projection};
+use amethyst::ui::{UiText, UiTransform};
+use amethyst::window::ScreenDimensions;
+
+use crate::components::HudLayout;
+use crate::resources::Arena;
+
+/// Fits the arena into the window whenever the window changes size
+///
+/// The game is always played in the same [arena](../resources/struct.Arena.html),
+/// whatever the size or shape of the window.
+/// When the window changes size, this system scales the arena to fit it, keeping its shape,
+/// then:
+///
+/// * widens (or narrows) the camera's view to take in the whole window,
+///   with the arena in the middle. The parts of the view outside the arena
+///   are covered by the [letterbox](../entities/letterbox/index.html) bars;
+/// * moves and scales each item of the HUD, so it stays in the same place over the arena.
+pub struct CameraSystem;
+
+impl<'s> System<'s> for CameraSystem {
+    /// The data for each pass of the camera system
+    /// We need:
+    ///
+    /// * **Cameras**:           write access to the camera so we can change its view
+    /// * **HudLayouts**:        read access to the layout of each HUD item over the arena
+    /// * **UiTransforms**:      write access to the position of each HUD item on the UI layer
+    /// * **UiTexts**:           write access to the HUD's text so we can scale its font
+    /// * **Arena**:             write access to the arena so we can fit it to the window
+    /// * **ScreenDimensions**:  read access to the screen dimensions so we know the size of the window
+    type SystemData = (
+        WriteStorage<'s, Camera>,
+        ReadStorage<'s, HudLayout>,
+        WriteStorage<'s, UiTransform>,
+        WriteStorage<'s, UiText>,
+        Write<'s, Arena>,
+        ReadExpect<'s, ScreenDimensions>,
+    );
+
+    /// Runs a pass of the system on our selected components
+    ///
+    /// This function is given a list of the components described under [SystemData](#associatedtype.SystemData).
+    ///
+    /// It does nothing unless the window is a different size from the last time the arena was fitted.
+    /// Otherwise, it fits the arena to the window, sets the camera's projection to the size of the
+    /// window in arena units (centred on the arena, since the camera sits over the middle of it),
+    /// and lays the HUD items out again.
+    fn run(
+        &mut self,
+        (mut cameras, hud_layouts, mut ui_transforms, mut ui_texts, mut arena, screen_dimensions): Self::SystemData,
+    ) {
+        let (screen_width, screen_height) = (screen_dimensions.width(), screen_dimensions.height());
+        if arena.fits(screen_width, screen_height) {
+            return;
+        }
+        arena.fit(screen_width, screen_height);
+
+        let (view_width, view_height) = arena.view_size();
+        for camera in (&mut cameras).join() {
+            camera.set_projection(Projection::orthographic(
+                -view_width / 2.,
+                view_width / 2.,
+                -view_height / 2.,
+                view_height / 2.,
+                0.125,
+                2000.0,
+            ));
+        }
+
+        // the UI layer is in pixels, measured from the top-left corner of the window
+        for (layout, ui_transform) in (&hud_layouts, &mut ui_transforms).join() {
+            ui_transform.local_x = arena.margin_x + layout.x * arena.scale;
+            ui_transform.local_y = -arena.margin_y + layout.y * arena.scale;
+            ui_transform.width = layout.width * arena.scale;
+            ui_transform.height = layout.height * arena.scale;
+        }
+        for (layout, ui_text) in (&hud_layouts, &mut ui_texts).join() {
+            if let Some(font_size) = layout.font_size {
+                ui_text.font_size = font_size * arena.scale;
+            }
+        }
+    }
+}
@@ -0,0 +1,32 @@
+use amethyst::core::timing::Time;
+use amethyst::ecs::prelude::{Read, System, Write};
+
+use crate::replay::Session;
+use crate::resources::GameClock;
+
+/// Winds the [game clock](../resources/struct.GameClock.html) on by one frame
+///
+/// When the session has a fixed timestep (e.g. while recording or replaying),
+/// every frame moves the game on by exactly that much time.
+/// Otherwise the game keeps up with the real time between frames.
+pub struct ClockSystem;
+
+impl<'s> System<'s> for ClockSystem {
+    /// The data for each pass of the clock system
+    /// We need:
+    ///
+    /// * **Time**:      read access to the time resource so we can know how much real time
+    ///                    has elapsed since we last ran this system
+    /// * **Session**:   read access to the session so we know its fixed timestep, if any
+    /// * **GameClock**: write access to the game clock so we can wind it on
+    type SystemData = (Read<'s, Time>, Read<'s, Session>, Write<'s, GameClock>);
+
+    /// Runs a pass of the system on our selected resources
+    fn run(&mut self, (time, session, mut game_clock): Self::SystemData) {
+        game_clock.delta_seconds = session
+            .timestep()
+            .unwrap_or_else(|| time.delta_seconds());
+        game_clock.frame += 1;
+        game_clock.elapsed_seconds += game_clock.delta_seconds;
+    }
+}
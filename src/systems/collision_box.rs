@@ -0,0 +1,140 @@
+use amethyst::core::math::Vector2;
+use amethyst::core::transform::Transform;
+
+/// An oriented collision box around an entity
+///
+/// The box is centred on the entity's position (sprites are drawn centred on their transform),
+/// its size is the sprite's width and height multiplied by the transform's scale,
+/// and it is turned by the same angle as the transform's rotation.
+/// That way the collision boxes follow the size and spin of whatever is being drawn,
+/// rather than relying on hard-coded numbers.
+///
+/// This is a good pattern for sharing a small calculation between several
+/// systems without having to make it a component of its own.
+#[derive(Clone, Copy, Debug)]
+pub struct CollisionBox {
+    /// The centre of the box
+    pub centre: Vector2<f32>,
+    /// Half the width and half the height of the box
+    pub half_extents: Vector2<f32>,
+    /// The directions of the box's width and height, i.e. its local x and y axes
+    pub axes: [Vector2<f32>; 2],
+}
+
+impl CollisionBox {
+    /// Creates the collision box for a sprite of the given (unscaled) width and height
+    /// drawn with the given transform.
+    ///
+    /// Note the scale may be negative when a sprite is flipped, so we only use its size.
+    pub fn new(transform: &Transform, width: f32, height: f32) -> CollisionBox {
+        let (_, _, angle) = transform.rotation().euler_angles();
+        let (sin, cos) = angle.sin_cos();
+        CollisionBox {
+            centre: Vector2::new(transform.translation()[0], transform.translation()[1]),
+            half_extents: Vector2::new(
+                width * transform.scale()[0].abs() / 2.,
+                height * transform.scale()[1].abs() / 2.,
+            ),
+            axes: [Vector2::new(cos, sin), Vector2::new(-sin, cos)],
+        }
+    }
+
+    /// Whether or not this box overlaps the other box
+    ///
+    /// This uses the separating axis test: two boxes overlap
+    /// unless we can find one of their axes along which their shadows don't meet.
+    pub fn overlaps(&self, other: &CollisionBox) -> bool {
+        let offset = other.centre - self.centre;
+        self.axes
+            .iter()
+            .chain(other.axes.iter())
+            .all(|axis| offset.dot(axis).abs() <= self.radius_along(axis) + other.radius_along(axis))
+    }
+
+    /// The corners of the box, going round it
+    pub fn corners(&self) -> [Vector2<f32>; 4] {
+        let width = self.axes[0] * self.half_extents[0];
+        let height = self.axes[1] * self.half_extents[1];
+        [
+            self.centre - width - height,
+            self.centre + width - height,
+            self.centre + width + height,
+            self.centre - width + height,
+        ]
+    }
+
+    /// How far the box reaches from its centre along the given axis
+    fn radius_along(&self, axis: &Vector2<f32>) -> f32 {
+        self.half_extents[0] * self.axes[0].dot(axis).abs()
+            + self.half_extents[1] * self.axes[1].dot(axis).abs()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use amethyst::core::math::Vector3;
+    use std::f32::consts::FRAC_PI_4;
+
+    fn square(x: f32, y: f32, angle: f32, scale_x: f32) -> CollisionBox {
+        let mut transform = Transform::default();
+        transform.set_translation_xyz(x, y, 0.0);
+        transform.set_rotation_2d(angle);
+        transform.set_scale(Vector3::new(scale_x, 1.0, 1.0));
+        CollisionBox::new(&transform, 10.0, 10.0)
+    }
+
+    fn assert_near(actual: Vector2<f32>, expected: (f32, f32)) {
+        assert!(
+            (actual[0] - expected.0).abs() < 1e-4 && (actual[1] - expected.1).abs() < 1e-4,
+            "{:?} is not near {:?}",
+            actual,
+            expected
+        );
+    }
+
+    #[test]
+    fn turned_boxes_whose_bounds_overlap_need_not_touch() {
+        // both are diamonds reaching about 7.07 from their centres, so their bounding boxes overlap,
+        // but their facing sides are more than 6 apart
+        let first = square(0.0, 0.0, FRAC_PI_4, 1.0);
+        let second = square(12.0, 12.0, FRAC_PI_4, 1.0);
+        assert!(!first.overlaps(&second));
+        assert!(!second.overlaps(&first));
+    }
+
+    #[test]
+    fn turned_boxes_overlap() {
+        let first = square(0.0, 0.0, FRAC_PI_4, 1.0);
+        let second = square(8.0, 0.0, FRAC_PI_4, 1.0);
+        assert!(first.overlaps(&second));
+        assert!(second.overlaps(&first));
+        // a turned box against one that isn't
+        assert!(first.overlaps(&square(11.0, 0.0, 0.0, 1.0)));
+        assert!(!first.overlaps(&square(13.0, 0.0, 0.0, 1.0)));
+    }
+
+    #[test]
+    fn a_flipped_sprite_keeps_its_size() {
+        let flipped = square(0.0, 0.0, 0.0, -1.0);
+        assert_near(flipped.half_extents, (5.0, 5.0));
+        assert!(flipped.overlaps(&square(9.0, 0.0, 0.0, 1.0)));
+        assert!(!flipped.overlaps(&square(11.0, 0.0, 0.0, 1.0)));
+    }
+
+    #[test]
+    fn corners_go_round_the_box() {
+        let diagonal = 50.0_f32.sqrt();
+        let corners = square(0.0, 0.0, FRAC_PI_4, 1.0).corners();
+        assert_near(corners[0], (0.0, -diagonal));
+        assert_near(corners[1], (diagonal, 0.0));
+        assert_near(corners[2], (0.0, diagonal));
+        assert_near(corners[3], (-diagonal, 0.0));
+
+        let corners = square(20.0, 10.0, 0.0, -1.0).corners();
+        assert_near(corners[0], (15.0, 5.0));
+        assert_near(corners[1], (25.0, 5.0));
+        assert_near(corners[2], (25.0, 15.0));
+        assert_near(corners[3], (15.0, 15.0));
+    }
+}
@@ -0,0 +1,126 @@
+use amethyst::ecs::prelude::{Join, ReadExpect, ReadStorage, System, Write};
+use amethyst::input::{InputHandler, StringBindings};
+
+use crate::components::Player;
+use crate::config::GAME_CONFIGURATION;
+use crate::replay::{Session, SessionMode};
+use crate::resources::{Controls, PlayerControls};
+
+/// Works out what each player is doing with their controls this frame
+///
+/// This system is the only gameplay system that looks at the input handler.
+/// It fills in the [Controls](../resources/struct.Controls.html) resource
+/// that the other systems work from, so the controls can be:
+///
+/// * read live, from each player's keyboard and controller bindings;
+/// * read live and recorded into the session's replay; or
+/// * played back from a replay, in place of the input handler.
+///
+/// In a [network game](../network/index.html), the controls are filled in by the
+/// [LockstepSystem](struct.LockstepSystem.html) instead, and in an [agent](../agent/index.html)'s game
+/// by the agent, and this system leaves them alone.
+///
+/// Each player is steered with their own axis and fire bindings
+/// (e.g. `ship_p1`, `ship_vertical_p1` and `fire_p1`), so several players
+/// can share the keyboard.
+/// While a controller is plugged into the player's controller slot,
+/// its stick and buttons (e.g. `pad_ship_p1` and `pad_fire_p1`) work too.
+/// Controllers can be plugged in or pulled out at any time:
+/// the keyboard always works as a fallback.
+pub struct ControlSystem;
+
+impl<'s> System<'s> for ControlSystem {
+    /// The data for each pass of the control system
+    /// We need:
+    ///
+    /// * **Players**:       read access to the list of players
+    ///                        so we know each player's bindings
+    /// * **Input Handler**: read access to the input handler so we can
+    ///                        sample the position of the "joystick" and the fire "button"
+    /// * **Controls**:      write access to the controls so we can fill them in
+    /// * **Session**:       write access to the session so we can record
+    ///                        or play back the controls
+    type SystemData = (
+        ReadStorage<'s, Player>,
+        ReadExpect<'s, InputHandler<StringBindings>>,
+        Write<'s, Controls>,
+        Write<'s, Session>,
+    );
+
+    /// Runs a pass of the system on our selected components
+    ///
+    /// When replaying, the controls come from the next frame of the replay
+    /// (and everyone lets go of their controls once the replay runs out).
+    ///
+    /// Otherwise, the function reads each player's controls from the input handler,
+    /// then, when recording, adds them to the replay.
+    fn run(&mut self, (players, input, mut controls, mut session): Self::SystemData) {
+        if session.mode == SessionMode::Networked || session.mode == SessionMode::Agent {
+            return;
+        }
+        if session.mode == SessionMode::Replaying {
+            let frame = session.next_controls().map(<[PlayerControls]>::to_vec);
+            controls.players = frame.unwrap_or_default();
+            return;
+        }
+
+        for player in (&players).join() {
+            if controls.players.len() <= player.index {
+                controls
+                    .players
+                    .resize(player.index + 1, PlayerControls::default());
+            }
+            controls.players[player.index] = read_controls(&input, player);
+        }
+        if let SessionMode::Recording(_) = session.mode {
+            session.replay.record(&controls.players);
+        }
+    }
+}
+
+/// Reads the player's 'joystick' and fire button
+///
+/// If the player has a controller plugged in and is using it
+/// (the stick is off centre, or the fire button is down), we use its readings,
+/// with the stick shaped by the configured deadzone and response curve.
+/// Otherwise we fall back to the player's keyboard bindings.
+pub(crate) fn read_controls(
+    input: &InputHandler<StringBindings>,
+    player: &Player,
+) -> PlayerControls {
+    let is_pad_connected = input.is_controller_connected(player.controller_id);
+    let is_down = |action: &str| input.action_is_down(action).unwrap_or(false);
+    PlayerControls {
+        horizontal: read_axis(input, &player.move_axis, &player.pad_move_axis, is_pad_connected),
+        vertical: read_axis(
+            input,
+            &player.vertical_axis,
+            &player.pad_vertical_axis,
+            is_pad_connected,
+        ),
+        fire: is_down(&player.fire_action)
+            || (is_pad_connected && is_down(&player.pad_fire_action)),
+    }
+}
+
+/// Reads one axis of the player's 'joystick', from the controller if it's in use,
+/// or else from the keyboard
+fn read_axis(
+    input: &InputHandler<StringBindings>,
+    key_axis: &str,
+    pad_axis: &str,
+    is_pad_connected: bool,
+) -> f32 {
+    let pad_value = if is_pad_connected {
+        input
+            .axis_value(pad_axis)
+            .map_or(0.0, |value| GAME_CONFIGURATION.shape_stick(value))
+    } else {
+        0.0
+    };
+    if pad_value != 0.0 {
+        pad_value
+    } else {
+        input.axis_value(key_axis).unwrap_or(0.0)
+    }
+}
@@ -0,0 +1,76 @@
+use amethyst::core::transform::Parent;
+use amethyst::core::Hidden;
+use amethyst::ecs::prelude::{Entities, Join, Read, ReadStorage, System, WriteStorage};
+use amethyst::renderer::SpriteRender;
+
+use crate::components::{DamageOverlay, Player};
+use crate::resources::PlayState;
+
+/// Shows the damage each ship has taken, based on its player's play state
+///
+/// Like the [LivesSystem](struct.LivesSystem.html), this is a pattern of updating
+/// entities based on a game-wide resource: here, we swap the sprite of
+/// the damage overlay for a heavier one as the damage goes up,
+/// and hide the overlay altogether when the ship has been repaired.
+///
+/// The overlay is also hidden whenever the ship it belongs to is hidden
+/// (e.g. while the ship is waiting to respawn, or blinking).
+pub struct DamageSystem;
+
+impl<'s> System<'s> for DamageSystem {
+    /// The data for each pass of the damage system
+    /// We need:
+    ///
+    /// * **Entities**:        the list of entities so we can hide or show an overlay
+    /// * **DamageOverlays**:  read access to the list of damage overlays
+    ///                          so we know which damage sprites to use
+    /// * **Parents**:         read access to the list of parents
+    ///                          so we can find the ship each overlay belongs to
+    /// * **Players**:         read access to the list of players
+    ///                          so we know whose damage each ship shows
+    /// * **SpriteRenders**:   write access to the list of sprite renders
+    ///                          so we can change the overlay's sprite
+    /// * **Hiddens**:         write access to the list of hidden flags
+    ///                          so we can hide or show the overlay
+    /// * **PlayState**:       read access to the play state
+    ///                          so we can read the damage each ship has taken
+    type SystemData = (
+        Entities<'s>,
+        ReadStorage<'s, DamageOverlay>,
+        ReadStorage<'s, Parent>,
+        ReadStorage<'s, Player>,
+        WriteStorage<'s, SpriteRender>,
+        WriteStorage<'s, Hidden>,
+        Read<'s, PlayState>,
+    );
+
+    /// Runs a pass of the system on our selected components
+    ///
+    /// This function is given a list of the components described under [SystemData](#associatedtype.SystemData).
+    ///
+    /// For each damage overlay, if the ship is undamaged or hidden the function hides the overlay.
+    /// Otherwise it shows the overlay with the damage sprite matching the damage taken,
+    /// using the heaviest damage sprite once the damage goes past the end of the list.
+    fn run(
+        &mut self,
+        (entities, overlays, parents, players, mut sprite_renders, mut hiddens, play_state): Self::SystemData,
+    ) {
+        for (overlay_entity, overlay, parent, sprite_render) in
+            (&*entities, &overlays, &parents, &mut sprite_renders).join()
+        {
+            let ship_is_hidden = hiddens.contains(parent.entity);
+            let damage = players
+                .get(parent.entity)
+                .and_then(|player| play_state.players.get(player.index))
+                .map_or(0, |player_state| player_state.damage);
+            if ship_is_hidden || damage == 0 || overlay.sprite_numbers.is_empty() {
+                let _result = hiddens.insert(overlay_entity, Hidden);
+            } else {
+                let damage_level =
+                    usize::from(damage).min(overlay.sprite_numbers.len()) - 1;
+                sprite_render.sprite_number = overlay.sprite_numbers[damage_level];
+                hiddens.remove(overlay_entity);
+            }
+        }
+    }
+}
@@ -0,0 +1,145 @@
+use amethyst::core::math::Point3;
+use amethyst::core::timing::Time;
+use amethyst::core::transform::Transform;
+use amethyst::ecs::prelude::{Entities, Join, Read, ReadStorage, System, Write, WriteStorage};
+use amethyst::renderer::debug_drawing::DebugLines;
+use amethyst::renderer::palette::Srgba;
+use amethyst::ui::UiText;
+use amethyst::utils::fps_counter::FpsCounter;
+
+use crate::components::{Asteroid, Explosion, HudItem, Inactive, Laser, Ship};
+use crate::resources::{DebugOverlay, DebugStats, Localisation};
+use crate::systems::CollisionBox;
+
+/// How far in front of the sprites the collision boxes are drawn
+/// (behind the [letterbox](../entities/letterbox/index.html), so they don't show outside the arena)
+const COLLIDER_Z: f32 = 0.3;
+
+/// Keeps the [debug overlay](../resources/struct.DebugOverlay.html) up to date
+///
+/// Every frame, this system counts the ships, asteroids, lasers, explosions and entities,
+/// and notes the frame rate and frame time.
+/// While the overlay is visible, it also draws the collision box of every ship, asteroid and laser
+/// (the same boxes the collision systems use, from [CollisionBox](struct.CollisionBox.html)),
+/// and shows the stats on the HUD.
+pub struct DebugSystem;
+
+impl<'s> System<'s> for DebugSystem {
+    /// The data for each pass of the debug system
+    /// We need:
+    ///
+    /// * **Entities**:      the list of entities so we can count them
+    /// * **Ships**, **Asteroids**, **Lasers** and **Explosions**:
+    ///                        read access to each kind of entity so we can count them
+    ///                        and work out their collision boxes
+    /// * **Inactives**:     read access to the inactive markers
+    ///                        so we don't count the entities resting in the pools
+    /// * **Transforms**:    read access to the transforms so we can place the collision boxes
+    /// * **HudItems**:      read access to the HUD items so we can find the debug text
+    /// * **UiTexts**:       write access to the UI texts so we can show the stats
+    /// * **Time**:          read access to the real time taken by the last frame
+    /// * **FpsCounter**:    read access to the frame rate
+    /// * **Localisation**:  read access to the chosen language, to write the stats in
+    /// * **DebugOverlay**:  write access to the debug overlay so we can update its stats
+    /// * **DebugLines**:    write access to the debug lines so we can draw the collision boxes
+    type SystemData = (
+        Entities<'s>,
+        ReadStorage<'s, Ship>,
+        ReadStorage<'s, Asteroid>,
+        ReadStorage<'s, Laser>,
+        ReadStorage<'s, Explosion>,
+        ReadStorage<'s, Inactive>,
+        ReadStorage<'s, Transform>,
+        ReadStorage<'s, HudItem>,
+        WriteStorage<'s, UiText>,
+        Read<'s, Time>,
+        Read<'s, FpsCounter>,
+        Read<'s, Localisation>,
+        Write<'s, DebugOverlay>,
+        Write<'s, DebugLines>,
+    );
+
+    /// Runs a pass of the system on our selected components
+    ///
+    /// This function is given a list of the components described under [SystemData](#associatedtype.SystemData).
+    fn run(
+        &mut self,
+        (entities, ships, asteroids, lasers, explosions, inactives, transforms, hud_items, mut ui_texts, time, fps_counter, localisation, mut debug_overlay, mut debug_lines): Self::SystemData,
+    ) {
+        debug_overlay.stats = DebugStats {
+            fps: fps_counter.sampled_fps(),
+            frame_time: time.delta_real_seconds(),
+            ships: ships.join().count(),
+            asteroids: asteroids.join().count(),
+            lasers: (&lasers, !&inactives).join().count(),
+            explosions: (&explosions, !&inactives).join().count(),
+            entities: (&*entities).join().count(),
+        };
+
+        let text = if debug_overlay.visible {
+            let stats = &debug_overlay.stats;
+            localisation.format(
+                "hud-debug-stats",
+                &[
+                    ("fps", format!("{:.0}", stats.fps)),
+                    ("frame_time", format!("{:.1}", stats.frame_time * 1000.0)),
+                    ("ships", stats.ships.to_string()),
+                    ("asteroids", stats.asteroids.to_string()),
+                    ("lasers", stats.lasers.to_string()),
+                    ("explosions", stats.explosions.to_string()),
+                    ("entities", stats.entities.to_string()),
+                ],
+            )
+        } else {
+            String::new()
+        };
+        for (hud_item, ui_text) in (&hud_items, &mut ui_texts).join() {
+            if *hud_item == HudItem::DebugStats {
+                ui_text.text = text.clone();
+            }
+        }
+
+        if !debug_overlay.visible {
+            return;
+        }
+        // the debug lines are cleared after each frame is drawn, so we draw the boxes every frame
+        for (ship, transform) in (&ships, &transforms).join() {
+            if !ship.is_destroyed() {
+                draw_box(
+                    &mut debug_lines,
+                    &CollisionBox::new(transform, ship.width, ship.height),
+                    Srgba::new(0.0, 1.0, 0.0, 1.0),
+                );
+            }
+        }
+        for (asteroid, transform) in (&asteroids, &transforms).join() {
+            if !asteroid.is_destroyed {
+                draw_box(
+                    &mut debug_lines,
+                    &CollisionBox::new(transform, asteroid.width, asteroid.height),
+                    Srgba::new(1.0, 0.2, 0.2, 1.0),
+                );
+            }
+        }
+        for (laser, transform, _active) in (&lasers, &transforms, !&inactives).join() {
+            draw_box(
+                &mut debug_lines,
+                &CollisionBox::new(transform, laser.width, laser.height),
+                Srgba::new(1.0, 1.0, 0.0, 1.0),
+            );
+        }
+    }
+}
+
+/// Draws the outline of a collision box
+fn draw_box(debug_lines: &mut DebugLines, collision_box: &CollisionBox, colour: Srgba) {
+    let corners = collision_box.corners();
+    for (number, corner) in corners.iter().enumerate() {
+        let next = corners[(number + 1) % corners.len()];
+        debug_lines.draw_line(
+            Point3::new(corner[0], corner[1], COLLIDER_Z),
+            Point3::new(next[0], next[1], COLLIDER_Z),
+            colour,
+        );
+    }
+}
@@ -0,0 +1,67 @@
+use amethyst::core::math::Vector3;
+use amethyst::core::transform::Transform;
+use amethyst::ecs::prelude::{
+    Entities, Join, LazyUpdate, Read, ReadExpect, ReadStorage, System, Write, WriteStorage,
+};
+
+use crate::components::{Explosion, Inactive};
+use crate::resources::{EntityPool, GameClock};
+
+/// How many times its sprite size an explosion grows to by the end of its life
+const EXPLOSION_GROWTH: f32 = 4.0;
+
+/// Grows the explosion and puts it back in the explosion pool when its time is up
+///
+/// Like the [LaserSystem](struct.LaserSystem.html), this is a simple system
+/// that handles an entity's animation and its destruction.
+pub struct ExplosionSystem;
+
+impl<'s> System<'s> for ExplosionSystem {
+    /// The data for each pass of the explosion system
+    /// We need:
+    ///
+    /// * **Entities**:    the list of entities so we can put the explosion back in the pool
+    ///                      when it is finished
+    /// * **Explosions**:  write access to the list of explosions so we can age them
+    /// * **Inactives**:   read access to the inactive markers
+    ///                      so we skip the explosions resting in the pool
+    /// * **Transforms**:  write access to the list of transforms so we can grow the explosions
+    /// * **GameClock**:   read access to the game clock so we can know how much game time
+    ///                      has elapsed since we last ran this system
+    /// * **Explosion pool**: write access to the explosion pool so we can put the explosion back in it
+    /// * **Lazy Update**: a mechanism that queues changes to the world
+    ///                      until after all the systems have run. The pool uses this
+    ///                      to hide the explosion.
+    type SystemData = (
+        Entities<'s>,
+        WriteStorage<'s, Explosion>,
+        ReadStorage<'s, Inactive>,
+        WriteStorage<'s, Transform>,
+        Read<'s, GameClock>,
+        Write<'s, EntityPool<Explosion>>,
+        ReadExpect<'s, LazyUpdate>,
+    );
+
+    /// Runs a pass of the system on our selected components
+    ///
+    /// This function is given a list of the components described under [SystemData](#associatedtype.SystemData).
+    ///
+    /// For each explosion, it ages the explosion and scales it up in proportion to its age.
+    /// Once the explosion is older than its duration, the function puts it back in the explosion pool.
+    fn run(
+        &mut self,
+        (entities, mut explosions, inactives, mut transforms, game_clock, mut explosion_pool, lazy_update): Self::SystemData,
+    ) {
+        for (explosion_entity, explosion, transform, _active) in
+            (&*entities, &mut explosions, &mut transforms, !&inactives).join()
+        {
+            explosion.age += game_clock.delta_seconds;
+            if explosion.age >= explosion.duration {
+                explosion_pool.release(explosion_entity, &lazy_update);
+            } else {
+                let scale = 1.0 + (EXPLOSION_GROWTH - 1.0) * explosion.age / explosion.duration;
+                transform.set_scale(Vector3::new(scale, scale, 1.0));
+            }
+        }
+    }
+}
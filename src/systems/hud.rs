@@ -0,0 +1,137 @@
+use amethyst::ecs::prelude::{Join, Read, ReadStorage, System, WriteStorage};
+use amethyst::ui::{UiText, UiTransform};
+
+use crate::components::{HudItem, HudLayout, Player, Ship};
+use crate::resources::{Arena, HighScore, Localisation, PlayState};
+
+/// Keeps the items of the HUD up to date with the play state and the players' ships
+///
+/// Like the [LivesSystem](struct.LivesSystem.html), this updates
+/// a list of entities based on game-wide resources:
+/// each player's score and score multiplier, the high score and the wave come from the play state,
+/// while each player's fire-cooldown bar and power-up timers come from their ship.
+/// The text is written in the players' chosen language (see [Localisation](../resources/struct.Localisation.html)).
+pub struct HudSystem;
+
+impl<'s> System<'s> for HudSystem {
+    /// The data for each pass of the HUD system
+    /// We need:
+    ///
+    /// * **HudItems**:      read access to the list of HUD items
+    ///                        so we know what each item shows
+    /// * **HudLayouts**:    read access to the layout of each HUD item
+    ///                        so we know the full width of each fire-cooldown bar
+    /// * **UiTexts**:       write access to the list of UI texts
+    ///                        so we can change the HUD's text
+    /// * **UiTransforms**:  write access to the position and size of each HUD item
+    ///                        so we can shorten the fire-cooldown bars
+    /// * **Ships**:         read access to the ships so we can read their cooldowns and power-ups
+    /// * **Players**:       read access to the list of players so we know whose ship is whose
+    /// * **PlayState**:     read access to the play state
+    ///                        so we can read each player's score and multiplier, and the wave
+    /// * **HighScore**:     read access to the best score of the games played before this one
+    /// * **Arena**:         read access to the arena so we know how much the HUD is scaled
+    /// * **Localisation**:  read access to the chosen language, to write the HUD in
+    type SystemData = (
+        ReadStorage<'s, HudItem>,
+        ReadStorage<'s, HudLayout>,
+        WriteStorage<'s, UiText>,
+        WriteStorage<'s, UiTransform>,
+        ReadStorage<'s, Ship>,
+        ReadStorage<'s, Player>,
+        Read<'s, PlayState>,
+        Read<'s, HighScore>,
+        Read<'s, Arena>,
+        Read<'s, Localisation>,
+    );
+
+    /// Runs a pass of the system on our selected components
+    ///
+    /// This function is given a list of the components described under [SystemData](#associatedtype.SystemData).
+    ///
+    /// It first reads how far each player's weapon has recharged, and their ship's power-up timers.
+    /// It then writes the right value into the text of each HUD item,
+    /// and shortens each fire-cooldown bar to show how far the weapon has recharged
+    /// (the bar is at its full width when the ship can fire).
+    fn run(
+        &mut self,
+        (hud_items, hud_layouts, mut ui_texts, mut ui_transforms, ships, players, play_state, high_score, arena, localisation): Self::SystemData,
+    ) {
+        let player_count = play_state.players.len();
+        let mut weapon_readiness = vec![1.0; player_count];
+        let mut power_ups = vec![String::new(); player_count];
+        for (ship, player) in (&ships, &players).join() {
+            if let Some(readiness) = weapon_readiness.get_mut(player.index) {
+                *readiness = ship.weapon_readiness();
+            }
+            if let Some(text) = power_ups.get_mut(player.index) {
+                *text = ship
+                    .power_up_timers()
+                    .iter()
+                    .map(|(name, time_left)| {
+                        localisation.format(
+                            "hud-power-up",
+                            &[
+                                ("name", localisation.text(name)),
+                                ("seconds", format!("{:.1}", time_left)),
+                            ],
+                        )
+                    })
+                    .collect::<Vec<_>>()
+                    .join("  ");
+            }
+        }
+        // a player beating the high score shows straight away
+        let high_score = high_score.score.max(play_state.best_score());
+
+        for (hud_item, ui_text) in (&hud_items, &mut ui_texts).join() {
+            let text = match *hud_item {
+                HudItem::Score { player } => play_state
+                    .players
+                    .get(player)
+                    .map(|player_state| {
+                        localisation.format(
+                            "hud-score",
+                            &[
+                                ("player", (player + 1).to_string()),
+                                ("score", player_state.score.to_string()),
+                            ],
+                        )
+                    }),
+                HudItem::Multiplier { player } => play_state
+                    .players
+                    .get(player)
+                    .map(|player_state| {
+                        localisation.format(
+                            "hud-multiplier",
+                            &[("multiplier", player_state.multiplier().to_string())],
+                        )
+                    }),
+                HudItem::PowerUps { player } => power_ups.get(player).cloned(),
+                HudItem::HighScore => Some(localisation.format(
+                    "hud-high-score",
+                    &[("score", high_score.to_string())],
+                )),
+                HudItem::Wave => Some(localisation.format(
+                    "hud-wave",
+                    &[("wave", play_state.level.to_string())],
+                )),
+                HudItem::CooldownBar { .. }
+                | HudItem::DebugStats
+                | HudItem::AchievementToast => None,
+            };
+            if let Some(text) = text {
+                ui_text.text = text;
+            }
+        }
+
+        for (hud_item, layout, ui_transform) in
+            (&hud_items, &hud_layouts, &mut ui_transforms).join()
+        {
+            if let HudItem::CooldownBar { player } = *hud_item {
+                let readiness = weapon_readiness.get(player).copied().unwrap_or(1.0);
+                ui_transform.width = layout.width * arena.scale * readiness;
+            }
+        }
+    }
+}
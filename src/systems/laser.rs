@@ -1,14 +1,15 @@
-use amethyst::core::timing::Time;
 use amethyst::core::transform::Transform;
-use amethyst::ecs::prelude::{Entities, Join, Read, ReadStorage, System, WriteStorage};
+use amethyst::ecs::prelude::{
+    Entities, Join, LazyUpdate, Read, ReadExpect, ReadStorage, System, Write, WriteStorage,
+};
 //use amethyst::core::Float;
 
-use crate::components::Laser;
-use crate::config::GAME_CONFIGURATION;
-use amethyst::window::ScreenDimensions;
-use amethyst::core::ecs::ReadExpect;
+use crate::components::{Inactive, Laser};
+use crate::resources::{Arena, EntityPool, GameClock, PlayState};
 
-/// Moves the laser and deletes it if it goes off the screen
+/// Moves the laser and puts it back in the laser pool if it goes off the screen
+///
+/// A laser that goes off the screen has missed, which ends the combo of the player who fired it.
 ///
 /// This is a good pattern of one of the simplest systems:
 /// one that handles movement and destruction on out-of-bounds.
@@ -18,23 +19,34 @@ impl<'s> System<'s> for LaserSystem {
     /// The data for each pass of the laser system
     /// We need:
     ///
-    /// * **Entities**:          the list of entities so we can delete the laser
+    /// * **Entities**:          the list of entities so we can put the laser back in the pool
     ///                            when it goes out of bounds
     /// * **Lasers**:            read access to the list of laser components
     ///                            so we select only the laser entities and transforms
+    /// * **Inactives**:         read access to the inactive markers
+    ///                            so we skip the lasers resting in the pool
     /// * **Transforms**:        write access to the list of transforms
     ///                            so we can update the laser positions
-    /// * **Time**:              read access to the time resource so we can know how much time
+    /// * **GameClock**:         read access to the game clock so we can know how much game time
     ///                            has elapsed since we last ran this system
-    /// * **ScreenDimensions**:  read access to the screen dimensions so we figure out if
-    ///                            we've gone off the screen. Note that this is `ReadExpect`
-    ///                            rather than `Read`, as there's no default value
+    /// * **Arena**:             read access to the arena so we figure out if
+    ///                            we've gone off the top of the arena
+    /// * **PlayState**:         write access to the play state so we can end the combo
+    ///                            of a player whose laser misses
+    /// * **Laser pool**:        write access to the laser pool so we can put the laser back in it
+    /// * **Lazy Update**:       a mechanism that queues changes to the world
+    ///                            until after all the systems have run. The pool uses this
+    ///                            to hide the laser.
     type SystemData = (
         Entities<'s>,
         ReadStorage<'s, Laser>,
+        ReadStorage<'s, Inactive>,
         WriteStorage<'s, Transform>,
-        Read<'s, Time>,
-        ReadExpect<'s, ScreenDimensions>,
+        Read<'s, GameClock>,
+        Read<'s, Arena>,
+        Write<'s, PlayState>,
+        Write<'s, EntityPool<Laser>>,
+        ReadExpect<'s, LazyUpdate>,
     );
 
     /// Runs a pass of the system on our selected components
@@ -42,28 +54,32 @@ impl<'s> System<'s> for LaserSystem {
     /// This function is given a list of the components described under [SystemData](#associatedtype.SystemData).
     ///
     /// This is a good example of how entity pattern matching works.
-    /// We don't use anything in the laser component (which is why it is named `_laser_component`).
-    /// It is only in our list so that, when we do the join,
+    /// The laser component is in our list so that, when we do the join,
     /// we pick out only the transforms and entities that have a laser component.
     ///
     /// This function scans the list of entities, lasers and transforms for laser entities and transforms.
     ///
-    /// For each laser transform, it updates the position based on the laser velocity in our game configuration.
+    /// For each laser transform, it updates the position based on the velocity in the laser component
+    /// (which comes from the weapon of the ship that fired it).
     ///
     /// The function then checks the laser's position against the screen top. If the laser has gone off the screen,
-    /// it asks the entity list to queue a request to delete the selected laser entity.
-    /// (The deletion will happen after all the systems have run and the Amethyst engine does a `world.maintain()`.)
-    fn run(&mut self, (entities, lasers, mut transforms, time, screen_dimensions): Self::SystemData) {
-        // Scan through the list of lasers and move them forward.
-        for (laser_entity, _laser_component, laser_transform) in
-            (&*entities, &lasers, &mut transforms).join()
+    /// it puts the selected laser entity back in the laser pool, to be fired again later,
+    /// and ends its owner's combo.
+    /// (The laser will be hidden after all the systems have run and the Amethyst engine does a `world.maintain()`.)
+    fn run(
+        &mut self,
+        (entities, lasers, inactives, mut transforms, game_clock, arena, mut play_state, mut laser_pool, lazy_update): Self::SystemData,
+    ) {
+        // Scan through the list of active lasers and move them forward.
+        for (laser_entity, laser_component, laser_transform, _active) in
+            (&*entities, &lasers, &mut transforms, !&inactives).join()
         {
-            laser_transform
-                .prepend_translation_y(GAME_CONFIGURATION.laser_velocity * time.delta_seconds());
+            laser_transform.prepend_translation_y(laser_component.velocity * game_clock.delta_seconds);
             //+println!("laser at ({},{})", laser_transform.translation()[0], laser_transform.translation()[1]);
-            // Delete the laser if it has gone off the screen
-            if laser_transform.translation()[1] > screen_dimensions.height() {
-                let _result = entities.delete(laser_entity);
+            // Put the laser back in the pool if it has gone off the screen
+            if laser_transform.translation()[1] > arena.height {
+                laser_pool.release(laser_entity, &lazy_update);
+                play_state.break_combo(laser_component.owner);
             }
         }
     }
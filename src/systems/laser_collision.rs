@@ -1,30 +1,65 @@
 use amethyst::core::transform::Transform;
-use amethyst::ecs::prelude::{Entities, Join, ReadStorage, System, WriteStorage};
+use amethyst::ecs::prelude::{
+    Entities, Join, LazyUpdate, Read, ReadExpect, ReadStorage, System, Write, WriteStorage,
+};
 
 use crate::components::Asteroid;
-use crate::components::Laser;
+use crate::components::{Inactive, Laser};
+use crate::entities::split_asteroid;
+use crate::resources::{
+    AsteroidResource, EntityPool, GameClock, GameRandom, PlayState, Telemetry,
+};
+use crate::telemetry::TelemetryEventKind;
+use crate::systems::CollisionBox;
 
-/// Removes the laser and repositions the asteroid
-/// if it detects a collision between them
+/// Puts the laser back in the laser pool and damages the asteroid
+/// if it detects a collision between them.
+///
+/// An asteroid that runs out of hit points is marked for repositioning,
+/// and, if it is large enough, split into smaller fragments.
+/// The player who fired the laser scores for it, adding to their combo.
 pub struct LaserCollisionSystem;
 
 impl<'s> System<'s> for LaserCollisionSystem {
     /// The data for each pass of the laser collision system
     /// We need:
     ///
-    /// * **Entities**:   the list of entities so we can delete the laser
-    ///                     when it collides with an asteroid
+    /// * **Entities**:   the list of entities so we can find the laser
+    ///                     that collides with an asteroid, and create fragments
     /// * **Lasers**:     read access to the list of laser components
     ///                     so we select only the laser entities and transforms
+    /// * **Inactives**:  read access to the inactive markers
+    ///                     so we skip the lasers resting in the pool
     /// * **Transforms**: read access to the list of transforms
     ///                     so we can determine the laser and asteroid positions
     /// * **Asteroids**:  write access to the list of asteroids
-    ///                     so we can mark an asteroid for repositioning
+    ///                     so we can damage an asteroid and mark it for repositioning
+    /// * **PlayState**:  write access to the play state
+    ///                     so we can add to the score and combo of the player who fired the laser
+    /// * **AsteroidResource**: read access to the asteroid creation resources
+    ///                     so we can create fragments when an asteroid splits
+    /// * **GameRandom**: write access to the game's random number generator
+    ///                     so we can give the fragments a random spin
+    /// * **Laser pool**: write access to the laser pool so we can put the laser back in it
+    /// * **Lazy Update**: a mechanism that queues changes to the world
+    ///                     until after all the systems have run. We use this
+    ///                     to create the fragment entities.
+    /// * **GameClock**:  read access to the game clock so we know when each hit happens
+    /// * **Telemetry**:  write access to the game's statistics so we can count the hits,
+    ///                     the destroyed asteroids and the new waves
     type SystemData = (
         Entities<'s>,
         ReadStorage<'s, Laser>,
+        ReadStorage<'s, Inactive>,
         ReadStorage<'s, Transform>,
         WriteStorage<'s, Asteroid>,
+        Write<'s, PlayState>,
+        Option<Read<'s, AsteroidResource>>,
+        Option<Write<'s, GameRandom>>,
+        Write<'s, EntityPool<Laser>>,
+        ReadExpect<'s, LazyUpdate>,
+        Read<'s, GameClock>,
+        Write<'s, Telemetry>,
     );
 
     /// Runs a pass of the system on our selected components.
@@ -34,35 +69,79 @@ impl<'s> System<'s> for LaserCollisionSystem {
     /// It first selects every laser component with its entity and transformation.
     /// For each laser, it scans every asteroid with its location.
     /// For each asteroid, it determines if the two items have collided.
-    /// If they have, the function deletes the laser and marks the asteroid for repositioning.
-    fn run(&mut self, (entities, lasers, transforms, mut asteroids): Self::SystemData) {
-        // For each laser,
-        for (laser_entity, laser_component, laser_transform) in
-            (&*entities, &lasers, &transforms).join()
+    /// If they have, the function puts the laser back in the pool and takes a hit point off the asteroid.
+    ///
+    /// When the asteroid has no hit points left, the function marks it for repositioning,
+    /// scores it for the laser's owner and calls [split_asteroid](../entities/asteroid/fn.split_asteroid.html)
+    /// to break it into smaller fragments.
+    fn run(
+        &mut self,
+        (entities, lasers, inactives, transforms, mut asteroids, mut play_state, asteroid_resource, game_random, mut laser_pool, lazy_update, game_clock, mut telemetry): Self::SystemData,
+    ) {
+        // the screens before the game dispatch the game's systems too
+        let (asteroid_resource, mut game_random) = match (asteroid_resource, game_random) {
+            (Some(asteroid_resource), Some(game_random)) => (asteroid_resource, game_random),
+            _ => return,
+        };
+        // For each active laser,
+        for (laser_entity, laser_component, laser_transform, _active) in
+            (&*entities, &lasers, &transforms, !&inactives).join()
         {
             // Set up the collision box for our laser:
-            let laser_left = laser_transform.translation()[0] - (laser_component.width / 2.);
-            let laser_right = laser_left + laser_component.width;
-            let laser_top = laser_transform.translation()[1] + (laser_component.height / 2.);
+            let laser_box =
+                CollisionBox::new(laser_transform, laser_component.width, laser_component.height);
 
             // scan our asteroids to see if we have hit any one of them
             for (asteroid_component, asteroid_transform) in (&mut asteroids, &transforms).join() {
+                // skip asteroids that are already waiting to be relocated
+                if asteroid_component.is_destroyed {
+                    continue;
+                }
                 // Set up a collision box for our asteroid
-                let asteroid_left =
-                    asteroid_transform.translation()[0] - (asteroid_component.width / 2.);
-                let asteroid_bottom =
-                    asteroid_transform.translation()[1] - (asteroid_component.height / 2.);
-                let asteroid_right = asteroid_left + asteroid_component.width;
+                let asteroid_box = CollisionBox::new(
+                    asteroid_transform,
+                    asteroid_component.width,
+                    asteroid_component.height,
+                );
 
                 // If the two items overlap,
-                if ((laser_left <= asteroid_right && laser_left >= asteroid_left)
-                    || (laser_right <= asteroid_left && laser_right >= asteroid_right))
-                    && (laser_top >= asteroid_bottom)
-                {
-                    // we have a collision. Delete the laser
-                    let _result = entities.delete(laser_entity);
-                    // let the asteroid system know the asteroid is ready for respawn/relocation
-                    asteroid_component.is_destroyed = true;
+                if laser_box.overlaps(&asteroid_box) {
+                    // we have a collision. Put the laser back in the pool
+                    laser_pool.release(laser_entity, &lazy_update);
+                    // and damage the asteroid.
+                    asteroid_component.hit_points = asteroid_component.hit_points.saturating_sub(1);
+                    let player = laser_component.owner;
+                    let size = asteroid_component.size;
+                    telemetry.record(
+                        &game_clock,
+                        TelemetryEventKind::AsteroidHit { player, size },
+                    );
+                    if asteroid_component.hit_points == 0 {
+                        // let the asteroid system know the asteroid is ready for respawn/relocation
+                        asteroid_component.is_destroyed = true;
+                        telemetry.record(
+                            &game_clock,
+                            TelemetryEventKind::AsteroidDestroyed { player, size },
+                        );
+                        let wave = play_state.level;
+                        play_state.score_asteroid(player);
+                        if play_state.level != wave {
+                            telemetry.record(
+                                &game_clock,
+                                TelemetryEventKind::WaveStarted { wave: play_state.level },
+                            );
+                        }
+                        split_asteroid(
+                            &entities,
+                            &asteroid_resource,
+                            asteroid_component,
+                            *asteroid_transform.translation(),
+                            &mut game_random.rng,
+                            &lazy_update,
+                        );
+                    }
+                    // a laser can only hit one asteroid
+                    break;
                 }
             }
         }
@@ -1,41 +1,125 @@
-use amethyst::ecs::prelude::{Entities, Join, Read, ReadStorage, System};
+use amethyst::ecs::prelude::{
+    Entities, Join, LazyUpdate, Read, ReadExpect, ReadStorage, System, Write,
+};
 
 use crate::components::Life;
-use crate::resources::PlayState;
+use crate::config::GAME_CONFIGURATION;
+use crate::entities::add_life_icon;
+use crate::resources::{Arena, GameClock, LivesResource, PlayState, Telemetry};
+use crate::telemetry::TelemetryEventKind;
 
-/// Manages the list of life icons in the UI layer based on the play state
+/// Awards extra lives, and keeps the life icons in the UI layer in step with the play state
 ///
 /// This is a good pattern for managing icons that represent number of lives.
 ///
-/// It updates a list of entities based on a game-wide resource.
+/// It updates a list of entities based on a game-wide resource,
+/// adding and removing entities so there is one for each life.
 pub struct LivesSystem;
 
 impl<'s> System<'s> for LivesSystem {
     /// The data for each pass of the lives system
     /// We need:
     ///
-    /// * **Entities**:  the list of entities so we can delete a life icon
-    ///                    when it's no longer valid
-    /// * **Lives**:     read access to the list of life components
-    ///                    so we can check their life number
-    /// * **PlayState**: read access to the play state
-    ///                    so we can read the current number of lives
-    type SystemData = (Entities<'s>, ReadStorage<'s, Life>, Read<'s, PlayState>);
+    /// * **Entities**:      the list of entities so we can delete a life icon
+    ///                        when it's no longer valid, or add one for an extra life
+    /// * **Lives**:         read access to the list of life components
+    ///                        so we can check their player and life number
+    /// * **PlayState**:     write access to the play state
+    ///                        so we can read each player's score and award extra lives
+    /// * **LivesResource**: read access to the life icon we set up in
+    ///                        <a href="../entities/lives/fn.initialise_lives.html">initialise_lives</a>
+    /// * **Arena**:         read access to the arena, so new life icons line up with the others
+    /// * **Lazy Update**:   a mechanism that queues changes to the world
+    ///                        until after all the systems have run. We use this
+    ///                        to create the life icon entities.
+    /// * **GameClock**:     read access to the game clock so we know when each extra life is awarded
+    /// * **Telemetry**:     write access to the game's statistics so we can note the extra lives
+    type SystemData = (
+        Entities<'s>,
+        ReadStorage<'s, Life>,
+        Write<'s, PlayState>,
+        Option<Read<'s, LivesResource>>,
+        Read<'s, Arena>,
+        ReadExpect<'s, LazyUpdate>,
+        Read<'s, GameClock>,
+        Write<'s, Telemetry>,
+    );
 
     /// Runs a pass of the system on our selected components
     ///
     /// This function is given a list of the components described under [SystemData](#associatedtype.SystemData).
     ///
-    /// It selects the life entities and components, and, for each life,
+    /// It first awards an extra life to each player whose score has reached their next
+    /// extra life score (as set in the [game configuration](../struct.GameConfiguration.html)),
+    /// up to the most lives a player can have. Players who are out of lives don't get any more.
+    /// An extra life also repairs the player's ship, so the
+    /// [DamageSystem](struct.DamageSystem.html) takes its damage overlay off.
+    ///
+    /// It then selects the life entities and components, and, for each life,
     /// checks to see if its number is greater than the current number
-    /// of player lives in the game.
+    /// of lives of the player it belongs to.
     /// If it is, the function asks the entity list to delete the life entity.
     ///
+    /// Finally, it adds an icon for each of a player's lives that doesn't have one.
+    ///
     /// Note that we have no need of the life transform, since we don't move the life.
-    fn run(&mut self, (entities, lives, play_state): Self::SystemData) {
+    fn run(
+        &mut self,
+        (entities, lives, mut play_state, lives_resource, arena, lazy_update, game_clock, mut telemetry): Self::SystemData,
+    ) {
+        // the screens before the game dispatch the game's systems too
+        let lives_resource = match lives_resource {
+            Some(lives_resource) => lives_resource,
+            None => return,
+        };
+        for (player, player_state) in play_state.players.iter_mut().enumerate() {
+            while let Some(extra_life_score) =
+                GAME_CONFIGURATION.extra_life_score(player_state.extra_lives_awarded)
+            {
+                if player_state.score < extra_life_score {
+                    break;
+                }
+                player_state.extra_lives_awarded += 1;
+                if player_state.lives > 0 {
+                    player_state.lives =
+                        player_state.lives.saturating_add(1).min(GAME_CONFIGURATION.max_lives());
+                    player_state.damage = 0;
+                    telemetry.record(
+                        &game_clock,
+                        TelemetryEventKind::ExtraLife {
+                            player,
+                            score: player_state.score,
+                        },
+                    );
+                }
+            }
+        }
+
+        let mut icons_shown = vec![Vec::new(); play_state.players.len()];
         for (life_entity, life_component) in (&*entities, &lives).join() {
-            if life_component.life_number >= play_state.lives {
+            let player_lives = play_state
+                .players
+                .get(life_component.player)
+                .map_or(0, |player_state| player_state.lives);
+            if life_component.life_number >= player_lives {
                 let _result = entities.delete(life_entity);
+            } else if let Some(icons) = icons_shown.get_mut(life_component.player) {
+                icons.push(life_component.life_number);
+            }
+        }
+
+        for (player, (player_state, icons)) in play_state.players.iter().zip(&icons_shown).enumerate() {
+            for life_number in 0..player_state.lives {
+                if !icons.contains(&life_number) {
+                    add_life_icon(
+                        &entities,
+                        &lives_resource,
+                        player,
+                        life_number,
+                        &arena,
+                        &lazy_update,
+                    );
+                }
             }
         }
     }
@@ -0,0 +1,130 @@
+use amethyst::core::Transform;
+use amethyst::ecs::prelude::{Join, Read, ReadExpect, ReadStorage, System, Write};
+use amethyst::input::{InputHandler, StringBindings};
+
+use crate::components::{Asteroid, Player, Ship};
+use crate::network::StateHasher;
+use crate::resources::{Controls, GameClock, GamePhase, Lockstep, PlayState};
+use crate::systems::control::read_controls;
+
+/// Keeps a [network game](../network/index.html) in step with the other copies of the game
+///
+/// Each frame, the system:
+///
+/// 1. takes in the other players' controls (and hashes) that have arrived;
+/// 2. hashes the game state left by the last frame played, so the copies can check they
+///    still agree (see [Lockstep](../resources/struct.Lockstep.html)); then
+/// 3. if every player's controls for the next frame are in, reads the local player's controls,
+///    sends them to the others and lets the gameplay systems play the frame;
+///    otherwise, holds the gameplay systems back (see [GamePhase](../resources/enum.GamePhase.html))
+///    and sends its controls again.
+///
+/// The local player always steers with player one's bindings, whichever ship they fly.
+/// Outside network games the system does nothing.
+pub struct LockstepSystem;
+
+impl<'s> System<'s> for LockstepSystem {
+    /// The data for each pass of the lockstep system
+    /// We need:
+    ///
+    /// * **Input Handler**: read access to the input handler so we can read the local player's controls
+    /// * **Lockstep**:      write access to the lockstep so we can swap controls with the other copies
+    /// * **Controls**:      write access to the controls so we can fill in every player's controls
+    /// * **GamePhase**:     write access to the game phase so we can hold the gameplay systems back
+    /// * **GameClock**:     read access to the game clock so we know how many frames have been played
+    /// * **PlayState**, **Ships**, **Players**, **Asteroids** and **Transforms**:
+    ///                        read access to the game state so we can hash it
+    type SystemData = (
+        ReadExpect<'s, InputHandler<StringBindings>>,
+        Write<'s, Lockstep>,
+        Write<'s, Controls>,
+        Write<'s, GamePhase>,
+        Read<'s, GameClock>,
+        Read<'s, PlayState>,
+        ReadStorage<'s, Ship>,
+        ReadStorage<'s, Player>,
+        ReadStorage<'s, Asteroid>,
+        ReadStorage<'s, Transform>,
+    );
+
+    /// Runs a pass of the system on our selected components
+    fn run(
+        &mut self,
+        (
+            input,
+            mut lockstep,
+            mut controls,
+            mut game_phase,
+            game_clock,
+            play_state,
+            ships,
+            players,
+            asteroids,
+            transforms,
+        ): Self::SystemData,
+    ) {
+        if !lockstep.is_networked() {
+            return;
+        }
+        lockstep.receive();
+        if lockstep.is_hash_due(game_clock.frame) {
+            let hash = hash_state(&play_state, &ships, &players, &asteroids, &transforms);
+            lockstep.record_hash(game_clock.frame, hash);
+        }
+
+        if lockstep.failure.is_none() && lockstep.is_next_frame_ready() {
+            let local_controls = read_controls(&input, &Player::new(0));
+            controls.players = lockstep.advance(&local_controls);
+            *game_phase = GamePhase::Playing;
+        } else {
+            lockstep.send_input();
+            *game_phase = GamePhase::Waiting;
+        }
+    }
+}
+
+/// The hash of everything that should be the same in every copy of the game:
+/// the ships, the asteroids, and the players' lives and scores
+///
+/// The entities are hashed one by one and the hashes added up,
+/// so the order the entities are stored in doesn't matter.
+fn hash_state(
+    play_state: &PlayState,
+    ships: &ReadStorage<Ship>,
+    players: &ReadStorage<Player>,
+    asteroids: &ReadStorage<Asteroid>,
+    transforms: &ReadStorage<Transform>,
+) -> u64 {
+    let mut hasher = StateHasher::default();
+    for player_state in &play_state.players {
+        hasher.write(&[player_state.lives, player_state.damage]);
+        hasher.write_u32(player_state.score);
+    }
+    let ships_hash = (ships, players, transforms)
+        .join()
+        .map(|(ship, player, transform)| {
+            let mut ship_hasher = StateHasher::default();
+            ship_hasher.write(&player.index.to_le_bytes());
+            ship_hasher.write_f32(transform.translation().x);
+            ship_hasher.write_f32(transform.translation().y);
+            ship_hasher.write_f32(ship.velocity.x);
+            ship_hasher.write_f32(ship.velocity.y);
+            ship_hasher.finish()
+        })
+        .fold(0, u64::wrapping_add);
+    let asteroids_hash = (asteroids, transforms)
+        .join()
+        .map(|(asteroid, transform)| {
+            let mut asteroid_hasher = StateHasher::default();
+            asteroid_hasher.write(&[asteroid.hit_points]);
+            asteroid_hasher.write_f32(transform.translation().x);
+            asteroid_hasher.write_f32(transform.translation().y);
+            asteroid_hasher.write_f32(asteroid.velocity.x);
+            asteroid_hasher.write_f32(asteroid.velocity.y);
+            asteroid_hasher.finish()
+        })
+        .fold(0, u64::wrapping_add);
+    hasher.write(&ships_hash.to_le_bytes());
+    hasher.write(&asteroids_hash.to_le_bytes());
+    hasher.finish()
+}
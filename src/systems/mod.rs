@@ -16,16 +16,42 @@
 //! A system takes a set of common elements, such as components, entities and resources,
 //! and performs some action with them.
 
+mod achievement;
 mod asteroid;
+mod background;
+mod camera;
+mod clock;
+mod collision_box;
+mod control;
+mod damage;
+mod debug;
+mod explosion;
+mod hud;
 mod laser;
 mod laser_collision;
 mod lives;
+mod lockstep;
+mod respawn;
 mod ship;
 mod ship_collision;
+mod telemetry;
 
+pub use self::achievement::AchievementSystem;
 pub use self::asteroid::AsteroidSystem;
+pub use self::background::BackgroundSystem;
+pub use self::camera::CameraSystem;
+pub use self::clock::ClockSystem;
+pub use self::collision_box::CollisionBox;
+pub use self::control::ControlSystem;
+pub use self::damage::DamageSystem;
+pub use self::debug::DebugSystem;
+pub use self::explosion::ExplosionSystem;
+pub use self::hud::HudSystem;
 pub use self::laser::LaserSystem;
 pub use self::laser_collision::LaserCollisionSystem;
 pub use self::lives::LivesSystem;
+pub use self::lockstep::LockstepSystem;
+pub use self::respawn::RespawnSystem;
 pub use self::ship::ShipSystem;
 pub use self::ship_collision::ShipCollisionSystem;
+pub use self::telemetry::TelemetrySystem;
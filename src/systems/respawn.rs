@@ -0,0 +1,114 @@
+use amethyst::core::math::Vector2;
+use amethyst::core::transform::Transform;
+use amethyst::core::Hidden;
+use amethyst::ecs::prelude::{
+    Entities, Join, Read, ReadStorage, System, WriteStorage,
+};
+
+use crate::components::{Player, Ship, ShipStatus};
+use crate::config::GAME_CONFIGURATION;
+use crate::entities::ship_start_position;
+use crate::resources::{Arena, GameClock, PlayState};
+
+/// Brings the ship back after it has been destroyed
+///
+/// This system implements a simple state machine on the ship's
+/// [status](../components/enum.ShipStatus.html):
+///
+/// 1. When the ship is destroyed, it is hidden until the respawn delay has passed
+///    (or for good, if its player is out of lives);
+/// 2. It then reappears at its start position and becomes invulnerable,
+///    blinking to let the player know asteroids can't hit it;
+/// 3. Once the invulnerability period is over, the ship goes back to normal.
+pub struct RespawnSystem;
+
+impl<'s> System<'s> for RespawnSystem {
+    /// The data for each pass of the respawn system
+    /// We need:
+    ///
+    /// * **Entities**:          the list of entities so we can hide or show the ship
+    /// * **Ships**:             write access to the list of ship(s)
+    ///                            so we can update the ship's status
+    /// * **Players**:           read access to the list of players
+    ///                            so we know each ship's start position and lives
+    /// * **Transforms**:        write access to the list of ship position(s)
+    ///                            so we can move the ship back to its start position
+    /// * **Hiddens**:           write access to the list of hidden flags
+    ///                            so we can hide the ship or make it blink
+    /// * **GameClock**:         read access to the game clock so we can know how much game time
+    ///                            has elapsed since we last ran this system
+    /// * **Arena**:             read access to the arena so we can
+    ///                            find the ship's start position
+    /// * **PlayState**:         read access to the play state so we don't bring back
+    ///                            the ship of a player who is out of lives
+    type SystemData = (
+        Entities<'s>,
+        WriteStorage<'s, Ship>,
+        ReadStorage<'s, Player>,
+        WriteStorage<'s, Transform>,
+        WriteStorage<'s, Hidden>,
+        Read<'s, GameClock>,
+        Read<'s, Arena>,
+        Read<'s, PlayState>,
+    );
+
+    /// Runs a pass of the system on our selected components
+    ///
+    /// This function is given a list of the components described under [SystemData](#associatedtype.SystemData).
+    ///
+    /// For each ship, it counts down the timer of the ship's current status
+    /// and moves the ship on to its next status when the timer runs out.
+    fn run(
+        &mut self,
+        (entities, mut ships, players, mut transforms, mut hiddens, game_clock, arena, play_state): Self::SystemData,
+    ) {
+        let player_count = play_state.players.len();
+        for (ship_entity, ship, player, transform) in
+            (&*entities, &mut ships, &players, &mut transforms).join()
+        {
+            let is_out_of_lives = play_state
+                .players
+                .get(player.index)
+                .map_or(true, |player_state| player_state.lives == 0);
+            match ship.status {
+                ShipStatus::Active => {}
+                ShipStatus::Destroyed { respawn_timer } => {
+                    let respawn_timer = respawn_timer - game_clock.delta_seconds;
+                    if respawn_timer > 0.0 || is_out_of_lives {
+                        ship.status = ShipStatus::Destroyed { respawn_timer };
+                        let _result = hiddens.insert(ship_entity, Hidden);
+                    } else {
+                        // bring the ship back, stationary, at its start position
+                        transform.set_translation(ship_start_position(
+                            ship,
+                            player.index,
+                            player_count,
+                            arena.width,
+                        ));
+                        ship.velocity = Vector2::zeros();
+                        ship.status = ShipStatus::Invulnerable {
+                            invulnerable_timer: GAME_CONFIGURATION.invulnerability_period,
+                        };
+                        hiddens.remove(ship_entity);
+                    }
+                }
+                ShipStatus::Invulnerable { invulnerable_timer } => {
+                    let invulnerable_timer = invulnerable_timer - game_clock.delta_seconds;
+                    if invulnerable_timer > 0.0 {
+                        ship.status = ShipStatus::Invulnerable { invulnerable_timer };
+                        // blink the ship on and off
+                        let blink_interval = GAME_CONFIGURATION.invulnerability_blink_interval.max(0.01);
+                        if (invulnerable_timer / blink_interval) as u32 % 2 == 0 {
+                            let _result = hiddens.insert(ship_entity, Hidden);
+                        } else {
+                            hiddens.remove(ship_entity);
+                        }
+                    } else {
+                        ship.status = ShipStatus::Active;
+                        hiddens.remove(ship_entity);
+                    }
+                }
+            }
+        }
+    }
+}
@@ -1,27 +1,37 @@
-use crate::components::Ship;
-use crate::config::GAME_CONFIGURATION;
+use crate::components::{Laser, Player, Ship};
+use crate::config::{AxisHandling, MovementModel, ShipEdgeBehaviour, GAME_CONFIGURATION};
 use crate::entities::fire_laser;
-use crate::resources::LaserResource;
+use crate::resources::{Arena, Controls, EntityPool, GameClock, LaserResource, Telemetry};
+use crate::telemetry::TelemetryEventKind;
 
 use amethyst::core::math::Vector3;
-use amethyst::core::timing::Time;
 use amethyst::core::transform::Transform;
-use amethyst::ecs::prelude::{Entities, Join, LazyUpdate, ReadExpect, System, WriteStorage};
-use amethyst::input::InputHandler;
-use amethyst::input::StringBindings;
+use amethyst::ecs::prelude::{
+    Entities, Join, LazyUpdate, Read, ReadExpect, ReadStorage, System, Write, WriteStorage,
+};
 
 /// Moves the ship and fires lasers based on user-provided input.
 ///
-/// This system implements a pattern of thrust- (or force-) based movement.
-/// When the player moves the joystick along an axis, that applies thrust
-/// to the ship's velocity.
+/// This system implements several patterns of movement, chosen by the
+/// [movement model](../enum.MovementModel.html) in the game configuration:
+/// thrust- (or force-) based movement, with or without drag,
+/// and direct arcade-style control.
+/// With thrust, when the player moves the joystick along an axis, that applies thrust
+/// to the ship's velocity, up to the ship's maximum speed.
+/// The further an analog stick is pushed, the more thrust is applied
+/// (or, with direct control, the faster the ship goes).
+/// Thrust, drag and maximum speed are tuned separately for each axis
+/// in the [game configuration](../struct.GameConfiguration.html).
 ///
-/// The ship also bounces off either side of the screen as if they were walls.
+/// The ship always moves from side to side. If the game configuration allows it,
+/// the ship can also move up and down, within a band at the bottom of the arena.
+/// The ship either bounces off or stops dead at the edges of its play area.
 ///
-/// This is also an example of keeping a list of entities when we know
-/// from a design point of view we have only one. It needs to be an entity
-/// so it can be rendered, and entities always come in lists, even if the
-/// list only has one entry.
+/// A destroyed ship is left alone until the [RespawnSystem](struct.RespawnSystem.html) brings it back.
+///
+/// Each ship belongs to a player, and is steered by that player's
+/// [controls](../resources/struct.Controls.html), which the
+/// [ControlSystem](struct.ControlSystem.html) reads from the keyboard, a controller or a replay.
 pub struct ShipSystem;
 
 impl<'s> System<'s> for ShipSystem {
@@ -31,33 +41,42 @@ impl<'s> System<'s> for ShipSystem {
     /// * **Entities**:          the list of entities so we can add a fired laser to them
     /// * **Ships**:             write access to the list of ship(s)
     ///                            so we can update the ship's velocity
+    /// * **Players**:           read access to the list of players
+    ///                            so we know whose controls steer each ship
     /// * **Transforms**:        write access to the list of ship position(s)
     ///                            so we can update the ship's location
-    /// * **Time**:              read access to the time resource so we can know how much time
+    /// * **GameClock**:         read access to the game clock so we can know how much game time
     ///                            has elapsed since we last ran this system
-    /// * **Input Handler**:     read access to the input handler so we can
-    ///                            sample the position of the "joystick" and the fire "button"
+    /// * **Controls**:          read access to the players' controls so we can
+    ///                            find the position of the "joystick" and the fire "button"
     /// * **LaserResource**:     read access to the laser creation resources we set up in
     ///                            <a href="../entities/laser/fn.initialise_laser_resource.html">initialise_laser</a>
+    /// * **Laser pool**:        write access to the lasers resting in the pool,
+    ///                            so we can re-use one rather than create a new one
     /// * **Lazy Update**:       a mechanism that queues changes to the world
     ///                            until after all the systems have run. We use this
-    ///                            to create a laser entity with all its related components.
+    ///                            to set up a laser entity with all its related components.
+    /// * **Arena**:             read access to the arena so we know
+    ///                            where the edges of the play area are
+    /// * **Telemetry**:         write access to the game's statistics so we can count the shots fired
     type SystemData = (
         Entities<'s>,
         WriteStorage<'s, Ship>,
+        ReadStorage<'s, Player>,
         WriteStorage<'s, Transform>,
-        ReadExpect<'s, Time>,
-        ReadExpect<'s, InputHandler<StringBindings>>,
-        ReadExpect<'s, LaserResource>,
+        Read<'s, GameClock>,
+        Read<'s, Controls>,
+        Option<Read<'s, LaserResource>>,
+        Write<'s, EntityPool<Laser>>,
         ReadExpect<'s, LazyUpdate>,
+        Read<'s, Arena>,
+        Write<'s, Telemetry>,
     );
 
     /// Runs a pass of the system on our selected components
     ///
     /// This function is given a list of the components described under [SystemData](#associatedtype.SystemData).
-    /// It does an operation for each ship with its related position (transform).
-    /// <em>(We don't care that there's only one ship; this is the appropriate way to
-    /// extract the ship and transport component from our storage.)</em>
+    /// It does an operation for each ship with its related player and position (transform).
     ///
     /// We can then figure out whether or not we can fire the laser,
     /// then whether or not the user wants to fire the laser,
@@ -65,63 +84,176 @@ impl<'s> System<'s> for ShipSystem {
     ///
     /// If the user wants to fire and we have a laser ready, we
     /// call the [fire_laser](../entities/laser/fn.fire_laser.html) function
-    /// with the correct resources so it can queue a request to create our laser entity.
+    /// with the correct resources so it can queue a request to set up our laser entity.
     ///
-    /// If the user moved the "joystick" off-centre (e.g. pressed **a** or **d** keys),
+    /// If the user moved the player's "joystick" off-centre (e.g. pressed **a** or **d** keys,
+    /// or pushed the controller's left stick),
     /// apply the appropriate thrust to the ship's velocity.
     ///
-    /// Finally, we move the ship and bounce it off the bounds if we have hit them.
+    /// Finally, we move the ship and keep it within its play area.
     fn run(
         &mut self,
-        (entities, mut ships, mut transforms, time, input, laser_resource, lazy_update): Self::SystemData,
+        (entities, mut ships, players, mut transforms, game_clock, controls, laser_resource, mut laser_pool, lazy_update, arena, mut telemetry): Self::SystemData,
     ) {
-        for (ship, transform) in (&mut ships, &mut transforms).join() {
+        // the screens before the game dispatch the game's systems too
+        let laser_resource = match laser_resource {
+            Some(laser_resource) => laser_resource,
+            None => return,
+        };
+        let delta_seconds = game_clock.delta_seconds;
+        let area_height = GAME_CONFIGURATION.ship_area_height.max(0.0).min(1.0);
+        let area_top = arena.height * area_height;
+        for (ship, player, transform) in (&mut ships, &players, &mut transforms).join() {
+            // a destroyed ship can't move or fire until it respawns
+            if ship.is_destroyed() {
+                continue;
+            }
             // count down on the amount of time before we can fire again.
             if ship.trigger_reset_timer > 0.0 {
-                ship.trigger_reset_timer -= time.delta_seconds();
+                ship.trigger_reset_timer -= delta_seconds;
             }
-            // get the current 'joystick' reading and whether the fire button has been pressed.
-            let optional_movement = input.axis_value("ship");
-            let optional_action = input.action_is_down("fire");
+            // get the player's current 'joystick' reading and whether their fire button has been pressed.
+            let player_controls = controls.players.get(player.index).copied().unwrap_or_default();
 
-            // if the fire button is down,
-            if let Some(action) = optional_action {
-                // and sufficient time has passed since we last fired,
-                if action && ship.trigger_reset_timer <= 0.0 {
-                    // fire from the middle top of the ship.
-                    let fire_position = Vector3::new(
-                        transform.translation()[0],
-                        transform.translation()[1] + (ship.height / 2.),
-                        0.0,
-                    );
-                    fire_laser(&entities, &laser_resource, fire_position, &lazy_update);
+            // if the fire button is down, and sufficient time has passed since we last fired,
+            if player_controls.fire && ship.trigger_reset_timer <= 0.0 {
+                // fire from the middle top of the ship.
+                let fire_position = Vector3::new(
+                    transform.translation()[0],
+                    transform.translation()[1] + (ship.height / 2.),
+                    0.0,
+                );
+                fire_laser(
+                    &entities,
+                    &laser_resource,
+                    fire_position,
+                    player.index,
+                    &mut laser_pool,
+                    &lazy_update,
+                );
+                telemetry.record(
+                    &game_clock,
+                    TelemetryEventKind::ShotFired {
+                        player: player.index,
+                    },
+                );
 
-                    // reset the timer so we can't fire again until the timeout has elapsed.
-                    ship.trigger_reset_timer = GAME_CONFIGURATION.trigger_reset_timeout;
-                }
+                // reset the timer so we can't fire again until the timeout has elapsed.
+                ship.trigger_reset_timer = ship.trigger_reset_timeout;
             }
 
-            // if joystick is off centre,
-            if let Some(movement) = optional_movement {
-                ship.velocity +=
-                    movement as f32 * time.delta_seconds() * GAME_CONFIGURATION.ship_thrust;
-            }
+            // steer in proportion to how far the joystick is pushed along each axis
+            ship.velocity[0] = steer(
+                ship,
+                ship.velocity[0],
+                player_controls.horizontal,
+                &GAME_CONFIGURATION.ship_horizontal_handling,
+                delta_seconds,
+            );
+            ship.velocity[1] = if GAME_CONFIGURATION.ship_vertical_movement {
+                steer(
+                    ship,
+                    ship.velocity[1],
+                    player_controls.vertical,
+                    &GAME_CONFIGURATION.ship_vertical_handling,
+                    delta_seconds,
+                )
+            } else {
+                0.0
+            };
 
             // move the ship according to its velocity
-            transform.prepend_translation_x(-ship.velocity * time.delta_seconds());
+            transform.prepend_translation_x(ship.velocity[0] * delta_seconds);
+            transform.prepend_translation_y(ship.velocity[1] * delta_seconds);
 
-            // make sure the ship stays on the screen
-            let arena_width = 1024.;
-            let arena_left = 0.;
-            let max_position = arena_width;
-            if transform.translation()[0] < arena_left
-            {
-                transform.set_translation_x(arena_left);
-                ship.velocity = -ship.velocity; // bounce off the left wall
-            } else if transform.translation()[0] >= max_position {
-                transform.set_translation_x(max_position);
-                ship.velocity = -ship.velocity; // bounce off right wall
+            // make sure the ship stays within its play area
+            let (half_width, half_height) = (ship.width / 2., ship.height / 2.);
+            let (position_x, velocity_x) = keep_within(
+                transform.translation()[0],
+                ship.velocity[0],
+                half_width,
+                arena.width - half_width,
+            );
+            transform.set_translation_x(position_x);
+            ship.velocity[0] = velocity_x;
+            if GAME_CONFIGURATION.ship_vertical_movement {
+                let (position_y, velocity_y) = keep_within(
+                    transform.translation()[1],
+                    ship.velocity[1],
+                    half_height + 0.1,
+                    (area_top - half_height).max(half_height + 0.1),
+                );
+                transform.set_translation_y(position_y);
+                ship.velocity[1] = velocity_y;
             }
         }
     }
 }
+
+/// Works out the ship's new speed along one axis,
+/// using the movement model chosen in the game configuration
+///
+/// * **Inertial**: the thrust from the joystick is added to the speed,
+///   and nothing else slows the ship down or caps its speed;
+/// * **Damped**: as inertial, but drag takes its share of the speed,
+///   which is capped at the ship's maximum speed;
+/// * **Direct**: the joystick sets the speed the ship heads for.
+///
+/// In every model, the axis handling scales the thrust.
+/// It also scales the maximum speed of the damped and direct models,
+/// and adds its own drag to that of the damped model.
+fn steer(
+    ship: &Ship,
+    velocity: f32,
+    movement: f32,
+    handling: &AxisHandling,
+    delta_seconds: f32,
+) -> f32 {
+    let (velocity, max_speed) = match GAME_CONFIGURATION.ship_movement_model {
+        MovementModel::Inertial => {
+            let model = &GAME_CONFIGURATION.inertial_movement;
+            let thrust = ship.thrust * model.thrust * handling.thrust;
+            return velocity + movement * thrust * delta_seconds;
+        }
+        MovementModel::Damped => {
+            let model = &GAME_CONFIGURATION.damped_movement;
+            let thrust = ship.thrust * model.thrust * handling.thrust;
+            let velocity = velocity + movement * thrust * delta_seconds;
+            let drag = model.drag + handling.drag;
+            (
+                velocity * (1.0 - drag * delta_seconds).max(0.0),
+                ship.max_speed * model.max_speed,
+            )
+        }
+        MovementModel::Direct => {
+            let model = &GAME_CONFIGURATION.direct_movement;
+            let max_speed = ship.max_speed * model.max_speed;
+            let target = movement * max_speed * handling.max_speed;
+            // close the gap to the speed the joystick asks for
+            let catch_up = if model.response_time > 0.0 {
+                (delta_seconds / model.response_time).min(1.0)
+            } else {
+                1.0
+            };
+            (velocity + (target - velocity) * catch_up, max_speed)
+        }
+    };
+    // don't let the ship go faster than it can handle
+    let max_speed = max_speed * handling.max_speed;
+    velocity.max(-max_speed).min(max_speed)
+}
+
+/// Keeps a position along one axis between the lowest and highest positions allowed
+///
+/// Returns the new position and speed: when the ship reaches the edge,
+/// it either bounces back off it or stops dead, depending on the game configuration.
+fn keep_within(position: f32, velocity: f32, lowest: f32, highest: f32) -> (f32, f32) {
+    let bounce = GAME_CONFIGURATION.ship_edge_behaviour == ShipEdgeBehaviour::Bounce;
+    if position < lowest {
+        (lowest, if bounce { velocity.abs() } else { 0.0 })
+    } else if position > highest {
+        (highest, if bounce { -velocity.abs() } else { 0.0 })
+    } else {
+        (position, velocity)
+    }
+}
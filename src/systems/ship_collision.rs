@@ -1,76 +1,144 @@
 use amethyst::core::transform::Transform;
-use amethyst::ecs::prelude::{Join, ReadStorage, System, Write, WriteStorage};
+use amethyst::ecs::prelude::{
+    Entities, Join, LazyUpdate, Read, ReadExpect, ReadStorage, System, Write, WriteStorage,
+};
 
 use crate::components::Asteroid;
-use crate::components::Ship;
-use crate::resources::PlayState;
+use crate::components::Explosion;
+use crate::components::{Player, Ship, ShipStatus};
+use crate::config::GAME_CONFIGURATION;
+use crate::entities::spawn_explosion;
+use crate::resources::{EntityPool, ExplosionResource};
+use crate::resources::{GameClock, PlayState, Telemetry};
+use crate::systems::CollisionBox;
+use crate::telemetry::TelemetryEventKind;
 
-/// Reduces the number of lives
+/// Destroys the ship, reduces its player's number of lives, damages the ship
 /// and marks the asteroid for repositioning
 /// if it detects a ship colliding with an asteroid.
+///
+/// Ships that are destroyed or invulnerable are ignored,
+/// so an asteroid can only cost the player one life.
 pub struct ShipCollisionSystem;
 
 impl<'s> System<'s> for ShipCollisionSystem {
     /// The data for each pass of the ship collision system
     /// We need:
     ///
+    /// * **Entities**:   the list of entities so we can create an explosion
     /// * **PlayState**:  write access to the play state
-    ///                     so we can update the number of lives
-    /// * **Ships**:      read access to the list of ships (which consists of exactly one ship)
-    ///                     so we can determine the ship's collision box
+    ///                     so we can update the player's number of lives, their ship's damage and their combo
+    /// * **Ships**:      write access to the list of ships (one per player)
+    ///                     so we can determine the ship's collision box and mark it as destroyed
+    /// * **Players**:    read access to the list of players
+    ///                     so we know whose lives to take
     /// * **Transforms**: read access to the list of ship and asteroid locations
     ///                     so we can determine both collision boxes
     /// * **Asteroids**:  write access to the list of asteroids
     ///                     so we can mark an asteroid for repositioning
-    ///
-    /// Note that we have a list of ships even though the game has only one ship.
-    /// This is the appropriate way to extract the ship and transport component from our storage.
+    /// * **ExplosionResource**: read access to the explosion creation resources
+    ///                     so we can blow up the ship
+    /// * **Explosion pool**: write access to the explosions resting in the pool,
+    ///                     so we can re-use one rather than create a new one
+    /// * **Lazy Update**: a mechanism that queues changes to the world
+    ///                     until after all the systems have run. We use this
+    ///                     to set up the explosion entity.
+    /// * **GameClock**:  read access to the game clock so we know when each ship is hit
+    /// * **Telemetry**:  write access to the game's statistics so we can count the lives lost
     type SystemData = (
+        Entities<'s>,
         Write<'s, PlayState>,
-        ReadStorage<'s, Ship>,
+        WriteStorage<'s, Ship>,
+        ReadStorage<'s, Player>,
         ReadStorage<'s, Transform>,
         WriteStorage<'s, Asteroid>,
+        Option<Read<'s, ExplosionResource>>,
+        Write<'s, EntityPool<Explosion>>,
+        ReadExpect<'s, LazyUpdate>,
+        Read<'s, GameClock>,
+        Write<'s, Telemetry>,
     );
 
     /// Runs a pass of the system on our selected components.
     ///
     /// This function is given a list of the components described under [SystemData](#associatedtype.SystemData).
-    /// It first runs a pass on every ship with its location (don't worry that we have only one ship).
+    /// It first runs a pass on every vulnerable ship with its player and location.
     ///
     /// For each ship, it then runs another pass on each asteroid with its location.
     /// For each asteroid, it checks whether or not the asteroid overlaps with the ship.
-    /// If it does, it marks the asteroid for repositioning.
+    /// If it does, it marks the asteroid for repositioning, blows up the ship
+    /// and starts the ship's respawn timer.
     ///
     /// The [AsteroidSystem](struct.AsteroidSystem.html) will do the actual repositioning,
-    /// since it has write access to the transform list.
+    /// since it has write access to the transform list,
+    /// and the [RespawnSystem](struct.RespawnSystem.html) will bring the ship back.
     ///
     /// Note the second pass ("join") will be run in parallel for maximum throughput. This
     /// is the key advantage of an Entity-Component System.
-    fn run(&mut self, (mut play_state, ships, transforms, mut asteroids): Self::SystemData) {
-        for (ship_component, ship_transform) in (&ships, &transforms).join() {
+    fn run(
+        &mut self,
+        (entities, mut play_state, mut ships, players, transforms, mut asteroids, explosion_resource, mut explosion_pool, lazy_update, game_clock, mut telemetry): Self::SystemData,
+    ) {
+        // the screens before the game dispatch the game's systems too
+        let explosion_resource = match explosion_resource {
+            Some(explosion_resource) => explosion_resource,
+            None => return,
+        };
+        for (ship_component, player, ship_transform) in (&mut ships, &players, &transforms).join() {
+            // ships that are destroyed or invulnerable can't be hit
+            if !ship_component.is_vulnerable() {
+                continue;
+            }
             // create a collision box for our ship
-            let ship_left = ship_transform.translation()[0];
-            let ship_top = ship_transform.translation()[1] + ship_component.height;
-            let ship_right = ship_left + ship_component.width;
+            let ship_box =
+                CollisionBox::new(ship_transform, ship_component.width, ship_component.height);
 
             // check to see if our ship has collided with any asteroid
             for (asteroid_component, asteroid_transform) in (&mut asteroids, &transforms).join() {
+                // skip asteroids that are already waiting to be relocated
+                if asteroid_component.is_destroyed {
+                    continue;
+                }
                 // create a collision box for our asteroid
-                let asteroid_left = asteroid_transform.translation()[0];
-                let asteroid_bottom = asteroid_transform.translation()[1];
-                let asteroid_right = asteroid_left + asteroid_component.width;
+                let asteroid_box = CollisionBox::new(
+                    asteroid_transform,
+                    asteroid_component.width,
+                    asteroid_component.height,
+                );
 
                 // if the two collision boxes overlap,
-                if ((ship_left <= asteroid_right && ship_left >= asteroid_left)
-                    || (ship_right <= asteroid_left && ship_right >= asteroid_right))
-                    && (ship_top >= asteroid_bottom)
-                {
-                    // we have a collision. Decrement the number of lives of the game
-                    if play_state.lives > 0 {
-                        play_state.lives -= 1;
+                if ship_box.overlaps(&asteroid_box) {
+                    // we have a collision. Decrement the number of lives of the player
+                    if let Some(player_state) = play_state.players.get_mut(player.index) {
+                        player_state.lives = player_state.lives.saturating_sub(1);
+                        // and show the ship has taken another hit
+                        player_state.damage = player_state.damage.saturating_add(1);
+                        telemetry.record(
+                            &game_clock,
+                            TelemetryEventKind::LifeLost {
+                                player: player.index,
+                                lives: player_state.lives,
+                            },
+                        );
                     }
+                    // losing the ship ends the player's combo
+                    play_state.break_combo(player.index);
                     // let the asteroid system know the asteroid is ready for respawn/relocation
                     asteroid_component.is_destroyed = true;
+
+                    // blow up the ship, and let the respawn system bring it back later
+                    spawn_explosion(
+                        &entities,
+                        &explosion_resource,
+                        *ship_transform.translation(),
+                        &mut explosion_pool,
+                        &lazy_update,
+                    );
+                    ship_component.status = ShipStatus::Destroyed {
+                        respawn_timer: GAME_CONFIGURATION.respawn_delay,
+                    };
+                    // the ship is gone, so it can't hit any more asteroids
+                    break;
                 }
             }
         }
@@ -0,0 +1,31 @@
+use amethyst::ecs::prelude::{System, Write};
+
+use crate::resources::Telemetry;
+use crate::telemetry::{append_json_lines, events_path};
+
+/// Writes out the [telemetry events](../struct.TelemetryEvent.html) of each frame, if they are being streamed
+///
+/// The events are added to the end of the events file in the player's data directory,
+/// as JSON Lines. If the file can't be written, streaming is turned off for the rest
+/// of the game, rather than reporting the same problem every frame.
+pub struct TelemetrySystem;
+
+impl<'s> System<'s> for TelemetrySystem {
+    /// The data for each pass of the telemetry system
+    /// We need:
+    ///
+    /// * **Telemetry**: write access to the game's statistics so we can take the frame's events
+    type SystemData = Write<'s, Telemetry>;
+
+    /// Runs a pass of the system on our selected resources
+    fn run(&mut self, mut telemetry: Self::SystemData) {
+        if telemetry.pending_events.is_empty() {
+            return;
+        }
+        if let Err(message) = append_json_lines(&events_path(), &telemetry.pending_events) {
+            log::warn!("{}", message);
+            telemetry.stream_events = false;
+        }
+        telemetry.pending_events.clear();
+    }
+}
@@ -0,0 +1,164 @@
+//! Statistics of each game, and the events that make them up, for balancing the game
+//!
+//! When a game stops, a [session record](struct.SessionRecord.html) of it is added to
+//! "space_shooter/sessions.jsonl" in the player's data directory (see [user_data_path](fn.user_data_path.html)):
+//! the shots fired, hits and accuracy, the asteroids destroyed, the lives lost, the time survived,
+//! the scores, and the seed and configuration hash needed to tell the runs apart.
+//!
+//! With `stream_telemetry_events` turned on in the [game configuration](struct.GameConfiguration.html),
+//! every shot, hit, destroyed asteroid, lost or extra life, and new wave is also added,
+//! as it happens, to "space_shooter/events.jsonl", as a [telemetry event](struct.TelemetryEvent.html).
+//!
+//! Both files are in [JSON Lines](https://jsonlines.org) format: one JSON object per line,
+//! added to the end of the file, so the records of many playtest sessions
+//! can be gathered into one file and read back a line at a time.
+//! Replays only repeat a game that was already played, so they aren't recorded.
+
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::Serialize;
+use serde_derive::Serialize;
+
+use crate::components::AsteroidSize;
+use crate::user_files::user_data_path;
+
+/// The name of the file the session records are added to, in the player's data directory
+const SESSIONS_FILE: &str = "sessions.jsonl";
+
+/// The name of the file the telemetry events are added to, in the player's data directory
+const EVENTS_FILE: &str = "events.jsonl";
+
+/// What happened, in a [telemetry event](struct.TelemetryEvent.html)
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum TelemetryEventKind {
+    /// A player fired a laser
+    ShotFired {
+        /// The index of the player who fired
+        player: usize,
+    },
+    /// A player's laser hit an asteroid
+    AsteroidHit {
+        /// The index of the player who fired the laser
+        player: usize,
+        /// The size of the asteroid
+        size: AsteroidSize,
+    },
+    /// A player's laser destroyed an asteroid
+    AsteroidDestroyed {
+        /// The index of the player who fired the laser
+        player: usize,
+        /// The size of the asteroid
+        size: AsteroidSize,
+    },
+    /// A player's ship was hit by an asteroid, costing them a life
+    LifeLost {
+        /// The index of the player whose ship was hit
+        player: usize,
+        /// How many lives the player has left
+        lives: u8,
+    },
+    /// A player was awarded an extra life for their score
+    ExtraLife {
+        /// The index of the player awarded the life
+        player: usize,
+        /// The player's score
+        score: u32,
+    },
+    /// A new wave started
+    WaveStarted {
+        /// The number of the wave (1 being the first)
+        wave: u32,
+    },
+}
+
+/// Something that happened during a game, and when
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+pub struct TelemetryEvent {
+    /// The seed of the game the event happened in, which tells the games apart
+    pub seed: u64,
+    /// The frame the event happened in
+    pub frame: u64,
+    /// How much game time had passed when the event happened (sec)
+    pub time: f32,
+    /// What happened
+    #[serde(flatten)]
+    pub kind: TelemetryEventKind,
+}
+
+/// The statistics of one game
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct SessionRecord {
+    /// When the game stopped, in seconds since the Unix epoch
+    pub finished_at: u64,
+    /// The seed of the game's random number generator
+    pub seed: u64,
+    /// The [configuration hash](fn.configuration_hash.html) of the game, in hexadecimal
+    /// (JSON numbers can't hold every 64-bit value)
+    pub config_hash: String,
+    /// The name of the ship chosen from the roster
+    pub ship: String,
+    /// How many players played
+    pub player_count: usize,
+    /// How much game time passed before the game stopped (sec)
+    pub time_survived: f32,
+    /// How many lasers the players fired
+    pub shots_fired: u32,
+    /// How many of those lasers hit an asteroid
+    pub hits: u32,
+    /// The share of lasers that hit an asteroid (0 to 1; 0 if no lasers were fired)
+    pub accuracy: f32,
+    /// How many asteroids the players destroyed
+    pub asteroids_destroyed: u32,
+    /// How many lives the players lost
+    pub lives_lost: u32,
+    /// The players' scores added together
+    pub score: u32,
+    /// Each player's score, in player order
+    pub scores: Vec<u32>,
+    /// The wave the game reached
+    pub wave: u32,
+}
+
+/// The path of the session records file
+pub fn sessions_path() -> PathBuf {
+    user_data_path(SESSIONS_FILE)
+}
+
+/// The path of the telemetry events file
+pub fn events_path() -> PathBuf {
+    user_data_path(EVENTS_FILE)
+}
+
+/// The number of seconds since the Unix epoch
+pub fn seconds_since_epoch() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default()
+}
+
+/// Adds the given records to the end of a JSON Lines file, one per line,
+/// creating the file (and its directory) if need be
+pub fn append_json_lines<T: Serialize>(path: &Path, records: &[T]) -> Result<(), String> {
+    if let Some(directory) = path.parent() {
+        std::fs::create_dir_all(directory)
+            .map_err(|error| format!("Can't create {}: {}", directory.display(), error))?;
+    }
+    let mut lines = String::new();
+    for record in records {
+        let line = serde_json::to_string(record)
+            .map_err(|error| format!("Can't write a record to {}: {}", path.display(), error))?;
+        lines.push_str(&line);
+        lines.push('\n');
+    }
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .and_then(|mut file| file.write_all(lines.as_bytes()))
+        .map_err(|error| format!("Can't write to {}: {}", path.display(), error))
+}
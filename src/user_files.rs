@@ -0,0 +1,33 @@
+//! Where the game keeps the files that belong to the player
+//!
+//! The files shipped with the game live in "resources",
+//! but anything the player changes is saved in their own
+//! configuration directory (e.g. "~/.config/space_shooter" on Linux),
+//! so it survives reinstalling or updating the game.
+//! What the game records about the player's games (e.g. their high score)
+//! goes in their data directory instead (e.g. "~/.local/share/space_shooter" on Linux).
+
+use std::path::PathBuf;
+
+/// The name of the game's directory within the player's directories
+const GAME_DIRECTORY: &str = "space_shooter";
+
+/// The path of the given file in the player's configuration directory
+///
+/// If the system has no configuration directory, the file goes in the current directory.
+pub fn user_config_path(file_name: &str) -> PathBuf {
+    dirs::config_dir()
+        .map(|directory| directory.join(GAME_DIRECTORY))
+        .unwrap_or_default()
+        .join(file_name)
+}
+
+/// The path of the given file in the player's data directory
+///
+/// If the system has no data directory, the file goes in the current directory.
+pub fn user_data_path(file_name: &str) -> PathBuf {
+    dirs::data_dir()
+        .map(|directory| directory.join(GAME_DIRECTORY))
+        .unwrap_or_default()
+        .join(file_name)
+}